@@ -0,0 +1,276 @@
+use std::{
+	borrow::Cow,
+	io::{self, Read, Write},
+};
+
+use crate::{de::ReadError, parser, Data, Error, FrameReader, ToArgs};
+
+mod manifest;
+
+pub use manifest::*;
+
+/// A command read from an AOF file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Command {
+	/// The offset of this command in the file.
+	pub offset: usize,
+	/// The arguments of this command, starting with its name.
+	pub args: Vec<Vec<u8>>,
+}
+
+impl Command {
+	fn from_data(offset: usize, data: Data<'_>) -> Result<Self, ReadError<'static>> {
+		let args = data
+			.into_array()
+			.ok()
+			.filter(|args| !args.is_empty())
+			.and_then(|args| {
+				args.into_iter()
+					.map(|arg| match arg {
+						Data::BulkString(arg) => Some(arg.into_owned()),
+						_ => None,
+					})
+					.collect::<Option<Vec<_>>>()
+			})
+			.ok_or_else(|| {
+				ReadError::at(
+					Error::Message("expected an array of bulk strings".into()),
+					offset,
+				)
+			})?;
+
+		Ok(Self { offset, args })
+	}
+
+	/// The name of this command, such as `SET`.
+	pub fn name(&self) -> &[u8] {
+		&self.args[0]
+	}
+
+	/// Whether this command is `name`, ignoring case.
+	pub fn is(&self, name: &str) -> bool {
+		self.name().eq_ignore_ascii_case(name.as_bytes())
+	}
+}
+
+/// Commands can be replayed by passing them to a connection.
+impl ToArgs for Command {
+	fn write_args<'a>(&'a self, args: &mut Vec<Cow<'a, [u8]>>) {
+		self.args.write_args(args)
+	}
+}
+
+/// An entry in an AOF file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Entry {
+	/// A single command.
+	Command(Command),
+	/// The commands between a `MULTI` and an `EXEC`, which were applied atomically.
+	Transaction(Vec<Command>),
+}
+
+/// Reader for the commands in an AOF file.
+///
+/// Commands inside `MULTI` and `EXEC` are grouped into an [Entry::Transaction]. A file which
+/// ends part way through a command or a transaction has been truncated, such as when Redis
+/// crashed while writing it, and gives an [incomplete](Error::is_incomplete) error. The offset of
+/// this error is the end of the complete entries, which is where Redis would truncate the file
+/// with `aof-load-truncated`.
+///
+/// Files with an RDB preamble, and RDB base files from a [Manifest], are not AOF and can't be
+/// read.
+#[derive(Debug)]
+pub struct Reader<R> {
+	frames: FrameReader<R>,
+}
+
+impl<R> Reader<R> {
+	/// Read commands from `reader`.
+	pub fn new(reader: R) -> Self {
+		Self {
+			frames: FrameReader::new(reader),
+		}
+	}
+
+	/// Get the inner reader.
+	pub fn into_inner(self) -> R {
+		self.frames.into_inner()
+	}
+}
+
+impl<R> Reader<R>
+where
+	R: Read,
+{
+	fn next_command(&mut self) -> Option<Result<Command, ReadError<'static>>> {
+		Some(match self.frames.next()? {
+			Ok((offset, data)) => Command::from_data(offset, data),
+			Err(err) if err.offset == 0 && err.remaining.starts_with(b"REDIS") => Err(
+				ReadError::at(Error::Message("unexpected RDB preamble".into()), 0),
+			),
+			Err(err) => Err(err),
+		})
+	}
+}
+
+impl<R> Iterator for Reader<R>
+where
+	R: Read,
+{
+	type Item = Result<Entry, ReadError<'static>>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let multi = match self.next_command()? {
+			Ok(cmd) if cmd.is("MULTI") => cmd,
+			cmd => return Some(cmd.map(Entry::Command)),
+		};
+
+		let mut cmds = Vec::new();
+		loop {
+			match self.next_command() {
+				Some(Ok(cmd)) if cmd.is("EXEC") => return Some(Ok(Entry::Transaction(cmds))),
+				Some(Ok(cmd)) => cmds.push(cmd),
+				Some(Err(err)) if !err.data.is_incomplete() => return Some(Err(err)),
+				// the transaction is incomplete, so the file should be truncated before it
+				_ => {
+					let err = Error::Parse(parser::Error::Incomplete(nom::Needed::Unknown));
+					return Some(Err(ReadError::at(err, multi.offset)));
+				}
+			}
+		}
+	}
+}
+
+/// Writer for commands in the AOF format.
+#[derive(Debug)]
+pub struct Writer<W> {
+	writer: W,
+}
+
+impl<W> Writer<W>
+where
+	W: Write,
+{
+	/// Write commands to `writer`, which should be buffered.
+	pub fn new(writer: W) -> Self {
+		Self { writer }
+	}
+
+	/// Write a command.
+	pub fn write_command<C>(&mut self, cmd: &C) -> io::Result<()>
+	where
+		C: ToArgs + ?Sized,
+	{
+		let args = cmd.to_args();
+		write!(self.writer, "*{}\r\n", args.len())?;
+		for arg in args {
+			write!(self.writer, "${}\r\n", arg.len())?;
+			self.writer.write_all(&arg)?;
+			self.writer.write_all(b"\r\n")?;
+		}
+
+		Ok(())
+	}
+
+	/// Write commands inside `MULTI` and `EXEC`.
+	pub fn write_transaction<C>(&mut self, cmds: impl IntoIterator<Item = C>) -> io::Result<()>
+	where
+		C: ToArgs,
+	{
+		self.write_command("MULTI")?;
+		for cmd in cmds {
+			self.write_command(&cmd)?;
+		}
+		self.write_command("EXEC")
+	}
+
+	/// Write an entry read from another file.
+	pub fn write_entry(&mut self, entry: &Entry) -> io::Result<()> {
+		match entry {
+			Entry::Command(cmd) => self.write_command(cmd),
+			Entry::Transaction(cmds) => self.write_transaction(cmds),
+		}
+	}
+
+	/// Flush the inner writer.
+	pub fn flush(&mut self) -> io::Result<()> {
+		self.writer.flush()
+	}
+
+	/// Get the inner writer.
+	pub fn into_inner(self) -> W {
+		self.writer
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::{Command, Entry, Reader, Writer};
+
+	fn cmd(offset: usize, args: &[&str]) -> Command {
+		Command {
+			offset,
+			args: args.iter().map(|arg| arg.as_bytes().to_vec()).collect(),
+		}
+	}
+
+	fn write() -> Vec<u8> {
+		let mut writer = Writer::new(Vec::new());
+		writer.write_command(&["SELECT", "0"]).unwrap();
+		writer
+			.write_transaction([("SET", "foo", 1), ("SET", "bar", 2)])
+			.unwrap();
+		writer.write_command(&("DEL", "foo")).unwrap();
+		writer.into_inner()
+	}
+
+	#[test]
+	fn test_aof() {
+		let aof = write();
+		assert!(aof.starts_with(b"*2\r\n$6\r\nSELECT\r\n$1\r\n0\r\n*1\r\n$5\r\nMULTI\r\n"));
+
+		let entries = Reader::new(&aof[..])
+			.collect::<Result<Vec<_>, _>>()
+			.unwrap();
+		assert_eq!(
+			entries,
+			[
+				Entry::Command(cmd(0, &["SELECT", "0"])),
+				Entry::Transaction(vec![
+					cmd(38, &["SET", "foo", "1"]),
+					cmd(67, &["SET", "bar", "2"])
+				]),
+				Entry::Command(cmd(110, &["DEL", "foo"])),
+			]
+		);
+
+		let mut writer = Writer::new(Vec::new());
+		for entry in &entries {
+			writer.write_entry(entry).unwrap();
+		}
+		assert_eq!(writer.into_inner(), aof);
+	}
+
+	#[test]
+	fn test_aof_truncated() {
+		let aof = write();
+
+		// truncated inside the last command
+		let mut reader = Reader::new(&aof[..aof.len() - 3]);
+		assert!(reader.nth(1).unwrap().is_ok());
+		let err = reader.next().unwrap().unwrap_err();
+		assert!(err.data.is_incomplete());
+		assert_eq!(err.offset, 110);
+
+		// truncated inside the transaction
+		let mut reader = Reader::new(&aof[..80]);
+		assert!(reader.next().unwrap().is_ok());
+		let err = reader.next().unwrap().unwrap_err();
+		assert!(err.data.is_incomplete());
+		assert_eq!(err.offset, 23);
+		assert!(reader.next().is_none());
+
+		let err = Reader::new(&b"REDIS0011"[..]).next().unwrap().unwrap_err();
+		assert!(!err.data.is_incomplete());
+	}
+}
@@ -0,0 +1,273 @@
+use std::{
+	fmt::{self, Display, Formatter},
+	str::FromStr,
+};
+
+use crate::Error;
+
+/// The type of a file in a [Manifest].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FileType {
+	/// The base file, which is either an RDB file or an AOF file from a rewrite.
+	Base,
+	/// A file from before the last rewrite, which is waiting to be deleted.
+	History,
+	/// An incremental AOF file, which is applied after the base file.
+	Incr,
+}
+
+impl FileType {
+	fn as_str(self) -> &'static str {
+		match self {
+			Self::Base => "b",
+			Self::History => "h",
+			Self::Incr => "i",
+		}
+	}
+}
+
+/// A file listed in a [Manifest].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ManifestFile {
+	/// The name of the file, relative to the append directory.
+	pub name: String,
+	/// The sequence number of the file.
+	pub seq: u64,
+	/// The type of the file.
+	pub file_type: FileType,
+}
+
+impl ManifestFile {
+	/// Whether this file is in the RDB format rather than AOF, judging by its name.
+	pub fn is_rdb(&self) -> bool {
+		self.name.ends_with(".rdb")
+	}
+}
+
+/// The manifest of a multi-part AOF, as written by Redis 7 to `appendonly.aof.manifest`.
+///
+/// Manifests are parsed like Redis does: each line lists a file as `key value` pairs, blank
+/// lines and lines starting with `#` are ignored, and so are unknown keys.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Manifest {
+	/// The files in the order they are listed.
+	pub files: Vec<ManifestFile>,
+}
+
+impl Manifest {
+	/// The base file.
+	pub fn base(&self) -> Option<&ManifestFile> {
+		self.files
+			.iter()
+			.find(|file| file.file_type == FileType::Base)
+	}
+
+	/// The incremental files, ordered by sequence number.
+	pub fn incrs(&self) -> Vec<&ManifestFile> {
+		let mut incrs = self
+			.files
+			.iter()
+			.filter(|file| file.file_type == FileType::Incr)
+			.collect::<Vec<_>>();
+		incrs.sort_by_key(|file| file.seq);
+		incrs
+	}
+
+	/// The files to replay the AOF, in order: the base file followed by the incremental files.
+	pub fn replay(&self) -> Vec<&ManifestFile> {
+		self.base().into_iter().chain(self.incrs()).collect()
+	}
+}
+
+fn invalid(line: usize, msg: &str) -> Error<'static> {
+	Error::Message(format!("invalid manifest on line {}: {}", line + 1, msg).into())
+}
+
+/// Split a line into arguments like `sdssplitargs`, which Redis uses to read manifests.
+fn split_args(line: &str) -> Option<Vec<String>> {
+	let mut args = Vec::new();
+	let mut chars = line.chars().peekable();
+
+	loop {
+		while chars.next_if(|c| c.is_ascii_whitespace()).is_some() {}
+		let Some(first) = chars.next() else {
+			return Some(args);
+		};
+
+		let mut arg = String::new();
+		match first {
+			'"' => loop {
+				match chars.next()? {
+					'"' => break,
+					'\\' => arg.push(match chars.next()? {
+						'n' => '\n',
+						'r' => '\r',
+						't' => '\t',
+						'b' => '\u{8}',
+						'a' => '\u{7}',
+						'x' => {
+							let hex = [chars.next()?, chars.next()?].iter().collect::<String>();
+							u8::from_str_radix(&hex, 16).ok()? as char
+						}
+						c => c,
+					}),
+					c => arg.push(c),
+				}
+			},
+			'\'' => loop {
+				match chars.next()? {
+					'\'' => break,
+					'\\' if chars.peek() == Some(&'\'') => arg.extend(chars.next()),
+					c => arg.push(c),
+				}
+			},
+			c => {
+				arg.push(c);
+				while let Some(c) = chars.next_if(|c| !c.is_ascii_whitespace()) {
+					arg.push(c);
+				}
+			}
+		}
+
+		// a closing quote must be followed by a space
+		if matches!(first, '"' | '\'') && chars.peek().is_some_and(|c| !c.is_ascii_whitespace()) {
+			return None;
+		}
+
+		args.push(arg);
+	}
+}
+
+impl FromStr for Manifest {
+	type Err = Error<'static>;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let mut files = Vec::new();
+
+		for (i, line) in s.lines().enumerate() {
+			let line = line.trim();
+			if line.is_empty() || line.starts_with('#') {
+				continue;
+			}
+
+			let args = split_args(line).ok_or_else(|| invalid(i, "unbalanced quotes"))?;
+			if args.len() % 2 != 0 {
+				return Err(invalid(i, "expected key value pairs"));
+			}
+
+			let (mut name, mut seq, mut file_type) = (None, None, None);
+			for pair in args.chunks_exact(2) {
+				match pair[0].as_str() {
+					"file" => name = Some(pair[1].clone()),
+					"seq" => {
+						let n = pair[1].parse().map_err(|_| invalid(i, "invalid seq"))?;
+						seq = Some(n);
+					}
+					"type" => {
+						file_type = Some(match pair[1].as_str() {
+							"b" => FileType::Base,
+							"h" => FileType::History,
+							"i" => FileType::Incr,
+							_ => return Err(invalid(i, "invalid type")),
+						})
+					}
+					_ => {}
+				}
+			}
+
+			files.push(ManifestFile {
+				name: name.ok_or_else(|| invalid(i, "missing file"))?,
+				seq: seq.ok_or_else(|| invalid(i, "missing seq"))?,
+				file_type: file_type.ok_or_else(|| invalid(i, "missing type"))?,
+			});
+		}
+
+		if files
+			.iter()
+			.filter(|file| file.file_type == FileType::Base)
+			.count() > 1
+		{
+			return Err(invalid(files.len() - 1, "more than one base file"));
+		}
+
+		Ok(Self { files })
+	}
+}
+
+/// Write `name`, quoting it like `sdscatrepr` if it contains spaces or special characters.
+fn write_name(f: &mut Formatter<'_>, name: &str) -> fmt::Result {
+	if !name.is_empty()
+		&& name
+			.chars()
+			.all(|c| c.is_ascii_graphic() && c != '"' && c != '\'' && c != '\\')
+	{
+		return f.write_str(name);
+	}
+
+	f.write_str("\"")?;
+	for c in name.chars() {
+		match c {
+			'\\' => f.write_str("\\\\")?,
+			'"' => f.write_str("\\\"")?,
+			'\n' => f.write_str("\\n")?,
+			'\r' => f.write_str("\\r")?,
+			'\t' => f.write_str("\\t")?,
+			'\u{7}' => f.write_str("\\a")?,
+			'\u{8}' => f.write_str("\\b")?,
+			c if c.is_ascii_control() => write!(f, "\\x{:02x}", c as u8)?,
+			c => write!(f, "{}", c)?,
+		}
+	}
+	f.write_str("\"")
+}
+
+/// Formats the manifest as Redis writes it, so it can be saved alongside the files.
+impl Display for Manifest {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		for file in &self.files {
+			f.write_str("file ")?;
+			write_name(f, &file.name)?;
+			writeln!(f, " seq {} type {}", file.seq, file.file_type.as_str())?;
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::{FileType, Manifest};
+
+	#[test]
+	fn test_manifest() {
+		let input = "# comment\nfile appendonly.aof.1.base.rdb seq 1 type b\n\
+			file appendonly.aof.2.incr.aof seq 2 type i startoffset 0\n\
+			file appendonly.aof.1.incr.aof seq 1 type h\n\
+			file \"append only.aof.3.incr.aof\" seq 3 type i\n";
+		let manifest = input.parse::<Manifest>().unwrap();
+
+		assert_eq!(manifest.files.len(), 4);
+		assert_eq!(manifest.files[2].file_type, FileType::History);
+		assert!(manifest.base().unwrap().is_rdb());
+		assert_eq!(
+			manifest
+				.replay()
+				.iter()
+				.map(|file| file.name.as_str())
+				.collect::<Vec<_>>(),
+			[
+				"appendonly.aof.1.base.rdb",
+				"appendonly.aof.2.incr.aof",
+				"append only.aof.3.incr.aof"
+			]
+		);
+
+		let output = manifest.to_string();
+		assert!(output.ends_with("file \"append only.aof.3.incr.aof\" seq 3 type i\n"));
+		assert_eq!(output.parse::<Manifest>().unwrap(), manifest);
+
+		assert!("file foo seq 1".parse::<Manifest>().is_err());
+		assert!("file \"foo seq 1 type b".parse::<Manifest>().is_err());
+		assert!("file foo seq x type b".parse::<Manifest>().is_err());
+	}
+}
@@ -1,60 +1,79 @@
 use crate::{
-	de::ReadError,
-	from_bytes,
-	nom::{Err, Needed},
-	to_bytes, Data, Error,
+	parser::{parse_blob_err, parse_err},
+	to_bytes, Data, Error, RedisError,
 };
-use bytes::{Buf, BufMut, BytesMut};
+use bytes::{BufMut, BytesMut};
 use tokio_util::codec::{Decoder, Encoder};
 
+#[cfg(feature = "futures-io")]
+mod compat;
+mod event;
+mod frame;
+mod options;
+mod scanner;
+mod server;
+mod stream;
+
+#[cfg(feature = "futures-io")]
+pub use compat::*;
+pub use event::*;
+pub use frame::*;
+pub use options::*;
+pub use server::*;
+pub use stream::*;
+
+use scanner::Scanner;
+
 /// Tokio codec with [`Encoder`] and [`Decoder`] for RESP.
 ///
-/// This codec has a Result as its Item in order to represent transient errors.
-#[derive(Debug)]
-pub struct Codec;
+/// This codec has a Result as its Item in order to represent transient errors. Decoded values are
+/// [Frame]s, which share the read buffer instead of copying it.
+///
+/// The codec keeps track of how much of a value it has already read, so values which arrive over
+/// many reads are only parsed once.
+#[derive(Debug, Default)]
+pub struct Codec {
+	pub options: CodecOptions,
+	scanner: Scanner,
+}
+
+impl Codec {
+	/// Make a new codec.
+	pub fn new(options: CodecOptions) -> Self {
+		Self {
+			options,
+			scanner: Scanner::default(),
+		}
+	}
+}
 
 impl Decoder for Codec {
-	type Item = Result<Data<'static>, Error<'static>>;
+	type Item = Result<Frame, Error<'static>>;
 
 	type Error = Error<'static>;
 
 	fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-		let start_len = src.len();
-		if start_len == 0 {
+		if src.is_empty() {
 			return Ok(None);
 		}
 
-		match from_bytes::<Data>(src) {
-			Ok((data, rem)) => {
-				let owned = data.into_owned();
+		let bytes = match self.scanner.split(src, &self.options)? {
+			Some(bytes) => bytes,
+			None => return Ok(None),
+		};
 
-				let end_len = rem.len();
-				src.advance(start_len - end_len);
+		// errors inside aggregates are part of the frame
+		if !self.scanner.is_error() {
+			return Ok(Some(Ok(Frame::new(bytes))));
+		}
 
-				Ok(Some(Ok(owned)))
-			}
-			Err(ReadError { data, remaining }) => {
-				let end_len = remaining.len();
-
-				let result = match data {
-					Error::Parse(Err::Incomplete(needed)) => {
-						if let Needed::Size(size) = needed {
-							src.reserve(size.into());
-						}
-
-						Ok(None)
-					}
-					other if other.is_transient() => Ok(Some(Err(other.into_owned()))),
-					other => Err(other.into_owned()),
-				};
-
-				if !matches!(result, Ok(None)) {
-					src.advance(start_len - end_len);
-				}
-
-				result
-			}
+		let (_, msg) = match bytes[0] {
+			b'-' => parse_err(&bytes),
+			_ => parse_blob_err(&bytes),
 		}
+		.map_err(|e| Error::from(e).into_owned())?;
+
+		Ok(Some(Err(RedisError::new(msg.to_owned()).into())))
 	}
 }
 
@@ -67,6 +86,15 @@ impl<'a> Encoder<Data<'a>> for Codec {
 	}
 }
 
+impl Encoder<Frame> for Codec {
+	type Error = Error<'static>;
+
+	fn encode(&mut self, item: Frame, dst: &mut BytesMut) -> Result<(), Self::Error> {
+		dst.extend_from_slice(item.as_bytes());
+		Ok(())
+	}
+}
+
 #[cfg(test)]
 mod test {
 	use std::{io, time::Duration};
@@ -76,19 +104,19 @@ mod test {
 	use tokio_stream::wrappers::UnboundedReceiverStream;
 	use tokio_util::{codec::FramedRead, io::StreamReader};
 
-	use crate::{Data, Error};
+	use crate::{array, from_data, Data, Error, Limit};
 
-	use super::Codec;
+	use super::{Codec, CodecOptions};
 
 	#[tokio::test]
 	async fn test_decoder() {
 		let bytes = b"+OK\r\n-ERR\r\n";
-		let mut stream = FramedRead::new(bytes.as_slice(), Codec);
+		let mut stream = FramedRead::new(bytes.as_slice(), Codec::default());
 
 		let first = dbg!(stream.next().await);
 		assert!(matches!(
 			first,
-			Some(Ok(Ok(Data::SimpleString(v)))) if v == "OK"
+			Some(Ok(Ok(frame))) if frame.data().unwrap() == "OK"
 		));
 
 		let second = dbg!(stream.next().await);
@@ -98,11 +126,51 @@ mod test {
 		assert!(third.is_none());
 	}
 
+	#[tokio::test]
+	async fn test_decoder_borrowed() -> Result<(), crate::Error<'static>> {
+		let bytes = b"*2\r\n$3\r\nfoo\r\n+bar\r\n";
+		let mut stream = FramedRead::new(bytes.as_slice(), Codec::default());
+
+		let frame = stream.try_next().await?.unwrap()?;
+		let (foo, bar) = from_data::<(&[u8], &str)>(frame.data().unwrap()).unwrap();
+		assert_eq!(foo, b"foo");
+		assert_eq!(bar, "bar");
+
+		let (foo, _) = frame.deserialize::<(&[u8], &str)>().unwrap();
+		let foo = frame.slice_ref(foo);
+		assert_eq!(foo, &b"foo"[..]);
+		assert_eq!(foo.as_ptr(), frame.as_bytes()[8..].as_ptr());
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_resp3_decoder() {
+		let bytes = b"%1\r\n+foo\r\n,1.5\r\n!3\r\nERR\r\n>1\r\n#t\r\n";
+		let mut stream = FramedRead::new(bytes.as_slice(), Codec::default());
+
+		let first = stream.next().await;
+		assert!(matches!(
+			first,
+			Some(Ok(Ok(frame))) if frame.data().unwrap() == Data::Map(vec![(Data::simple_string("foo"), Data::Double(1.5))])
+		));
+
+		let second = stream.next().await;
+		assert!(matches!(second, Some(Ok(Err(Error::Redis(v)))) if v == "ERR"));
+
+		let third = stream.next().await;
+		assert!(
+			matches!(third, Some(Ok(Ok(frame))) if frame.data().unwrap() == Data::Push(vec![Data::Boolean(true)]))
+		);
+
+		assert!(stream.next().await.is_none());
+	}
+
 	#[tokio::test]
 	async fn test_chunked_decoder() -> Result<(), crate::Error<'static>> {
 		let (tx, rx) = mpsc::unbounded_channel::<Result<&'static [u8], io::Error>>();
 		let rd = StreamReader::new(UnboundedReceiverStream::new(rx));
-		let mut stream = FramedRead::new(rd, Codec);
+		let mut stream = FramedRead::new(rd, Codec::default());
 
 		spawn(async move {
 			let send = |b: &'static [u8]| async {
@@ -118,7 +186,7 @@ mod test {
 		});
 
 		for _ in 0..4 {
-			let data = stream.try_next().await?.unwrap()?;
+			let data = stream.try_next().await?.unwrap()?.into_data()?;
 			assert!(matches!(data, Data::SimpleString(v) if v == "OK"));
 		}
 
@@ -126,11 +194,36 @@ mod test {
 		Ok(())
 	}
 
+	#[tokio::test]
+	async fn test_chunked_streamed_decoder() -> Result<(), crate::Error<'static>> {
+		let (tx, rx) = mpsc::unbounded_channel::<Result<&'static [u8], io::Error>>();
+		let rd = StreamReader::new(UnboundedReceiverStream::new(rx));
+		let mut stream = FramedRead::new(rd, Codec::default());
+
+		spawn(async move {
+			let send = |b: &'static [u8]| async {
+				tx.send(Ok(b)).unwrap();
+				sleep(Duration::from_millis(10)).await;
+			};
+
+			send(b"*?\r\n$?\r\n;2").await;
+			send(b"\r\nOK\r\n;2\r\nOK\r").await;
+			send(b"\n;0\r\n:1\r\n").await;
+			send(b".\r\n").await;
+		});
+
+		let data = stream.try_next().await?.unwrap()?;
+		assert_eq!(data, array!(Data::bulk_string("OKOK"), 1));
+
+		assert!(stream.try_next().await?.is_none());
+		Ok(())
+	}
+
 	#[tokio::test]
 	async fn test_chunked_array_decoder() -> Result<(), crate::Error<'static>> {
 		let (tx, rx) = mpsc::unbounded_channel::<Result<&'static [u8], io::Error>>();
 		let rd = StreamReader::new(UnboundedReceiverStream::new(rx));
-		let mut stream = FramedRead::new(rd, Codec);
+		let mut stream = FramedRead::new(rd, Codec::default());
 
 		spawn(async move {
 			let send = |b: &'static [u8]| async {
@@ -146,12 +239,12 @@ mod test {
 			send(b":42\r\n").await;
 		});
 
-		let data = dbg!(stream.try_next().await?.unwrap()?);
+		let data = dbg!(stream.try_next().await?.unwrap()?.into_data()?);
 		match data {
 			Data::Array(arr) if arr.len() == 3 => {
 				assert!(matches!(&arr[0], Data::SimpleString(v) if v == "OK"));
 				assert!(matches!(&arr[1], Data::Integer(-999)));
-				assert!(matches!(&arr[2], Data::BulkString(v) if &*v == &&b"OK"[..]));
+				assert!(matches!(&arr[2], Data::BulkString(v) if v == &&b"OK"[..]));
 			}
 			_ => panic!("{:?}", data),
 		}
@@ -162,4 +255,36 @@ mod test {
 		assert!(stream.try_next().await?.is_none());
 		Ok(())
 	}
+
+	#[tokio::test]
+	async fn test_nested_error_decoder() {
+		let bytes = b"*2\r\n-ERR\r\n+OK\r\n+next\r\n";
+		let mut stream = FramedRead::new(bytes.as_slice(), Codec::default());
+
+		let first = stream.next().await;
+		assert!(matches!(
+			first,
+			Some(Ok(Ok(frame))) if frame.data().unwrap() == array!(Data::Error("ERR".into()), "OK")
+		));
+
+		let second = stream.next().await;
+		assert!(matches!(second, Some(Ok(Ok(frame))) if frame.data().unwrap() == "next"));
+	}
+
+	#[tokio::test]
+	async fn test_limit_decoder() {
+		let options = CodecOptions {
+			max_bulk_len: 1024,
+			..Default::default()
+		};
+
+		let bytes = b"$4000000000\r\n";
+		let mut stream = FramedRead::new(bytes.as_slice(), Codec::new(options));
+
+		let first = stream.next().await;
+		assert!(matches!(
+			first,
+			Some(Err(Error::Limit(Limit::BulkLen(1024))))
+		));
+	}
 }
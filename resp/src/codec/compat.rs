@@ -0,0 +1,40 @@
+use futures_io::{AsyncRead, AsyncWrite};
+use tokio_util::{
+	codec::Framed,
+	compat::{Compat, FuturesAsyncReadCompatExt},
+};
+
+use super::Codec;
+
+/// A [Framed] stream and sink over a transport which implements the [futures_io] traits, such as
+/// a socket from smol or async-std. The framing doesn't depend on the Tokio runtime.
+pub type FramedIo<T, C = Codec> = Framed<Compat<T>, C>;
+
+/// Frame `io` with `codec`, which can be any of the codecs in this crate.
+pub fn framed_io<T, C>(io: T, codec: C) -> FramedIo<T, C>
+where
+	T: AsyncRead + AsyncWrite,
+{
+	Framed::new(io.compat(), codec)
+}
+
+#[cfg(test)]
+mod test {
+	use futures::{io::Cursor, SinkExt, StreamExt};
+
+	use crate::{Codec, Data};
+
+	use super::framed_io;
+
+	#[tokio::test]
+	async fn test_framed_io() {
+		let mut framed = framed_io(Cursor::new(b"+OK\r\n".to_vec()), Codec::default());
+
+		let frame = framed.next().await.unwrap().unwrap().unwrap();
+		assert_eq!(frame.data().unwrap(), "OK");
+
+		framed.send(Data::from_args(&["PING"])).await.unwrap();
+		let written = framed.into_inner().into_inner().into_inner();
+		assert_eq!(written, b"+OK\r\n*1\r\n$4\r\nPING\r\n");
+	}
+}
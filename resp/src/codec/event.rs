@@ -0,0 +1,387 @@
+use std::io::Write;
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use nom::{Err, IResult, Needed};
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::{
+	parser::{
+		parse_array, parse_attribute, parse_chunk, parse_end, parse_map, parse_push, parse_set,
+		parse_streamed_header,
+	},
+	to_bytes, Data, Error, Limit,
+};
+
+use super::{
+	scanner::{check_len, complete, header_len, Remaining},
+	Codec, CodecOptions,
+};
+
+/// The kinds of RESP aggregates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AggregateKind {
+	Array,
+	Map,
+	Set,
+	Push,
+	Attribute,
+}
+
+impl AggregateKind {
+	fn prefix(&self) -> u8 {
+		match self {
+			Self::Array => b'*',
+			Self::Map => b'%',
+			Self::Set => b'~',
+			Self::Push => b'>',
+			Self::Attribute => b'|',
+		}
+	}
+
+	fn from_prefix(prefix: u8) -> Option<Self> {
+		Some(match prefix {
+			b'*' => Self::Array,
+			b'%' => Self::Map,
+			b'~' => Self::Set,
+			b'>' => Self::Push,
+			b'|' => Self::Attribute,
+			_ => return None,
+		})
+	}
+}
+
+/// A piece of RESP, as read by [EventCodec].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+	/// A complete value which is not an aggregate.
+	Data(Data<'static>),
+	/// The start of an aggregate. It's followed by `len` values, or twice that for maps and
+	/// attributes. If `len` is `None`, the aggregate is streamed and its values are followed by
+	/// [Event::End].
+	Aggregate {
+		kind: AggregateKind,
+		len: Option<usize>,
+	},
+	/// The start of a streamed string, which is followed by [Event::Chunk]s.
+	StreamedString,
+	/// A chunk of a streamed string. The string ends with an empty chunk.
+	Chunk(Bytes),
+	/// The end of a streamed aggregate.
+	End,
+}
+
+/// Tokio codec with [`Encoder`] and [`Decoder`] for RESP [Event]s.
+///
+/// Unlike [Codec], aggregates and streamed strings are not collected into a single value. Each
+/// chunk of a streamed string is passed on as soon as it's read, without copying, so proxies can
+/// forward large strings without buffering them.
+///
+/// Chunks and aggregates are checked against the [CodecOptions], but as values aren't collected,
+/// [max_frame_len](CodecOptions::max_frame_len) only applies to values which aren't aggregates.
+#[derive(Debug, Default)]
+pub struct EventCodec {
+	options: CodecOptions,
+	/// Reads values which aren't aggregates.
+	codec: Codec,
+	/// What remains of the aggregates and streamed strings which the codec is inside of.
+	stack: Vec<Remaining>,
+}
+
+impl EventCodec {
+	/// Make a new codec.
+	pub fn new(options: CodecOptions) -> Self {
+		Self {
+			options,
+			codec: Codec::new(options),
+			stack: Vec::new(),
+		}
+	}
+
+	/// Check `event` against the limits and enter or leave the aggregates it starts or ends.
+	fn track(&mut self, event: &Event) -> Result<(), Error<'static>> {
+		let remaining = match event {
+			Event::Aggregate {
+				kind,
+				len: Some(len),
+			} => {
+				if *len > self.options.max_aggregate_len {
+					return Err(Limit::AggregateLen(self.options.max_aggregate_len).into());
+				}
+
+				let len = *len as u64;
+				let elements = match kind {
+					AggregateKind::Map => len * 2,
+					// attributes are followed by the value they describe
+					AggregateKind::Attribute => len * 2 + 1,
+					_ => len,
+				};
+
+				(elements > 0).then_some(Remaining::Elements(elements))
+			}
+			Event::Aggregate { kind, len: None } => Some(Remaining::Streamed {
+				elements: 0,
+				per_entry: if *kind == AggregateKind::Map { 2 } else { 1 },
+			}),
+			Event::StreamedString => Some(Remaining::Chunks),
+			Event::Chunk(chunk) if !chunk.is_empty() => return Ok(()),
+			Event::Chunk(_) | Event::End => {
+				self.stack.pop();
+				None
+			}
+			Event::Data(_) => None,
+		};
+
+		match remaining {
+			Some(_) if self.stack.len() >= self.options.max_depth => {
+				Err(Limit::Depth(self.options.max_depth).into())
+			}
+			Some(remaining) => {
+				self.stack.push(remaining);
+				Ok(())
+			}
+			None => {
+				complete(&mut self.stack);
+				Ok(())
+			}
+		}
+	}
+
+	fn decode_header(src: &mut BytesMut) -> IResult<&[u8], Event> {
+		let prefix = src[0];
+
+		if src.get(1) == Some(&b'?') {
+			let (rem, prefix) = parse_streamed_header(src)?;
+			let event = match AggregateKind::from_prefix(prefix) {
+				Some(kind) => Event::Aggregate { kind, len: None },
+				None => Event::StreamedString,
+			};
+
+			return Ok((rem, event));
+		}
+
+		let (rem, len) = match prefix {
+			b'%' => parse_map(src)?,
+			b'~' => parse_set(src)?,
+			b'>' => parse_push(src)?,
+			b'|' => parse_attribute(src)?,
+			_ => parse_array(src)?,
+		};
+
+		let event = match (AggregateKind::from_prefix(prefix), len.try_into()) {
+			(Some(kind), Ok(len)) => Event::Aggregate {
+				kind,
+				len: Some(len),
+			},
+			_ => Event::Data(Data::Null),
+		};
+
+		Ok((rem, event))
+	}
+}
+
+impl Decoder for EventCodec {
+	type Item = Result<Event, Error<'static>>;
+
+	type Error = Error<'static>;
+
+	fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+		if let Some(Remaining::Streamed {
+			elements,
+			per_entry,
+		}) = self.stack.last()
+		{
+			let max = self.options.max_aggregate_len;
+			if src.first() != Some(&b'.') && *elements >= (max as u64).saturating_mul(*per_entry) {
+				return Err(Limit::AggregateLen(max).into());
+			}
+		}
+
+		let start_len = src.len();
+		let result = match (src.first(), src.get(1)) {
+			(None, _) => return Ok(None),
+			(Some(b'*' | b'%' | b'~' | b'>' | b'|'), _) | (Some(b'$'), Some(b'?')) => {
+				Self::decode_header(src).map(|(rem, event)| (rem.len(), event))
+			}
+			(Some(b';'), _) => {
+				// the length is checked before space is reserved for the chunk
+				if let Ok((_, len)) = header_len(src) {
+					check_len(len, self.options.max_bulk_len, Limit::BulkLen)?;
+				}
+
+				match parse_chunk(src) {
+					Ok((rem, chunk)) => {
+						let offset = chunk.as_ptr() as usize - src.as_ptr() as usize;
+						let len = chunk.len();
+						let rem_len = rem.len();
+
+						src.advance(offset);
+						let chunk = Event::Chunk(src.split_to(len).freeze());
+						src.advance(src.len() - rem_len);
+
+						self.track(&chunk)?;
+						return Ok(Some(Ok(chunk)));
+					}
+					Err(e) => Err(e),
+				}
+			}
+			(Some(b'.'), _) => parse_end(src).map(|(rem, _)| (rem.len(), Event::End)),
+			_ => {
+				let item = match self.codec.decode(src)? {
+					Some(Ok(frame)) => Ok(Event::Data(frame.into_data()?)),
+					Some(Err(err)) => Err(err),
+					None => return Ok(None),
+				};
+
+				complete(&mut self.stack);
+				return Ok(Some(item));
+			}
+		};
+
+		match result {
+			Ok((rem_len, event)) => {
+				self.track(&event)?;
+				src.advance(start_len - rem_len);
+				Ok(Some(Ok(event)))
+			}
+			Err(Err::Incomplete(needed)) => {
+				if let Needed::Size(size) = needed {
+					src.reserve(size.into());
+				}
+
+				Ok(None)
+			}
+			Err(e) => Err(Error::from(e).into_owned()),
+		}
+	}
+}
+
+impl Encoder<Event> for EventCodec {
+	type Error = Error<'static>;
+
+	fn encode(&mut self, item: Event, dst: &mut BytesMut) -> Result<(), Self::Error> {
+		let mut writer = dst.writer();
+
+		match item {
+			Event::Data(data) => to_bytes(&data, writer).map_err(|e| e.into_owned())?,
+			Event::Aggregate { kind, len } => {
+				writer.write_all(&[kind.prefix()])?;
+				match len {
+					Some(len) => write!(writer, "{}\r\n", len)?,
+					None => writer.write_all(b"?\r\n")?,
+				}
+			}
+			Event::StreamedString => writer.write_all(b"$?\r\n")?,
+			Event::Chunk(chunk) => {
+				write!(writer, ";{}\r\n", chunk.len())?;
+				if !chunk.is_empty() {
+					writer.write_all(&chunk)?;
+					writer.write_all(b"\r\n")?;
+				}
+			}
+			Event::End => writer.write_all(b".\r\n")?,
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use bytes::{Bytes, BytesMut};
+	use futures::StreamExt;
+	use tokio_util::codec::{Decoder, Encoder, FramedRead};
+
+	use crate::{codec::CodecOptions, Data, Error, Limit};
+
+	use super::{AggregateKind, Event, EventCodec};
+
+	fn events() -> Vec<Event> {
+		vec![
+			Event::Aggregate {
+				kind: AggregateKind::Array,
+				len: Some(2),
+			},
+			Event::StreamedString,
+			Event::Chunk(Bytes::from_static(b"foo")),
+			Event::Chunk(Bytes::new()),
+			Event::Aggregate {
+				kind: AggregateKind::Map,
+				len: None,
+			},
+			Event::Data(Data::simple_string("a")),
+			Event::Data(Data::Integer(1)),
+			Event::End,
+			Event::Data(Data::Null),
+		]
+	}
+
+	const BYTES: &[u8] = b"*2\r\n$?\r\n;3\r\nfoo\r\n;0\r\n%?\r\n+a\r\n:1\r\n.\r\n$-1\r\n";
+
+	#[tokio::test]
+	async fn test_decoder() {
+		let stream = FramedRead::new(BYTES, EventCodec::default());
+		let res = stream
+			.map(|event| event.unwrap().unwrap())
+			.collect::<Vec<_>>()
+			.await;
+
+		assert_eq!(res, events());
+	}
+
+	#[tokio::test]
+	async fn test_decoder_error() {
+		let mut stream = FramedRead::new(&b"-ERR\r\n"[..], EventCodec::default());
+
+		let first = stream.next().await;
+		assert!(matches!(first, Some(Ok(Err(Error::Redis(v)))) if v == "ERR"));
+	}
+
+	#[test]
+	fn test_decoder_limits() {
+		let options = CodecOptions {
+			max_bulk_len: 1024,
+			max_aggregate_len: 2,
+			max_depth: 2,
+			..Default::default()
+		};
+
+		let decode = |bytes: &[u8]| {
+			let mut codec = EventCodec::new(options);
+			let mut src = BytesMut::from(bytes);
+			loop {
+				match codec.decode(&mut src) {
+					Ok(Some(_)) => {}
+					Ok(None) => return (None, src.capacity()),
+					Err(Error::Limit(limit)) => return (Some(limit), src.capacity()),
+					Err(err) => panic!("{err}"),
+				}
+			}
+		};
+
+		// hostile lengths are rejected before any space is reserved for them
+		let (limit, capacity) = decode(b"$?\r\n;4000000000\r\n");
+		assert_eq!(limit, Some(Limit::BulkLen(1024)));
+		assert!(capacity < 1024);
+
+		assert_eq!(decode(b"$?\r\n;3\r\nfo").0, None);
+		assert_eq!(decode(b"*9999999999\r\n").0, Some(Limit::AggregateLen(2)));
+		assert_eq!(
+			decode(b"~?\r\n:1\r\n:2\r\n:3\r\n").0,
+			Some(Limit::AggregateLen(2))
+		);
+		assert_eq!(decode(b"*1\r\n*1\r\n*1\r\n").0, Some(Limit::Depth(2)));
+		assert_eq!(decode(b"*1\r\n*0\r\n*1\r\n*1\r\n").0, None);
+		assert_eq!(decode(b"$4000000000\r\n").0, Some(Limit::BulkLen(1024)));
+	}
+
+	#[test]
+	fn test_encoder() {
+		let mut dst = BytesMut::new();
+		let mut codec = EventCodec::default();
+		for event in events() {
+			codec.encode(event, &mut dst).unwrap();
+		}
+
+		assert_eq!(dst, BYTES);
+	}
+}
@@ -0,0 +1,79 @@
+use std::fmt::{self, Debug, Formatter};
+
+use bytes::Bytes;
+use serde::Deserialize;
+
+use crate::{from_bytes, Data, Error};
+
+/// A complete RESP value, as read by [Codec](super::Codec).
+///
+/// The frame owns the bytes it was read from, so reading it doesn't require copying. [Frame::data]
+/// and [Frame::deserialize] borrow strings from the frame, and [Frame::slice_ref] turns them
+/// into [Bytes] which share the frame's buffer.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Frame {
+	bytes: Bytes,
+}
+
+impl Frame {
+	/// Make a frame from bytes which are known to contain exactly one valid RESP value.
+	pub(crate) fn new(bytes: Bytes) -> Self {
+		Self { bytes }
+	}
+
+	/// The raw RESP bytes of this frame.
+	pub fn as_bytes(&self) -> &Bytes {
+		&self.bytes
+	}
+
+	/// The data in this frame, borrowing strings from the frame. Fails if the frame isn't valid
+	/// RESP, which can't happen for frames read by [Codec](super::Codec).
+	pub fn data(&self) -> Result<Data<'_>, Error<'_>> {
+		self.deserialize()
+	}
+
+	/// Convert this frame into owned data. This copies every string in the frame.
+	pub fn into_data(self) -> Result<Data<'static>, Error<'static>> {
+		self.data().map(Data::into_owned).map_err(Error::into_owned)
+	}
+
+	/// Deserialize this frame into `T` without going through [Data].
+	pub fn deserialize<'de, T>(&'de self) -> Result<T, Error<'de>>
+	where
+		T: Deserialize<'de>,
+	{
+		from_bytes(&self.bytes)
+			.map(|(res, _)| res)
+			.map_err(|e| e.data)
+	}
+
+	/// Get a [Bytes] slice of this frame which is equivalent to `subset`, without copying.
+	///
+	/// `subset` must be borrowed from this frame, such as a bulk string in [Frame::data].
+	pub fn slice_ref(&self, subset: &[u8]) -> Bytes {
+		self.bytes.slice_ref(subset)
+	}
+}
+
+impl Debug for Frame {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self.data() {
+			Ok(data) => f.debug_tuple("Frame").field(&data).finish(),
+			Err(_) => f.debug_tuple("Frame").field(&self.bytes).finish(),
+		}
+	}
+}
+
+impl TryFrom<Frame> for Data<'static> {
+	type Error = Error<'static>;
+
+	fn try_from(frame: Frame) -> Result<Self, Error<'static>> {
+		frame.into_data()
+	}
+}
+
+impl PartialEq<Data<'_>> for Frame {
+	fn eq(&self, other: &Data<'_>) -> bool {
+		self.data().is_ok_and(|data| data == *other)
+	}
+}
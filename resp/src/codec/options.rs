@@ -0,0 +1,30 @@
+/// Limits for values read by [Codec](super::Codec).
+///
+/// Values which exceed a limit cause a [Limit](crate::Limit) error. The defaults are generous
+/// enough for any reply from a Redis server with default settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CodecOptions {
+	/// The maximum length of a bulk string, verbatim string, blob error or streamed string chunk.
+	pub max_bulk_len: usize,
+	/// The maximum number of elements in an aggregate. Each entry in a map counts as one element.
+	pub max_aggregate_len: usize,
+	/// The maximum number of aggregates which can be nested inside each other.
+	pub max_depth: usize,
+	/// The maximum length of a value, including all of its elements.
+	pub max_frame_len: usize,
+	/// The maximum length of an inline command read by [ServerCodec](super::ServerCodec).
+	pub max_inline_len: usize,
+}
+
+impl Default for CodecOptions {
+	fn default() -> Self {
+		Self {
+			max_bulk_len: 512 * 1024 * 1024,
+			max_aggregate_len: u32::MAX as usize,
+			max_depth: 128,
+			max_frame_len: 1024 * 1024 * 1024,
+			// the same as Redis
+			max_inline_len: 64 * 1024,
+		}
+	}
+}
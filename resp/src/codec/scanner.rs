@@ -0,0 +1,395 @@
+use bytes::{Bytes, BytesMut};
+use nom::{
+	character::streaming::{crlf, i64, one_of},
+	error::ErrorKind,
+	sequence::{preceded, terminated},
+	Err, IResult, Needed,
+};
+
+use crate::{
+	parser::{
+		parse_big_number, parse_blob_err, parse_bool, parse_bytes, parse_chunk, parse_double,
+		parse_end, parse_err, parse_int, parse_null, parse_str, parse_streamed_header,
+		parse_verbatim,
+	},
+	Error, Limit,
+};
+
+use super::CodecOptions;
+
+/// What remains of an aggregate which the [Scanner] is inside of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Remaining {
+	/// A number of elements.
+	Elements(u64),
+	/// Elements of a streamed aggregate, until an end marker.
+	Streamed { elements: u64, per_entry: u64 },
+	/// Chunks of a streamed string, until an empty chunk.
+	Chunks,
+}
+
+/// Finds the end of a RESP value which may arrive over several reads.
+///
+/// The scanner remembers how far it got, including a stack of the aggregates it is inside of, so
+/// each call to [Scanner::scan] only parses bytes which haven't been scanned yet.
+#[derive(Debug, Default)]
+pub(crate) struct Scanner {
+	offset: usize,
+	stack: Vec<Remaining>,
+	/// Whether the value being scanned is an error, not counting errors inside aggregates.
+	error: bool,
+}
+
+impl Scanner {
+	/// Scan the value at the start of `src`, returning its length once it is complete.
+	///
+	/// `src` must start with the same bytes as the last call, until a length or an error is
+	/// returned.
+	pub(crate) fn scan<'a>(
+		&mut self,
+		src: &'a [u8],
+		options: &CodecOptions,
+	) -> Result<usize, Error<'a>> {
+		let result = match self.scan_inner(src, options) {
+			Ok(len) if len > options.max_frame_len => {
+				Err(Limit::FrameLen(options.max_frame_len).into())
+			}
+			Err(Error::Parse(Err::Incomplete(needed))) => {
+				let needed = match needed {
+					Needed::Size(size) => size.get(),
+					Needed::Unknown => 1,
+				};
+
+				if src.len().saturating_add(needed) > options.max_frame_len {
+					Err(Limit::FrameLen(options.max_frame_len).into())
+				} else {
+					Err(Error::Parse(Err::Incomplete(Needed::new(needed))))
+				}
+			}
+			result => result,
+		};
+
+		if !matches!(result, Err(Error::Parse(Err::Incomplete(_)))) {
+			self.offset = 0;
+			self.stack.clear();
+		}
+
+		result
+	}
+
+	/// Split the value at the start of `src` from it, once it is complete. If it isn't, space is
+	/// reserved in `src` for the rest of it.
+	pub(crate) fn split(
+		&mut self,
+		src: &mut BytesMut,
+		options: &CodecOptions,
+	) -> Result<Option<Bytes>, Error<'static>> {
+		match self.scan(src, options) {
+			Ok(len) => Ok(Some(src.split_to(len).freeze())),
+			Err(Error::Parse(Err::Incomplete(needed))) => {
+				if let Needed::Size(size) = needed {
+					src.reserve(size.into());
+				}
+
+				Ok(None)
+			}
+			Err(e) => Err(e.into_owned()),
+		}
+	}
+
+	/// Whether the last value scanned is a simple or blob error at the top level.
+	pub(crate) fn is_error(&self) -> bool {
+		self.error
+	}
+
+	fn scan_inner<'a>(
+		&mut self,
+		src: &'a [u8],
+		options: &CodecOptions,
+	) -> Result<usize, Error<'a>> {
+		loop {
+			let data = &src[self.offset..];
+			let (rem, complete) = match self.stack.last() {
+				Some(Remaining::Chunks) => {
+					check_len(header_len(data)?.1, options.max_bulk_len, Limit::BulkLen)?;
+					let (rem, chunk) = parse_chunk(data)?;
+					(rem, chunk.is_empty() && self.close())
+				}
+				Some(Remaining::Streamed { .. }) if data.first() == Some(&b'.') => {
+					let (rem, _) = parse_end(data)?;
+					(rem, self.close())
+				}
+				Some(Remaining::Streamed {
+					elements,
+					per_entry,
+				}) if *elements
+					>= (options.max_aggregate_len as u64).saturating_mul(*per_entry) =>
+				{
+					return Err(Limit::AggregateLen(options.max_aggregate_len).into())
+				}
+				_ => self.element(data, options)?,
+			};
+
+			self.offset = src.len() - rem.len();
+			if complete {
+				return Ok(self.offset);
+			}
+		}
+	}
+
+	/// Scan a single element, returning whether it completed the value.
+	fn element<'a>(
+		&mut self,
+		data: &'a [u8],
+		options: &CodecOptions,
+	) -> Result<(&'a [u8], bool), Error<'a>> {
+		if self.stack.is_empty() {
+			self.error = matches!(data.first(), Some(b'-' | b'!'));
+		}
+
+		let (rem, remaining) = match (data.first(), data.get(1)) {
+			(None, _) => return Err(Error::Parse(Err::Incomplete(Needed::new(1)))),
+			(Some(b'$' | b'*' | b'%' | b'~' | b'>'), Some(b'?')) => {
+				let (rem, prefix) = parse_streamed_header(data)?;
+				let remaining = match prefix {
+					b'$' => Remaining::Chunks,
+					b'%' => Remaining::Streamed {
+						elements: 0,
+						per_entry: 2,
+					},
+					_ => Remaining::Streamed {
+						elements: 0,
+						per_entry: 1,
+					},
+				};
+
+				(rem, Some(remaining))
+			}
+			(Some(prefix @ (b'*' | b'%' | b'~' | b'>' | b'|')), _) => {
+				let (rem, len) = header_len(data)?;
+				let len = check_len(len, options.max_aggregate_len, Limit::AggregateLen)?;
+				let elements = match prefix {
+					b'%' => len * 2,
+					// attributes are followed by the value they describe
+					b'|' => len * 2 + 1,
+					_ => len,
+				};
+
+				(rem, (elements > 0).then_some(Remaining::Elements(elements)))
+			}
+			(Some(prefix @ (b'$' | b'=' | b'!')), _) => {
+				check_len(header_len(data)?.1, options.max_bulk_len, Limit::BulkLen)?;
+				let rem = match prefix {
+					b'$' => parse_bytes(data)?.0,
+					b'=' => parse_verbatim(data)?.0,
+					_ => parse_blob_err(data)?.0,
+				};
+
+				(rem, None)
+			}
+			(Some(b'+'), _) => (parse_str(data)?.0, None),
+			(Some(b'-'), _) => (parse_err(data)?.0, None),
+			(Some(b':'), _) => (parse_int(data)?.0, None),
+			(Some(b'_'), _) => (parse_null(data)?.0, None),
+			(Some(b'#'), _) => (parse_bool(data)?.0, None),
+			(Some(b','), _) => (parse_double(data)?.0, None),
+			(Some(b'('), _) => (parse_big_number(data)?.0, None),
+			(Some(_), _) => {
+				return Err(Err::Error(nom::error::Error::new(data, ErrorKind::Char)).into())
+			}
+		};
+
+		match remaining {
+			Some(_) if self.stack.len() >= options.max_depth => {
+				Err(Limit::Depth(options.max_depth).into())
+			}
+			Some(remaining) => {
+				self.stack.push(remaining);
+				Ok((rem, false))
+			}
+			None => Ok((rem, self.complete())),
+		}
+	}
+
+	/// Leave the innermost aggregate, returning whether it completed the value.
+	fn close(&mut self) -> bool {
+		self.stack.pop();
+		self.complete()
+	}
+
+	/// Count a complete element, returning whether it completed the value.
+	fn complete(&mut self) -> bool {
+		complete(&mut self.stack)
+	}
+}
+
+/// Count a complete element in the innermost aggregate of `stack`, leaving any aggregates which it
+/// completes. Returns whether the value is complete.
+pub(crate) fn complete(stack: &mut Vec<Remaining>) -> bool {
+	loop {
+		match stack.last_mut() {
+			None => return true,
+			Some(Remaining::Elements(1)) => {
+				stack.pop();
+			}
+			Some(Remaining::Elements(n)) => {
+				*n -= 1;
+				return false;
+			}
+			Some(Remaining::Streamed { elements, .. }) => {
+				*elements += 1;
+				return false;
+			}
+			Some(Remaining::Chunks) => return false,
+		}
+	}
+}
+
+/// Parse the length in the header of a blob, aggregate or chunk.
+pub(crate) fn header_len(data: &[u8]) -> IResult<&[u8], i64> {
+	preceded(one_of("$=!*%~>|;"), terminated(i64, crlf))(data)
+}
+
+/// Check a length from a header against its limit. Negative lengths are treated as empty.
+pub(crate) fn check_len(
+	len: i64,
+	max: usize,
+	limit: fn(usize) -> Limit,
+) -> Result<u64, Error<'static>> {
+	let len = u64::try_from(len).unwrap_or_default();
+	if len > max as u64 {
+		Err(limit(max).into())
+	} else {
+		Ok(len)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use nom::Err;
+
+	use crate::{codec::CodecOptions, Error, Limit};
+
+	use super::Scanner;
+
+	#[test]
+	fn test_scan_resumes() {
+		let bytes = b"*3\r\n+?\r\n%1\r\n+foo\r\n|1\r\n+a\r\n:1\r\n$?\r\n;2\r\nba\r\n;0\r\n*?\r\n:1\r\n.\r\n+next\r\n";
+		let len = bytes.len() - b"+next\r\n".len();
+		let options = CodecOptions::default();
+
+		let mut scanner = Scanner::default();
+		for end in 0..len {
+			assert!(matches!(
+				scanner.scan(&bytes[..end], &options),
+				Err(Error::Parse(Err::Incomplete(_)))
+			));
+		}
+
+		assert_eq!(scanner.scan(bytes, &options).unwrap(), len);
+		assert_eq!(
+			scanner.scan(&bytes[len..], &options).unwrap(),
+			bytes.len() - len
+		);
+	}
+
+	#[test]
+	fn test_scan_is_linear() {
+		let len = 10_000;
+		let mut bytes = format!("*{}\r\n", len).into_bytes();
+		for _ in 0..len {
+			bytes.extend_from_slice(b"$3\r\nfoo\r\n");
+		}
+
+		let options = CodecOptions::default();
+		let mut scanner = Scanner::default();
+		let mut scanned = 0;
+
+		for end in (256..bytes.len()).step_by(256) {
+			scanned += end - scanner.offset;
+			assert!(matches!(
+				scanner.scan(&bytes[..end], &options),
+				Err(Error::Parse(Err::Incomplete(_)))
+			));
+		}
+
+		scanned += bytes.len() - scanner.offset;
+		assert_eq!(scanner.scan(&bytes, &options).unwrap(), bytes.len());
+
+		// only the incomplete element at the end of each read is scanned again
+		let reads = bytes.len() / 256 + 1;
+		assert!(
+			scanned <= bytes.len() + reads * 9,
+			"scanned {scanned} bytes"
+		);
+	}
+
+	#[test]
+	fn test_scan_empty_aggregates() {
+		let options = CodecOptions::default();
+		let mut scanner = Scanner::default();
+		assert_eq!(scanner.scan(b"*0\r\n", &options).unwrap(), 4);
+		assert_eq!(scanner.scan(b"*-1\r\n", &options).unwrap(), 5);
+		assert_eq!(scanner.scan(b"%0\r\n", &options).unwrap(), 4);
+		assert_eq!(scanner.scan(b"|0\r\n:1\r\n", &options).unwrap(), 8);
+	}
+
+	#[test]
+	fn test_scan_invalid() {
+		let options = CodecOptions::default();
+		let mut scanner = Scanner::default();
+		assert!(matches!(
+			scanner.scan(b"*1\r\nfoo\r\n", &options),
+			Err(Error::Parse(Err::Error(_)))
+		));
+		assert_eq!(scanner.scan(b"+OK\r\n", &options).unwrap(), 5);
+	}
+
+	#[test]
+	fn test_scan_errors() {
+		let options = CodecOptions::default();
+		let mut scanner = Scanner::default();
+
+		scanner.scan(b"-ERR\r\n", &options).unwrap();
+		assert!(scanner.is_error());
+		scanner.scan(b"*1\r\n-ERR\r\n", &options).unwrap();
+		assert!(!scanner.is_error());
+		scanner.scan(b"!3\r\nERR\r\n", &options).unwrap();
+		assert!(scanner.is_error());
+		scanner.scan(b"+OK\r\n", &options).unwrap();
+		assert!(!scanner.is_error());
+	}
+
+	#[test]
+	fn test_scan_limits() {
+		let options = CodecOptions {
+			max_bulk_len: 3,
+			max_aggregate_len: 2,
+			max_depth: 2,
+			max_frame_len: 32,
+			max_inline_len: 32,
+		};
+
+		let scan = |bytes: &[u8]| match Scanner::default().scan(bytes, &options) {
+			Err(Error::Limit(limit)) => Some(limit),
+			_ => None,
+		};
+
+		assert_eq!(scan(b"$3\r\nfoo\r\n"), None);
+		assert_eq!(scan(b"$4000000000\r\n"), Some(Limit::BulkLen(3)));
+		assert_eq!(scan(b"$?\r\n;4\r\n"), Some(Limit::BulkLen(3)));
+		assert_eq!(scan(b"*9999999999\r\n"), Some(Limit::AggregateLen(2)));
+		assert_eq!(scan(b"%3\r\n"), Some(Limit::AggregateLen(2)));
+		assert_eq!(
+			scan(b"~?\r\n:1\r\n:2\r\n:3\r\n"),
+			Some(Limit::AggregateLen(2))
+		);
+		assert_eq!(scan(b"%?\r\n:1\r\n:2\r\n:3\r\n"), None);
+		assert_eq!(scan(b"*1\r\n*1\r\n*1\r\n"), Some(Limit::Depth(2)));
+		assert_eq!(
+			scan(b"+aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"),
+			Some(Limit::FrameLen(32))
+		);
+		assert_eq!(scan(b"$3\r\nfo"), None);
+	}
+}
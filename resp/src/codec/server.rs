@@ -0,0 +1,212 @@
+use bytes::{BufMut, Bytes, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::{from_bytes, to_bytes, Data, Error, Limit};
+
+use super::{scanner::Scanner, CodecOptions, Frame};
+
+/// Tokio codec for the server side of RESP, with [`Encoder`] and [`Decoder`].
+///
+/// Requests are decoded into their arguments, which share the read buffer instead of copying it.
+/// Both multibulk requests (`*1\r\n$4\r\nPING\r\n`) and inline commands (`PING\r\n`) are
+/// supported. Replies are encoded from [Data], including [Data::Error]s, or a [Frame] read from
+/// another server. Errors containing line breaks are written as RESP3 blob errors
+/// (`!<len>\r\n...`), since simple errors can't contain them.
+///
+/// Inline commands are limited to [CodecOptions::max_inline_len] bytes and are split on
+/// whitespace, without handling quotes.
+#[derive(Debug, Default)]
+pub struct ServerCodec {
+	pub options: CodecOptions,
+	scanner: Scanner,
+}
+
+impl ServerCodec {
+	/// Make a new server codec.
+	pub fn new(options: CodecOptions) -> Self {
+		Self {
+			options,
+			scanner: Scanner::default(),
+		}
+	}
+
+	fn decode_multibulk(
+		&mut self,
+		src: &mut BytesMut,
+	) -> Result<Option<Vec<Bytes>>, Error<'static>> {
+		let bytes = match self.scanner.split(src, &self.options)? {
+			Some(bytes) => bytes,
+			None => return Ok(None),
+		};
+
+		let (args, _) = from_bytes::<Vec<&[u8]>>(&bytes).map_err(|e| e.data.into_owned())?;
+		Ok(Some(
+			args.into_iter().map(|arg| bytes.slice_ref(arg)).collect(),
+		))
+	}
+
+	fn decode_inline(&mut self, src: &mut BytesMut) -> Result<Option<Vec<Bytes>>, Error<'static>> {
+		let max = self.options.max_inline_len;
+		let len = match src.iter().take(max + 1).position(|b| *b == b'\n') {
+			Some(pos) => pos + 1,
+			None if src.len() <= max => return Ok(None),
+			None => return Err(Limit::InlineLen(max).into()),
+		};
+
+		let line = src.split_to(len).freeze();
+		let args = line
+			.split(|b| b.is_ascii_whitespace())
+			.filter(|arg| !arg.is_empty())
+			.map(|arg| line.slice_ref(arg))
+			.collect();
+
+		Ok(Some(args))
+	}
+}
+
+impl Decoder for ServerCodec {
+	type Item = Vec<Bytes>;
+
+	type Error = Error<'static>;
+
+	fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+		loop {
+			let args = match src.first() {
+				None => return Ok(None),
+				Some(b'*') => self.decode_multibulk(src)?,
+				Some(_) => self.decode_inline(src)?,
+			};
+
+			// empty inline commands are ignored
+			match args {
+				Some(args) if args.is_empty() => continue,
+				args => return Ok(args),
+			}
+		}
+	}
+}
+
+impl<'a> Encoder<Data<'a>> for ServerCodec {
+	type Error = Error<'static>;
+
+	fn encode(&mut self, item: Data<'a>, dst: &mut BytesMut) -> Result<(), Self::Error> {
+		to_bytes(&item, dst.writer()).map_err(|e| e.into_owned())?;
+		Ok(())
+	}
+}
+
+impl Encoder<Frame> for ServerCodec {
+	type Error = Error<'static>;
+
+	fn encode(&mut self, item: Frame, dst: &mut BytesMut) -> Result<(), Self::Error> {
+		dst.extend_from_slice(item.as_bytes());
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::{io, time::Duration};
+
+	use bytes::{Bytes, BytesMut};
+	use futures::TryStreamExt;
+	use tokio::{spawn, sync::mpsc, time::sleep};
+	use tokio_stream::wrappers::UnboundedReceiverStream;
+	use tokio_util::{
+		codec::{Encoder, FramedRead},
+		io::StreamReader,
+	};
+
+	use crate::{
+		codec::{Codec, CodecOptions},
+		Data, Error, Limit,
+	};
+
+	use super::ServerCodec;
+
+	#[tokio::test]
+	async fn test_server_decoder() -> Result<(), Error<'static>> {
+		let (tx, rx) = mpsc::unbounded_channel::<Result<&'static [u8], io::Error>>();
+		let rd = StreamReader::new(UnboundedReceiverStream::new(rx));
+		let mut stream = FramedRead::new(rd, ServerCodec::default());
+
+		spawn(async move {
+			let send = |b: &'static [u8]| async {
+				tx.send(Ok(b)).unwrap();
+				sleep(Duration::from_millis(10)).await;
+			};
+
+			send(b"*2\r\n$4\r\nECHO\r\n$").await;
+			send(b"3\r\nfoo\r\nPING\r\n\r\nSET  a").await;
+			send(b" b\r\n").await;
+		});
+
+		let expected: [&[&str]; 3] = [&["ECHO", "foo"], &["PING"], &["SET", "a", "b"]];
+		for args in expected {
+			let actual = stream.try_next().await?.unwrap();
+			assert_eq!(
+				actual,
+				args.iter().map(|a| Bytes::from(*a)).collect::<Vec<_>>()
+			);
+		}
+
+		assert!(stream.try_next().await?.is_none());
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_server_decoder_invalid() {
+		let mut stream = FramedRead::new(&b"*1\r\n*0\r\n"[..], ServerCodec::default());
+		assert!(stream.try_next().await.is_err());
+	}
+
+	#[tokio::test]
+	async fn test_server_decoder_inline_limit() {
+		let options = CodecOptions {
+			max_inline_len: 8,
+			..Default::default()
+		};
+
+		let mut stream = FramedRead::new(&b"PING\r\nECHO foo\r\n"[..], ServerCodec::new(options));
+		assert_eq!(stream.try_next().await.unwrap().unwrap(), ["PING"]);
+		assert!(matches!(
+			stream.try_next().await,
+			Err(Error::Limit(Limit::InlineLen(8)))
+		));
+
+		let mut stream = FramedRead::new(&b"PINGPINGPING"[..], ServerCodec::new(options));
+		assert!(matches!(
+			stream.try_next().await,
+			Err(Error::Limit(Limit::InlineLen(8)))
+		));
+	}
+
+	#[tokio::test]
+	async fn test_server_encoder() -> Result<(), Error<'static>> {
+		let mut dst = BytesMut::new();
+		let mut codec = ServerCodec::default();
+
+		codec.encode(Data::simple_string("OK"), &mut dst)?;
+		codec.encode(Data::Error("ERR bad\r\nthing".into()), &mut dst)?;
+		codec.encode(
+			Data::Array(vec![Data::Integer(1), Data::Error("ERR".into())]),
+			&mut dst,
+		)?;
+
+		assert_eq!(
+			dst,
+			&b"+OK\r\n!14\r\nERR bad\r\nthing\r\n*2\r\n:1\r\n-ERR\r\n"[..]
+		);
+
+		let mut stream = FramedRead::new(&dst[..], Codec::default());
+		assert_eq!(
+			stream.try_next().await?.unwrap()?,
+			Data::simple_string("OK")
+		);
+		assert!(
+			matches!(stream.try_next().await?, Some(Err(Error::Redis(v))) if v == "ERR bad\r\nthing")
+		);
+
+		Ok(())
+	}
+}
@@ -0,0 +1,193 @@
+use std::io;
+
+use bytes::{Buf, Bytes, BytesMut};
+use nom::Err;
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::{Data, Error};
+
+use super::{scanner::header_len, Codec, Frame};
+
+/// A value read by [StreamCodec].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Streamed {
+	/// A complete value.
+	Frame(Frame),
+	/// The start of a bulk string which is longer than the threshold. It's followed by
+	/// [Streamed::Chunk]s with `len` bytes in total.
+	Bulk { len: usize },
+	/// A chunk of a streamed bulk string. The string ends with an empty chunk.
+	Chunk(Bytes),
+}
+
+/// Tokio codec which reads like [Codec], except that bulk strings longer than a threshold are
+/// passed on in chunks as they're read instead of being buffered, so large values can be piped
+/// elsewhere with bounded memory.
+///
+/// Only bulk strings at the top level are streamed; bulk strings inside aggregates are part of
+/// their [Frame]. Streamed strings aren't subject to the [limits](super::CodecOptions) of the
+/// codec, since they're never buffered.
+#[derive(Debug, Default)]
+pub struct StreamCodec {
+	/// The codec used for values which aren't streamed.
+	pub codec: Codec,
+	/// Bulk strings longer than this are streamed. If `None`, nothing is streamed.
+	pub threshold: Option<usize>,
+	/// The number of bytes left in the string being streamed, if any.
+	remaining: Option<usize>,
+}
+
+impl StreamCodec {
+	/// Make a new codec which streams bulk strings longer than `threshold`.
+	pub fn new(codec: Codec, threshold: Option<usize>) -> Self {
+		Self {
+			codec,
+			threshold,
+			remaining: None,
+		}
+	}
+
+	/// Whether the codec is part way through a streamed string.
+	pub fn is_streaming(&self) -> bool {
+		self.remaining.is_some()
+	}
+
+	fn decode_chunk(
+		&mut self,
+		src: &mut BytesMut,
+		remaining: usize,
+	) -> Result<Option<Streamed>, Error<'static>> {
+		if remaining > 0 {
+			if src.is_empty() {
+				return Ok(None);
+			}
+
+			let len = remaining.min(src.len());
+			self.remaining = Some(remaining - len);
+			return Ok(Some(Streamed::Chunk(src.split_to(len).freeze())));
+		}
+
+		if src.len() < 2 {
+			return Ok(None);
+		}
+
+		self.remaining = None;
+		if src.split_to(2) != b"\r\n"[..] {
+			return Err(Error::Message("expected CRLF after bulk string".into()));
+		}
+
+		Ok(Some(Streamed::Chunk(Bytes::new())))
+	}
+}
+
+impl Decoder for StreamCodec {
+	type Item = Result<Streamed, Error<'static>>;
+
+	type Error = Error<'static>;
+
+	fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+		if let Some(remaining) = self.remaining {
+			return Ok(self.decode_chunk(src, remaining)?.map(Ok));
+		}
+
+		if let (Some(threshold), Some(b'$')) = (self.threshold, src.first()) {
+			match header_len(src) {
+				Ok((rem, len)) => {
+					if let Some(len) = usize::try_from(len).ok().filter(|len| *len > threshold) {
+						src.advance(src.len() - rem.len());
+						self.remaining = Some(len);
+						return Ok(Some(Ok(Streamed::Bulk { len })));
+					}
+				}
+				Err(Err::Incomplete(_)) => return Ok(None),
+				// invalid headers are reported by the codec
+				Err(_) => {}
+			}
+		}
+
+		Ok(self
+			.codec
+			.decode(src)?
+			.map(|frame| frame.map(Streamed::Frame)))
+	}
+
+	fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+		match self.decode(src)? {
+			Some(item) => Ok(Some(item)),
+			None if self.is_streaming() => Err(io::Error::new(
+				io::ErrorKind::UnexpectedEof,
+				"stream closed in a bulk string",
+			)
+			.into()),
+			None if !src.is_empty() => Err(io::Error::other("bytes remaining on stream").into()),
+			None => Ok(None),
+		}
+	}
+}
+
+impl<'a> Encoder<Data<'a>> for StreamCodec {
+	type Error = Error<'static>;
+
+	fn encode(&mut self, item: Data<'a>, dst: &mut BytesMut) -> Result<(), Self::Error> {
+		self.codec.encode(item, dst)
+	}
+}
+
+impl Encoder<Frame> for StreamCodec {
+	type Error = Error<'static>;
+
+	fn encode(&mut self, item: Frame, dst: &mut BytesMut) -> Result<(), Self::Error> {
+		self.codec.encode(item, dst)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use bytes::{Bytes, BytesMut};
+	use tokio_util::codec::Decoder;
+
+	use crate::{Codec, Data};
+
+	use super::{StreamCodec, Streamed};
+
+	#[test]
+	fn test_stream_codec() {
+		let mut codec = StreamCodec::new(Codec::default(), Some(4));
+		let mut src = BytesMut::from(&b"$10\r\n0123"[..]);
+		let mut next = |src: &mut BytesMut| codec.decode(src).unwrap().map(Result::unwrap);
+
+		assert_eq!(next(&mut src), Some(Streamed::Bulk { len: 10 }));
+		assert_eq!(
+			next(&mut src),
+			Some(Streamed::Chunk(Bytes::from_static(b"0123")))
+		);
+		assert_eq!(next(&mut src), None);
+
+		src.extend_from_slice(b"456789\r");
+		assert_eq!(
+			next(&mut src),
+			Some(Streamed::Chunk(Bytes::from_static(b"456789")))
+		);
+		assert_eq!(next(&mut src), None);
+
+		src.extend_from_slice(b"\n$3\r\nfoo\r\n");
+		assert_eq!(next(&mut src), Some(Streamed::Chunk(Bytes::new())));
+		assert!(matches!(
+			next(&mut src),
+			Some(Streamed::Frame(frame)) if frame == Data::BulkString(b"foo"[..].into())
+		));
+		assert_eq!(next(&mut src), None);
+		assert!(src.is_empty());
+	}
+
+	#[test]
+	fn test_stream_codec_eof() {
+		let mut codec = StreamCodec::new(Codec::default(), Some(0));
+		let mut src = BytesMut::from(&b"$3\r\nfo"[..]);
+
+		codec.decode(&mut src).unwrap();
+		codec.decode(&mut src).unwrap();
+		assert!(codec.is_streaming());
+		assert!(codec.decode_eof(&mut src).is_err());
+	}
+}
@@ -0,0 +1,9 @@
+mod from_data;
+#[cfg(feature = "json")]
+mod json;
+mod to_args;
+
+pub use from_data::*;
+#[cfg(feature = "json")]
+pub use json::*;
+pub use to_args::*;
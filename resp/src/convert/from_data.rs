@@ -0,0 +1,505 @@
+use alloc::{
+	borrow::Cow,
+	collections::BTreeMap,
+	string::{String, ToString},
+	vec,
+	vec::Vec,
+};
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::{
+	collections::{HashMap, HashSet},
+	hash::{BuildHasher, Hash},
+};
+
+use bytes::Bytes;
+use serde::de::{self, Unexpected};
+
+use crate::{float::Float, Data, Error, RedisError};
+
+/// Conversion from [Data] without going through serde.
+///
+/// Conversions follow Redis's loose typing: integers, floats and booleans can be read from
+/// strings, and strings can be read from numbers. A null can only be converted into an [Option]
+/// or `()`, so a nil reply can be told apart from an empty one. Errors inside aggregates can be
+/// kept with `Result<T, RedisError>`.
+pub trait FromData: Sized {
+	/// Convert `data` into this type.
+	fn from_data(data: Data<'_>) -> Result<Self, Error<'static>>;
+
+	/// Convert a string into a [Vec] of this type, which is only possible for bytes.
+	#[doc(hidden)]
+	fn from_bytes_vec(_bytes: Cow<'_, [u8]>) -> Option<Vec<Self>> {
+		None
+	}
+}
+
+/// Remove any attributes from `data`, which aren't relevant to conversions.
+fn strip(data: Data<'_>) -> Data<'_> {
+	match data {
+		Data::Attribute { data, .. } => strip(*data),
+		data => data,
+	}
+}
+
+fn unexpected<'a>(data: &'a Data<'_>) -> Unexpected<'a> {
+	match data {
+		Data::SimpleString(str) | Data::BigNumber(str) => Unexpected::Str(str),
+		Data::BulkString(bytes) | Data::VerbatimString { text: bytes, .. } => {
+			Unexpected::Bytes(bytes)
+		}
+		Data::Integer(i) => Unexpected::Signed(*i),
+		Data::Double(d) => Unexpected::Float(*d),
+		Data::Boolean(b) => Unexpected::Bool(*b),
+		Data::Null => Unexpected::Option,
+		Data::Array(_) | Data::Set(_) | Data::Push(_) => Unexpected::Seq,
+		Data::Map(_) => Unexpected::Map,
+		Data::Attribute { .. } => Unexpected::Other("attribute"),
+		Data::Error(_) => Unexpected::Other("error"),
+	}
+}
+
+/// Fail to convert `data` into the `expected` type. Errors are returned as they are.
+fn invalid<T>(data: Data<'_>, expected: &str) -> Result<T, Error<'static>> {
+	match data {
+		Data::Error(msg) => Err(RedisError::new(msg).into_owned().into()),
+		data => Err(de::Error::invalid_type(unexpected(&data), &expected)),
+	}
+}
+
+/// Get the bytes of a string type or number, returning `data` if it has none.
+fn into_bytes(data: Data<'_>) -> Result<Cow<'_, [u8]>, Data<'_>> {
+	match data {
+		Data::BulkString(bytes) | Data::VerbatimString { text: bytes, .. } => Ok(bytes),
+		Data::SimpleString(Cow::Borrowed(str)) | Data::BigNumber(Cow::Borrowed(str)) => {
+			Ok(Cow::Borrowed(str.as_bytes()))
+		}
+		Data::SimpleString(Cow::Owned(str)) | Data::BigNumber(Cow::Owned(str)) => {
+			Ok(Cow::Owned(str.into_bytes()))
+		}
+		Data::Integer(i) => Ok(Cow::Owned(i.to_string().into_bytes())),
+		Data::Double(d) => Ok(Cow::Owned(Float::new(d, None).to_string().into_bytes())),
+		data => Err(data),
+	}
+}
+
+/// Get the elements of an aggregate, returning `data` if it isn't one.
+fn into_elements(data: Data<'_>) -> Result<Vec<Data<'_>>, Data<'_>> {
+	match data {
+		Data::Array(elements) | Data::Set(elements) | Data::Push(elements) => Ok(elements),
+		data => Err(data),
+	}
+}
+
+/// Get the entries of a map, or of an aggregate of alternating keys and values.
+fn into_pairs(data: Data<'_>) -> Result<Vec<(Data<'_>, Data<'_>)>, Error<'static>> {
+	match into_elements(strip(data)) {
+		Ok(elements) if elements.len() % 2 == 0 => {
+			let mut elements = elements.into_iter();
+			Ok(core::iter::from_fn(|| Some((elements.next()?, elements.next()?))).collect())
+		}
+		Ok(elements) => Err(de::Error::invalid_length(elements.len(), &"an even length")),
+		Err(Data::Map(pairs)) => Ok(pairs),
+		Err(data) => invalid(data, "a map"),
+	}
+}
+
+fn parse_int<T>(data: Data<'_>, expected: &str) -> Result<T, Error<'static>>
+where
+	T: TryFrom<i64> + FromStr,
+{
+	match data.to_int() {
+		Some(int) => Ok(int),
+		None => invalid(strip(data), expected),
+	}
+}
+
+macro_rules! impl_int {
+	($($ty:ty)*) => {
+		$(
+			impl FromData for $ty {
+				fn from_data(data: Data<'_>) -> Result<Self, Error<'static>> {
+					parse_int(data, stringify!($ty))
+				}
+			}
+		)*
+	};
+}
+
+impl_int!(i8 i16 i32 i64 i128 isize u16 u32 u64 u128 usize);
+
+impl FromData for u8 {
+	fn from_data(data: Data<'_>) -> Result<Self, Error<'static>> {
+		parse_int(data, "u8")
+	}
+
+	fn from_bytes_vec(bytes: Cow<'_, [u8]>) -> Option<Vec<Self>> {
+		Some(bytes.into_owned())
+	}
+}
+
+macro_rules! impl_float {
+	($($ty:ty)*) => {
+		$(
+			impl FromData for $ty {
+				fn from_data(data: Data<'_>) -> Result<Self, Error<'static>> {
+					match data.as_f64() {
+						Some(float) => Ok(float as $ty),
+						None => invalid(strip(data), stringify!($ty)),
+					}
+				}
+			}
+		)*
+	};
+}
+
+impl_float!(f32 f64);
+
+impl FromData for bool {
+	fn from_data(data: Data<'_>) -> Result<Self, Error<'static>> {
+		let data = strip(data);
+		let bool = match &data {
+			Data::Boolean(b) => Some(*b),
+			Data::Integer(i) => Some(*i != 0),
+			// status replies such as `OK`
+			Data::SimpleString(str) if str == "OK" => Some(true),
+			data => match data.as_str() {
+				Some("1" | "true") => Some(true),
+				Some("0" | "false") => Some(false),
+				_ => None,
+			},
+		};
+
+		match bool {
+			Some(bool) => Ok(bool),
+			None => invalid(data, "bool"),
+		}
+	}
+}
+
+impl FromData for String {
+	fn from_data(data: Data<'_>) -> Result<Self, Error<'static>> {
+		match into_bytes(strip(data)) {
+			Ok(bytes) => String::from_utf8(bytes.into_owned()).map_err(de::Error::custom),
+			Err(data) => invalid(data, "a string"),
+		}
+	}
+}
+
+impl FromData for Bytes {
+	fn from_data(data: Data<'_>) -> Result<Self, Error<'static>> {
+		match into_bytes(strip(data)) {
+			Ok(bytes) => Ok(bytes.into_owned().into()),
+			Err(data) => invalid(data, "bytes"),
+		}
+	}
+}
+
+impl FromData for () {
+	fn from_data(data: Data<'_>) -> Result<Self, Error<'static>> {
+		match strip(data) {
+			data @ Data::Error(_) => invalid(data, "any data"),
+			_ => Ok(()),
+		}
+	}
+}
+
+impl FromData for Data<'static> {
+	fn from_data(data: Data<'_>) -> Result<Self, Error<'static>> {
+		Ok(data.into_owned())
+	}
+}
+
+impl<T> FromData for Option<T>
+where
+	T: FromData,
+{
+	fn from_data(data: Data<'_>) -> Result<Self, Error<'static>> {
+		match strip(data) {
+			Data::Null => Ok(None),
+			data => T::from_data(data).map(Some),
+		}
+	}
+}
+
+impl<T> FromData for Result<T, RedisError<'static>>
+where
+	T: FromData,
+{
+	fn from_data(data: Data<'_>) -> Result<Self, Error<'static>> {
+		match strip(data) {
+			Data::Error(msg) => Ok(Err(RedisError::new(msg).into_owned())),
+			data => T::from_data(data).map(Ok),
+		}
+	}
+}
+
+impl<T> FromData for Vec<T>
+where
+	T: FromData,
+{
+	fn from_data(data: Data<'_>) -> Result<Self, Error<'static>> {
+		match into_elements(strip(data)) {
+			Ok(elements) => elements.into_iter().map(T::from_data).collect(),
+			Err(Data::Map(pairs)) => pairs
+				.into_iter()
+				.map(|(k, v)| T::from_data(Data::Array(vec![k, v])))
+				.collect(),
+			Err(data) => match into_bytes(data) {
+				Ok(bytes) => match T::from_bytes_vec(bytes) {
+					Some(vec) => Ok(vec),
+					None => Err(de::Error::invalid_type(
+						Unexpected::Bytes(&[]),
+						&"a sequence",
+					)),
+				},
+				Err(data) => invalid(data, "a sequence"),
+			},
+		}
+	}
+}
+
+#[cfg(feature = "std")]
+impl<T, S> FromData for HashSet<T, S>
+where
+	T: FromData + Eq + Hash,
+	S: BuildHasher + Default,
+{
+	fn from_data(data: Data<'_>) -> Result<Self, Error<'static>> {
+		match into_elements(strip(data)) {
+			Ok(elements) => elements.into_iter().map(T::from_data).collect(),
+			Err(data) => invalid(data, "a set"),
+		}
+	}
+}
+
+#[cfg(feature = "std")]
+impl<K, V, S> FromData for HashMap<K, V, S>
+where
+	K: FromData + Eq + Hash,
+	V: FromData,
+	S: BuildHasher + Default,
+{
+	fn from_data(data: Data<'_>) -> Result<Self, Error<'static>> {
+		into_pairs(data)?
+			.into_iter()
+			.map(|(k, v)| Ok((K::from_data(k)?, V::from_data(v)?)))
+			.collect()
+	}
+}
+
+impl<K, V> FromData for BTreeMap<K, V>
+where
+	K: FromData + Ord,
+	V: FromData,
+{
+	fn from_data(data: Data<'_>) -> Result<Self, Error<'static>> {
+		into_pairs(data)?
+			.into_iter()
+			.map(|(k, v)| Ok((K::from_data(k)?, V::from_data(v)?)))
+			.collect()
+	}
+}
+
+macro_rules! impl_tuple {
+	($len:literal: $($ty:ident)+) => {
+		impl<$($ty),+> FromData for ($($ty,)+)
+		where
+			$($ty: FromData,)+
+		{
+			fn from_data(data: Data<'_>) -> Result<Self, Error<'static>> {
+				let elements = match into_elements(strip(data)) {
+					Ok(elements) if elements.len() == $len => elements,
+					Ok(elements) => {
+						return Err(de::Error::invalid_length(
+							elements.len(),
+							&concat!("a sequence of length ", $len),
+						))
+					}
+					Err(data) => return invalid(data, "a sequence"),
+				};
+
+				let mut elements = elements.into_iter();
+				Ok(($($ty::from_data(elements.next().unwrap())?,)+))
+			}
+		}
+	};
+}
+
+impl_tuple!(1: A);
+impl_tuple!(2: A B);
+impl_tuple!(3: A B C);
+impl_tuple!(4: A B C D);
+impl_tuple!(5: A B C D E);
+impl_tuple!(6: A B C D E F);
+impl_tuple!(7: A B C D E F G);
+impl_tuple!(8: A B C D E F G H);
+impl_tuple!(9: A B C D E F G H I);
+impl_tuple!(10: A B C D E F G H I J);
+impl_tuple!(11: A B C D E F G H I J K);
+impl_tuple!(12: A B C D E F G H I J K L);
+
+macro_rules! impl_try_from {
+	($($ty:ty),*) => {
+		$(
+			/// Converts data with [FromData].
+			impl TryFrom<Data<'_>> for $ty {
+				type Error = Error<'static>;
+
+				fn try_from(data: Data<'_>) -> Result<Self, Self::Error> {
+					Self::from_data(data)
+				}
+			}
+		)*
+	};
+}
+
+impl_try_from!(
+	String,
+	Vec<u8>,
+	Bytes,
+	i64,
+	u64,
+	i32,
+	u32,
+	isize,
+	usize,
+	f64,
+	bool
+);
+
+#[cfg(test)]
+mod test {
+	use std::collections::{BTreeMap, HashMap, HashSet};
+
+	use bytes::Bytes;
+
+	use crate::{array, Data, Error, RedisError};
+
+	use super::FromData;
+
+	#[test]
+	fn test_from_int() {
+		assert_eq!(i64::from_data(Data::Integer(-1)).unwrap(), -1);
+		assert_eq!(u8::from_data(Data::bulk_string("42")).unwrap(), 42);
+		assert_eq!(u64::from_data(Data::simple_string("7")).unwrap(), 7);
+		assert_eq!(i32::from_data(Data::Double(3.0)).unwrap(), 3);
+		assert_eq!(
+			u128::from_data(Data::BigNumber(
+				"34928903284092385093248509438509438250".into()
+			))
+			.unwrap(),
+			34928903284092385093248509438509438250
+		);
+		assert!(u8::from_data(Data::Integer(256)).is_err());
+		assert!(i64::from_data(Data::Double(1.5)).is_err());
+		assert!(i64::from_data(Data::Null).is_err());
+	}
+
+	#[test]
+	fn test_from_float_and_bool() {
+		assert_eq!(f64::from_data(Data::bulk_string("1.5")).unwrap(), 1.5);
+		assert_eq!(
+			f64::from_data(Data::bulk_string("-inf")).unwrap(),
+			f64::NEG_INFINITY
+		);
+		assert_eq!(f32::from_data(Data::Integer(2)).unwrap(), 2.0);
+
+		assert!(bool::from_data(Data::Integer(1)).unwrap());
+		assert!(!bool::from_data(Data::bulk_string("0")).unwrap());
+		assert!(bool::from_data(Data::simple_string("OK")).unwrap());
+		assert!(bool::from_data(Data::Boolean(true)).unwrap());
+		assert!(bool::from_data(Data::bulk_string("yes")).is_err());
+	}
+
+	#[test]
+	fn test_from_string() {
+		assert_eq!(
+			String::from_data(Data::simple_string("foo")).unwrap(),
+			"foo"
+		);
+		assert_eq!(String::from_data(Data::Integer(1)).unwrap(), "1");
+		assert_eq!(String::from_data(Data::Double(1.5)).unwrap(), "1.5");
+		assert_eq!(
+			String::from_data(Data::Double(f64::NEG_INFINITY)).unwrap(),
+			"-inf"
+		);
+		assert_eq!(
+			Vec::<u8>::from_data(Data::bulk_string("foo")).unwrap(),
+			b"foo"
+		);
+		assert_eq!(
+			Bytes::from_data(Data::bulk_string("foo")).unwrap(),
+			&b"foo"[..]
+		);
+		assert!(String::from_data(Data::bulk_string(b"\xff")).is_err());
+	}
+
+	#[test]
+	fn test_from_nil() {
+		assert_eq!(Option::<String>::from_data(Data::Null).unwrap(), None);
+		assert_eq!(
+			Option::<Vec<String>>::from_data(array!()).unwrap(),
+			Some(vec![])
+		);
+		assert!(Vec::<String>::from_data(Data::Null).is_err());
+		assert!(String::from_data(Data::Null).is_err());
+		assert!(<()>::from_data(Data::Null).is_ok());
+	}
+
+	#[test]
+	fn test_from_aggregate() {
+		let data = array!("foo", 1, Data::Null);
+		let res = <(String, i64, Option<Bytes>)>::from_data(data).unwrap();
+		assert_eq!(res, ("foo".to_owned(), 1, None));
+
+		assert!(<(String, i64)>::from_data(array!("foo", 1, 2)).is_err());
+
+		let data = array!("a", 1, "b", 2);
+		let res = HashMap::<String, i64>::from_data(data.clone()).unwrap();
+		assert_eq!(
+			res,
+			HashMap::from([("a".to_owned(), 1), ("b".to_owned(), 2)])
+		);
+		let res = Vec::<(String, i64)>::from_data(Data::Map(vec![
+			(Data::simple_string("a"), Data::Integer(1)),
+			(Data::simple_string("b"), Data::Integer(2)),
+		]))
+		.unwrap();
+		assert_eq!(res, [("a".to_owned(), 1), ("b".to_owned(), 2)]);
+
+		let res = BTreeMap::<String, i64>::from_data(Data::Attribute {
+			attributes: vec![],
+			data: Box::new(Data::Map(vec![(
+				Data::simple_string("a"),
+				Data::Integer(1),
+			)])),
+		})
+		.unwrap();
+		assert_eq!(res, BTreeMap::from([("a".to_owned(), 1)]));
+
+		let res = HashSet::<i64>::from_data(Data::Set(vec![1.into(), 2.into()])).unwrap();
+		assert_eq!(res, HashSet::from([1, 2]));
+	}
+
+	#[test]
+	fn test_try_from() {
+		assert_eq!(i64::try_from(Data::bulk_string("5")).unwrap(), 5);
+
+		let err = i64::try_from(Data::simple_string("foo")).unwrap_err();
+		assert_eq!(
+			err.to_string(),
+			"invalid type: string \"foo\", expected i64"
+		);
+	}
+
+	#[test]
+	fn test_from_error() {
+		let data = array!(1, Data::Error("WRONGTYPE wrong".into()));
+		let res = Vec::<Result<i64, RedisError>>::from_data(data.clone()).unwrap();
+		assert_eq!(res, [Ok(1), Err(RedisError::new("WRONGTYPE wrong"))]);
+
+		let err = Vec::<i64>::from_data(data).unwrap_err();
+		assert!(matches!(err, Error::Redis(err) if err == "WRONGTYPE wrong"));
+	}
+}
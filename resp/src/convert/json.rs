@@ -0,0 +1,255 @@
+use std::{borrow::Cow, str::from_utf8};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::{Map, Number, Value};
+
+use crate::Data;
+
+use super::ToArgs;
+
+/// How strings are converted into JSON by [Data::to_json].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum BytesPolicy {
+	/// UTF-8 strings become JSON strings, and other strings become arrays of bytes.
+	#[default]
+	Utf8OrArray,
+	/// UTF-8 strings become JSON strings, and other strings become base64 strings.
+	Utf8OrBase64,
+	/// Every bulk string becomes a base64 string, so binary data can't be mistaken for text.
+	Base64,
+}
+
+impl BytesPolicy {
+	fn to_json(self, bytes: &[u8]) -> Value {
+		match (self, from_utf8(bytes)) {
+			(Self::Utf8OrArray | Self::Utf8OrBase64, Ok(str)) => Value::String(str.to_owned()),
+			(Self::Utf8OrArray, Err(_)) => Value::Array(bytes.iter().map(|&b| b.into()).collect()),
+			(Self::Utf8OrBase64 | Self::Base64, _) => Value::String(STANDARD.encode(bytes)),
+		}
+	}
+}
+
+impl Data<'_> {
+	/// Convert this data into JSON. Bulk and verbatim strings are converted according to
+	/// `policy`, and the other types as follows:
+	///
+	/// - Simple strings become strings, and null becomes `null`.
+	/// - Integers and big numbers become numbers, unless a big number is out of range for a JSON
+	///   number, in which case it becomes a string of its digits.
+	/// - Doubles become numbers, except the non-finite values which become `"inf"`, `"-inf"` and
+	///   `"nan"`.
+	/// - Arrays, sets and pushes become arrays.
+	/// - Maps become objects when every key is a UTF-8 string or an integer, and arrays of
+	///   `[key, value]` pairs otherwise.
+	/// - Attributes are removed.
+	/// - Errors become `{"error": message}`.
+	pub fn to_json(&self, policy: BytesPolicy) -> Value {
+		match self {
+			Data::SimpleString(str) => Value::String(str.to_string()),
+			Data::BulkString(bytes) | Data::VerbatimString { text: bytes, .. } => {
+				policy.to_json(bytes)
+			}
+			Data::Integer(i) => (*i).into(),
+			Data::Double(d) => match Number::from_f64(*d) {
+				Some(num) => Value::Number(num),
+				None if d.is_nan() => Value::String("nan".to_owned()),
+				None if d.is_sign_positive() => Value::String("inf".to_owned()),
+				None => Value::String("-inf".to_owned()),
+			},
+			Data::BigNumber(num) => num
+				.parse::<i64>()
+				.map(Value::from)
+				.or_else(|_| num.parse::<u64>().map(Value::from))
+				.unwrap_or_else(|_| Value::String(num.to_string())),
+			Data::Boolean(b) => Value::Bool(*b),
+			Data::Null => Value::Null,
+			Data::Array(elements) | Data::Set(elements) | Data::Push(elements) => {
+				Value::Array(elements.iter().map(|e| e.to_json(policy)).collect())
+			}
+			Data::Map(pairs) => match pairs
+				.iter()
+				.map(|(k, v)| Some((json_key(k)?, v.to_json(policy))))
+				.collect::<Option<Map<_, _>>>()
+			{
+				Some(object) => Value::Object(object),
+				None => Value::Array(
+					pairs
+						.iter()
+						.map(|(k, v)| Value::Array(vec![k.to_json(policy), v.to_json(policy)]))
+						.collect(),
+				),
+			},
+			Data::Attribute { data, .. } => data.to_json(policy),
+			Data::Error(msg) => Value::Object(Map::from_iter([(
+				"error".to_owned(),
+				msg.to_string().into(),
+			)])),
+		}
+	}
+}
+
+fn json_key(data: &Data<'_>) -> Option<String> {
+	match data {
+		Data::SimpleString(str) | Data::BigNumber(str) => Some(str.to_string()),
+		Data::BulkString(bytes) | Data::VerbatimString { text: bytes, .. } => {
+			from_utf8(bytes).ok().map(str::to_owned)
+		}
+		Data::Integer(i) => Some(i.to_string()),
+		_ => None,
+	}
+}
+
+/// Converts data into JSON with the default [BytesPolicy].
+impl From<Data<'_>> for Value {
+	fn from(data: Data<'_>) -> Self {
+		data.to_json(BytesPolicy::default())
+	}
+}
+
+fn number_to_data(num: &Number) -> Data<'static> {
+	if let Some(i) = num.as_i64() {
+		Data::Integer(i)
+	} else if num.is_u64() {
+		Data::BigNumber(num.to_string().into())
+	} else {
+		Data::Double(num.as_f64().unwrap_or(f64::NAN))
+	}
+}
+
+/// Converts JSON into data. Strings become bulk strings and objects become maps, so the data can
+/// be sent to Redis. Integers which don't fit in an `i64` become big numbers.
+impl From<Value> for Data<'static> {
+	fn from(value: Value) -> Self {
+		match value {
+			Value::Null => Data::Null,
+			Value::Bool(b) => Data::Boolean(b),
+			Value::Number(num) => number_to_data(&num),
+			Value::String(str) => Data::BulkString(str.into_bytes().into()),
+			Value::Array(values) => Data::Array(values.into_iter().map(Data::from).collect()),
+			Value::Object(object) => Data::Map(
+				object
+					.into_iter()
+					.map(|(k, v)| (Data::BulkString(k.into_bytes().into()), v.into()))
+					.collect(),
+			),
+		}
+	}
+}
+
+/// Converts JSON into data, borrowing its strings. See the conversion from [Value].
+impl<'a> From<&'a Value> for Data<'a> {
+	fn from(value: &'a Value) -> Self {
+		match value {
+			Value::Null => Data::Null,
+			Value::Bool(b) => Data::Boolean(*b),
+			Value::Number(num) => number_to_data(num),
+			Value::String(str) => Data::bulk_string(str),
+			Value::Array(values) => Data::Array(values.iter().map(Data::from).collect()),
+			Value::Object(object) => Data::Map(
+				object
+					.iter()
+					.map(|(k, v)| (Data::bulk_string(k), v.into()))
+					.collect(),
+			),
+		}
+	}
+}
+
+/// JSON arguments are flattened like the other [ToArgs]: arrays become an argument for each
+/// element, objects become an argument for each key and value, and `null` has no arguments.
+///
+/// To send a JSON document as a single argument, such as for `JSON.SET`, use its text instead.
+impl ToArgs for Value {
+	fn write_args<'a>(&'a self, args: &mut Vec<Cow<'a, [u8]>>) {
+		match self {
+			Value::Null => {}
+			Value::Bool(b) => b.write_args(args),
+			Value::Number(num) => args.push(Cow::Owned(num.to_string().into_bytes())),
+			Value::String(str) => str.write_args(args),
+			Value::Array(values) => values.write_args(args),
+			Value::Object(object) => {
+				for (k, v) in object {
+					k.write_args(args);
+					v.write_args(args);
+				}
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use serde_json::{json, Value};
+
+	use crate::{array, Data, ToArgs};
+
+	use super::BytesPolicy;
+
+	#[test]
+	fn test_to_json() {
+		let data = array!(
+			Data::bulk_string("foo"),
+			Data::Null,
+			Data::Double(f64::INFINITY),
+			Data::BigNumber("18446744073709551615".into()),
+			Data::BigNumber("18446744073709551616".into()),
+			Data::Map(vec![(Data::simple_string("a"), Data::Integer(1))]),
+			Data::Map(vec![(Data::Null, Data::Boolean(true))]),
+			Data::Error("ERR foo".into())
+		);
+
+		assert_eq!(
+			Value::from(data),
+			json!([
+				"foo",
+				null,
+				"inf",
+				18446744073709551615u64,
+				"18446744073709551616",
+				{ "a": 1 },
+				[[null, true]],
+				{ "error": "ERR foo" }
+			])
+		);
+	}
+
+	#[test]
+	fn test_to_json_bytes() {
+		let text = Data::bulk_string("foo");
+		let binary = Data::bulk_string(b"\xff\x00");
+
+		assert_eq!(text.to_json(BytesPolicy::Utf8OrArray), json!("foo"));
+		assert_eq!(binary.to_json(BytesPolicy::Utf8OrArray), json!([255, 0]));
+		assert_eq!(text.to_json(BytesPolicy::Utf8OrBase64), json!("foo"));
+		assert_eq!(binary.to_json(BytesPolicy::Utf8OrBase64), json!("/wA="));
+		assert_eq!(text.to_json(BytesPolicy::Base64), json!("Zm9v"));
+	}
+
+	#[test]
+	fn test_from_json() {
+		let value = json!({ "a": [1, 1.5, "foo", null, true] });
+		let expected = Data::Map(vec![(
+			Data::bulk_string("a"),
+			array!(
+				1,
+				Data::Double(1.5),
+				Data::bulk_string("foo"),
+				Data::Null,
+				Data::Boolean(true)
+			),
+		)]);
+
+		assert_eq!(Data::from(&value), expected);
+		assert_eq!(Value::from(Data::from(value.clone())), value);
+		assert_eq!(Data::from(value), expected);
+	}
+
+	#[test]
+	fn test_json_args() {
+		let value = json!({ "a": 1, "b": ["c", null, false] });
+		assert_eq!(
+			("HSET", "foo", &value).to_args(),
+			[&b"HSET"[..], b"foo", b"a", b"1", b"b", b"c", b"0"]
+		);
+	}
+}
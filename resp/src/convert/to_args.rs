@@ -0,0 +1,280 @@
+use alloc::{
+	borrow::Cow,
+	collections::BTreeMap,
+	string::{String, ToString},
+	vec::Vec,
+};
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+
+use bytes::Bytes;
+
+use crate::float::Float;
+
+/// Conversion into the arguments of a command.
+///
+/// Strings and bytes are a single argument, and numbers are formatted as strings. Sequences,
+/// tuples and maps are flattened into an argument for each of their elements, so
+/// `("SET", "foo", 1)` and `["SET", "foo", "1"]` are the same command. Byte sequences such as
+/// `Vec<u8>` are a single argument.
+///
+/// [None] has no arguments rather than an empty one, so options can be optional parts of a
+/// command, such as `("SET", "foo", 1, expiry.map(|secs| ("EX", secs)))`.
+pub trait ToArgs {
+	/// Append this value's arguments to `args`.
+	fn write_args<'a>(&'a self, args: &mut Vec<Cow<'a, [u8]>>);
+
+	/// Get this value's arguments.
+	fn to_args(&self) -> Vec<Cow<'_, [u8]>> {
+		let mut args = Vec::new();
+		self.write_args(&mut args);
+		args
+	}
+
+	/// Append the arguments of a slice of this type, which is only a single argument for bytes.
+	#[doc(hidden)]
+	fn write_slice_args<'a>(items: &'a [Self], args: &mut Vec<Cow<'a, [u8]>>)
+	where
+		Self: Sized,
+	{
+		for item in items {
+			item.write_args(args);
+		}
+	}
+}
+
+impl ToArgs for str {
+	fn write_args<'a>(&'a self, args: &mut Vec<Cow<'a, [u8]>>) {
+		args.push(Cow::Borrowed(self.as_bytes()));
+	}
+}
+
+impl ToArgs for String {
+	fn write_args<'a>(&'a self, args: &mut Vec<Cow<'a, [u8]>>) {
+		args.push(Cow::Borrowed(self.as_bytes()));
+	}
+}
+
+impl ToArgs for Cow<'_, str> {
+	fn write_args<'a>(&'a self, args: &mut Vec<Cow<'a, [u8]>>) {
+		args.push(Cow::Borrowed(self.as_bytes()));
+	}
+}
+
+impl ToArgs for Cow<'_, [u8]> {
+	fn write_args<'a>(&'a self, args: &mut Vec<Cow<'a, [u8]>>) {
+		args.push(Cow::Borrowed(self));
+	}
+}
+
+impl ToArgs for Bytes {
+	fn write_args<'a>(&'a self, args: &mut Vec<Cow<'a, [u8]>>) {
+		args.push(Cow::Borrowed(self));
+	}
+}
+
+impl ToArgs for u8 {
+	fn write_args<'a>(&'a self, args: &mut Vec<Cow<'a, [u8]>>) {
+		args.push(Cow::Owned(self.to_string().into_bytes()));
+	}
+
+	fn write_slice_args<'a>(items: &'a [Self], args: &mut Vec<Cow<'a, [u8]>>) {
+		args.push(Cow::Borrowed(items));
+	}
+}
+
+macro_rules! impl_display {
+	($($ty:ty)*) => {
+		$(
+			impl ToArgs for $ty {
+				fn write_args<'a>(&'a self, args: &mut Vec<Cow<'a, [u8]>>) {
+					args.push(Cow::Owned(self.to_string().into_bytes()));
+				}
+			}
+		)*
+	};
+}
+
+impl_display!(i8 i16 i32 i64 i128 isize u16 u32 u64 u128 usize);
+
+macro_rules! impl_float {
+	($($ty:ty)*) => {
+		$(
+			impl ToArgs for $ty {
+				fn write_args<'a>(&'a self, args: &mut Vec<Cow<'a, [u8]>>) {
+					args.push(Cow::Owned(Float::new(*self, None).to_string().into_bytes()));
+				}
+			}
+		)*
+	};
+}
+
+impl_float!(f32 f64);
+
+impl ToArgs for bool {
+	fn write_args<'a>(&'a self, args: &mut Vec<Cow<'a, [u8]>>) {
+		let arg: &'static [u8] = if *self { b"1" } else { b"0" };
+		args.push(Cow::Borrowed(arg));
+	}
+}
+
+impl<T> ToArgs for &T
+where
+	T: ToArgs + ?Sized,
+{
+	fn write_args<'a>(&'a self, args: &mut Vec<Cow<'a, [u8]>>) {
+		(**self).write_args(args)
+	}
+}
+
+impl<T> ToArgs for Option<T>
+where
+	T: ToArgs,
+{
+	fn write_args<'a>(&'a self, args: &mut Vec<Cow<'a, [u8]>>) {
+		if let Some(value) = self {
+			value.write_args(args);
+		}
+	}
+}
+
+impl<T> ToArgs for [T]
+where
+	T: ToArgs,
+{
+	fn write_args<'a>(&'a self, args: &mut Vec<Cow<'a, [u8]>>) {
+		T::write_slice_args(self, args)
+	}
+}
+
+impl<T, const N: usize> ToArgs for [T; N]
+where
+	T: ToArgs,
+{
+	fn write_args<'a>(&'a self, args: &mut Vec<Cow<'a, [u8]>>) {
+		T::write_slice_args(self, args)
+	}
+}
+
+impl<T> ToArgs for Vec<T>
+where
+	T: ToArgs,
+{
+	fn write_args<'a>(&'a self, args: &mut Vec<Cow<'a, [u8]>>) {
+		T::write_slice_args(self, args)
+	}
+}
+
+#[cfg(feature = "std")]
+impl<T, S> ToArgs for HashSet<T, S>
+where
+	T: ToArgs,
+{
+	fn write_args<'a>(&'a self, args: &mut Vec<Cow<'a, [u8]>>) {
+		for item in self {
+			item.write_args(args);
+		}
+	}
+}
+
+#[cfg(feature = "std")]
+impl<K, V, S> ToArgs for HashMap<K, V, S>
+where
+	K: ToArgs,
+	V: ToArgs,
+{
+	fn write_args<'a>(&'a self, args: &mut Vec<Cow<'a, [u8]>>) {
+		for (k, v) in self {
+			k.write_args(args);
+			v.write_args(args);
+		}
+	}
+}
+
+impl<K, V> ToArgs for BTreeMap<K, V>
+where
+	K: ToArgs,
+	V: ToArgs,
+{
+	fn write_args<'a>(&'a self, args: &mut Vec<Cow<'a, [u8]>>) {
+		for (k, v) in self {
+			k.write_args(args);
+			v.write_args(args);
+		}
+	}
+}
+
+macro_rules! impl_tuple {
+	($($ty:ident $idx:tt)+) => {
+		impl<$($ty),+> ToArgs for ($($ty,)+)
+		where
+			$($ty: ToArgs,)+
+		{
+			fn write_args<'a>(&'a self, args: &mut Vec<Cow<'a, [u8]>>) {
+				$(self.$idx.write_args(args);)+
+			}
+		}
+	};
+}
+
+impl_tuple!(A 0);
+impl_tuple!(A 0 B 1);
+impl_tuple!(A 0 B 1 C 2);
+impl_tuple!(A 0 B 1 C 2 D 3);
+impl_tuple!(A 0 B 1 C 2 D 3 E 4);
+impl_tuple!(A 0 B 1 C 2 D 3 E 4 F 5);
+impl_tuple!(A 0 B 1 C 2 D 3 E 4 F 5 G 6);
+impl_tuple!(A 0 B 1 C 2 D 3 E 4 F 5 G 6 H 7);
+impl_tuple!(A 0 B 1 C 2 D 3 E 4 F 5 G 6 H 7 I 8);
+impl_tuple!(A 0 B 1 C 2 D 3 E 4 F 5 G 6 H 7 I 8 J 9);
+impl_tuple!(A 0 B 1 C 2 D 3 E 4 F 5 G 6 H 7 I 8 J 9 K 10);
+impl_tuple!(A 0 B 1 C 2 D 3 E 4 F 5 G 6 H 7 I 8 J 9 K 10 L 11);
+
+#[cfg(test)]
+mod test {
+	use std::{borrow::Cow, collections::BTreeMap};
+
+	use super::ToArgs;
+
+	fn args(value: impl ToArgs) -> Vec<Vec<u8>> {
+		value.to_args().into_iter().map(Cow::into_owned).collect()
+	}
+
+	#[test]
+	fn test_to_args() {
+		let expected = [b"SET".to_vec(), b"foo".to_vec(), b"1".to_vec()];
+		assert_eq!(args(["SET", "foo", "1"]), expected);
+		assert_eq!(args(("SET", "foo", 1)), expected);
+		assert_eq!(args(("SET", b"foo".to_vec(), 1u8)), expected);
+		assert_eq!(
+			args(vec!["SET".to_owned(), "foo".to_owned(), "1".to_owned()]),
+			expected
+		);
+		assert_eq!(args(("SET", &b"foo"[..], Some(1), None::<&str>)), expected);
+	}
+
+	#[test]
+	fn test_to_args_flatten() {
+		let fields = BTreeMap::from([("a", 1.5), ("b", f64::INFINITY)]);
+		assert_eq!(
+			args(("HSET", "foo", fields)),
+			[&b"HSET"[..], b"foo", b"a", b"1.5", b"b", b"inf"]
+		);
+		assert_eq!(
+			args(("ZADD", "foo", f64::NAN)),
+			[&b"ZADD"[..], b"foo", b"nan"]
+		);
+		assert_eq!(
+			args(("DEL", ["a", "b"], true)),
+			[&b"DEL"[..], b"a", b"b", b"1"]
+		);
+	}
+
+	#[test]
+	fn test_to_args_borrowed() {
+		let key = String::from("foo");
+		let cmd = ("GET", &key);
+		let args = cmd.to_args();
+		assert!(matches!(args[1], Cow::Borrowed(arg) if arg.as_ptr() == key.as_ptr()));
+	}
+}
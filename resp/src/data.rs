@@ -1,7 +1,17 @@
-use std::borrow::Cow;
+use alloc::{borrow::Cow, boxed::Box, string::String, vec::Vec};
 
+use crate::ToArgs;
+
+use self::display::RawDisplay;
+
+/// Accessors for the values inside data.
+mod access;
 pub mod de;
+/// `redis-cli` style formatting.
+pub(crate) mod display;
 pub mod ser;
+/// Names used to pass RESP3 types through serde, in the vein of `serde_json::value::RawValue`.
+pub(crate) mod token;
 
 /// RESP data. Read the [Redis documenation](https://redis.io/commands) for details on which type
 /// to expect as a response.
@@ -10,18 +20,47 @@ pub mod ser;
 /// representation they are not optional. They will be represented with [Data::Null] if the bulk
 /// string or array is null.
 ///
-/// Errors are not represented here for two reasons: 1) it's never correct to send an error to the
-/// Redis server, and 2) it's more ergonomic to have errors returned in a [Result](crate::Result).
+/// Errors in a reply are returned in a [Result](crate::Result) rather than represented here. The
+/// exception is errors inside an aggregate, such as the reply to `EXEC`, which are represented by
+/// [Data::Error] so that the other elements are kept.
+///
+/// Since strings are never converted into errors, it's possible to convert a Rust string into
+/// `Data` without ambiguity.
 ///
-/// Since errors are not represented, it's possible to convert a Rust string into `Data` without
-/// ambiguity.
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// The variants after [Data::Null] are only sent by servers speaking
+/// [RESP3](https://github.com/redis/redis-specification/blob/master/protocol/RESP3.md), which is
+/// enabled with `HELLO 3`. The RESP3 null is also represented by [Data::Null], and blob errors are
+/// treated like any other error.
+#[derive(Debug, Clone, PartialEq)]
 pub enum Data<'a> {
 	SimpleString(Cow<'a, str>),
 	Integer(i64),
 	BulkString(Cow<'a, [u8]>),
 	Array(Vec<Data<'a>>),
 	Null,
+	/// An ordered list of key-value pairs.
+	Map(Vec<(Data<'a>, Data<'a>)>),
+	/// An unordered collection of unique elements.
+	Set(Vec<Data<'a>>),
+	Double(f64),
+	Boolean(bool),
+	/// An integer too large for [Data::Integer], as a string of digits with an optional sign.
+	BigNumber(Cow<'a, str>),
+	/// A string with a three byte format, such as `txt` or `mkd`.
+	VerbatimString {
+		format: [u8; 3],
+		text: Cow<'a, [u8]>,
+	},
+	/// Auxiliary information attached to the reply in `data`.
+	Attribute {
+		attributes: Vec<(Data<'a>, Data<'a>)>,
+		data: Box<Data<'a>>,
+	},
+	/// Out-of-band data, such as PubSub messages and client tracking invalidations.
+	Push(Vec<Data<'a>>),
+	/// An error inside an aggregate. It's serialized as a simple error, or a blob error if it
+	/// contains line breaks.
+	Error(Cow<'a, str>),
 }
 
 impl<'a> Data<'a> {
@@ -49,9 +88,38 @@ impl<'a> Data<'a> {
 			Self::BulkString(bytes) => Data::BulkString(bytes.into_owned().into()),
 			Self::Array(arr) => Data::Array(arr.into_iter().map(Data::into_owned).collect()),
 			Self::Null => Data::Null,
+			Self::Map(map) => Data::Map(owned_pairs(map)),
+			Self::Set(set) => Data::Set(set.into_iter().map(Data::into_owned).collect()),
+			Self::Double(double) => Data::Double(double),
+			Self::Boolean(bool) => Data::Boolean(bool),
+			Self::BigNumber(num) => Data::BigNumber(num.into_owned().into()),
+			Self::VerbatimString { format, text } => Data::VerbatimString {
+				format,
+				text: text.into_owned().into(),
+			},
+			Self::Attribute { attributes, data } => Data::Attribute {
+				attributes: owned_pairs(attributes),
+				data: Box::new(data.into_owned()),
+			},
+			Self::Push(push) => Data::Push(push.into_iter().map(Data::into_owned).collect()),
+			Self::Error(msg) => Data::Error(msg.into_owned().into()),
 		}
 	}
 
+	/// Format this data like `redis-cli --raw`. The [Display](std::fmt::Display) implementation
+	/// formats it like `redis-cli` in a terminal.
+	pub fn display_raw(&self) -> RawDisplay<'_, 'a> {
+		RawDisplay(self)
+	}
+
+	/// Make a command from its arguments, see [ToArgs].
+	pub fn from_args<A>(args: &'a A) -> Data<'a>
+	where
+		A: ToArgs + ?Sized,
+	{
+		Data::Array(args.to_args().into_iter().map(Data::BulkString).collect())
+	}
+
 	pub fn from_bytes_iter<I, B>(iter: I) -> Data<'a>
 	where
 		I: IntoIterator<Item = &'a B>,
@@ -68,6 +136,13 @@ impl<'a> Data<'a> {
 	}
 }
 
+fn owned_pairs(pairs: Vec<(Data<'_>, Data<'_>)>) -> Vec<(Data<'static>, Data<'static>)> {
+	pairs
+		.into_iter()
+		.map(|(k, v)| (k.into_owned(), v.into_owned()))
+		.collect()
+}
+
 impl<'a> From<&'a str> for Data<'a> {
 	fn from(str: &'a str) -> Self {
 		Data::SimpleString(str.into())
@@ -122,6 +197,18 @@ impl<'a> From<Vec<Data<'a>>> for Data<'a> {
 	}
 }
 
+impl From<f64> for Data<'_> {
+	fn from(double: f64) -> Self {
+		Data::Double(double)
+	}
+}
+
+impl From<bool> for Data<'_> {
+	fn from(bool: bool) -> Self {
+		Data::Boolean(bool)
+	}
+}
+
 impl From<()> for Data<'_> {
 	fn from(_: ()) -> Self {
 		Data::Null
@@ -170,6 +257,18 @@ impl PartialEq<i64> for Data<'_> {
 	}
 }
 
+impl PartialEq<f64> for Data<'_> {
+	fn eq(&self, other: &f64) -> bool {
+		matches!(self, Data::Double(d) if *d == *other)
+	}
+}
+
+impl PartialEq<bool> for Data<'_> {
+	fn eq(&self, other: &bool) -> bool {
+		matches!(self, Data::Boolean(b) if *b == *other)
+	}
+}
+
 impl PartialEq<()> for Data<'_> {
 	fn eq(&self, _: &()) -> bool {
 		matches!(self, Data::Null)
@@ -191,6 +290,6 @@ impl PartialEq<()> for Data<'_> {
 #[macro_export]
 macro_rules! array {
 	($($items:expr),*) => {
-		$crate::Data::Array(vec![$($crate::Data::from($items)),*])
+		$crate::Data::Array($crate::__vec![$($crate::Data::from($items)),*])
 	};
 }
@@ -0,0 +1,208 @@
+use alloc::vec::Vec;
+use core::str::{from_utf8, FromStr};
+
+use itertools::Either;
+
+use super::Data;
+
+/// Accessors for the values inside data. Attributes are skipped, so these look at the data they
+/// are attached to.
+impl<'a> Data<'a> {
+	/// The data inside any attributes.
+	pub fn without_attributes(&self) -> &Data<'a> {
+		match self {
+			Data::Attribute { data, .. } => data.without_attributes(),
+			data => data,
+		}
+	}
+
+	/// Whether this is [Data::Null].
+	pub fn is_null(&self) -> bool {
+		matches!(self.without_attributes(), Data::Null)
+	}
+
+	/// The text of a simple, bulk or verbatim string or a big number, if it is UTF-8.
+	pub fn as_str(&self) -> Option<&str> {
+		match self.without_attributes() {
+			Data::SimpleString(str) | Data::BigNumber(str) => Some(str),
+			Data::BulkString(bytes) | Data::VerbatimString { text: bytes, .. } => {
+				from_utf8(bytes).ok()
+			}
+			_ => None,
+		}
+	}
+
+	/// The bytes of a simple, bulk or verbatim string or a big number.
+	pub fn as_bytes(&self) -> Option<&[u8]> {
+		match self.without_attributes() {
+			Data::SimpleString(str) | Data::BigNumber(str) => Some(str.as_bytes()),
+			Data::BulkString(bytes) | Data::VerbatimString { text: bytes, .. } => Some(bytes),
+			_ => None,
+		}
+	}
+
+	/// This data as an integer. Like Redis, strings are parsed and doubles without a fractional
+	/// part are accepted, as are booleans.
+	pub fn as_i64(&self) -> Option<i64> {
+		self.to_int()
+	}
+
+	/// This data as a double. Like Redis, strings are parsed, including `inf` and `-inf`, and
+	/// integers are accepted.
+	pub fn as_f64(&self) -> Option<f64> {
+		match self.without_attributes() {
+			Data::Double(d) => Some(*d),
+			Data::Integer(i) => Some(*i as f64),
+			data => data.as_str()?.parse().ok(),
+		}
+	}
+
+	/// The elements of an array, set or push.
+	pub fn as_array(&self) -> Option<&[Data<'a>]> {
+		match self.without_attributes() {
+			Data::Array(elements) | Data::Set(elements) | Data::Push(elements) => Some(elements),
+			_ => None,
+		}
+	}
+
+	/// The elements of an array, set or push, or this data if it isn't one.
+	pub fn into_array(self) -> Result<Vec<Data<'a>>, Self> {
+		match self {
+			Data::Array(elements) | Data::Set(elements) | Data::Push(elements) => Ok(elements),
+			Data::Attribute { data, .. } if data.as_array().is_some() => (*data).into_array(),
+			data => Err(data),
+		}
+	}
+
+	/// The entries of a map.
+	pub fn as_map(&self) -> Option<&[(Data<'a>, Data<'a>)]> {
+		match self.without_attributes() {
+			Data::Map(pairs) => Some(pairs),
+			_ => None,
+		}
+	}
+
+	/// The element at `index` of an array, set or push.
+	pub fn get(&self, index: usize) -> Option<&Data<'a>> {
+		self.as_array()?.get(index)
+	}
+
+	/// The value for `key` in a map, or in an array of alternating keys and values such as the
+	/// RESP2 reply to `HGETALL`. Keys are compared with their bytes.
+	pub fn get_key(&self, key: impl AsRef<[u8]>) -> Option<&Data<'a>> {
+		let key = key.as_ref();
+		self.pairs()
+			.find(|(k, _)| k.as_bytes() == Some(key))
+			.map(|(_, v)| v)
+	}
+
+	/// Iterate over the elements of an array, set or push. Other data has no elements.
+	pub fn iter(&self) -> core::slice::Iter<'_, Data<'a>> {
+		self.as_array().unwrap_or_default().iter()
+	}
+
+	/// Iterate over the entries of a map, or of an array of alternating keys and values. Other
+	/// data has no entries.
+	pub fn pairs(&self) -> impl Iterator<Item = (&Data<'a>, &Data<'a>)> {
+		match self.without_attributes() {
+			Data::Map(pairs) => Either::Left(pairs.iter().map(|(k, v)| (k, v))),
+			data => Either::Right(
+				data.as_array()
+					.filter(|elements| elements.len() % 2 == 0)
+					.unwrap_or_default()
+					.chunks_exact(2)
+					.map(|pair| (&pair[0], &pair[1])),
+			),
+		}
+	}
+
+	/// Look up nested data with a [JSON pointer](https://www.rfc-editor.org/rfc/rfc6901), such
+	/// as `/0/foo/1`. Each token is an index into an aggregate or a key of a map, as in
+	/// [Data::get] and [Data::get_key].
+	pub fn pointer(&self, pointer: &str) -> Option<&Data<'a>> {
+		if pointer.is_empty() {
+			return Some(self);
+		}
+
+		pointer
+			.strip_prefix('/')?
+			.split('/')
+			.map(|token| token.replace("~1", "/").replace("~0", "~"))
+			.try_fold(self, |data, token| {
+				match (data.without_attributes(), token.parse()) {
+					(Data::Map(_), _) => data.get_key(&token),
+					(_, Ok(index)) => data.get(index),
+					(_, Err(_)) => data.get_key(&token),
+				}
+			})
+	}
+
+	/// This data as an integer of any size, like [Data::as_i64].
+	pub(crate) fn to_int<T>(&self) -> Option<T>
+	where
+		T: TryFrom<i64> + FromStr,
+	{
+		match self.without_attributes() {
+			Data::Integer(i) => T::try_from(*i).ok(),
+			Data::Boolean(b) => T::try_from(*b as i64).ok(),
+			Data::Double(d) if d.abs() < i64::MAX as f64 && *d as i64 as f64 == *d => {
+				T::try_from(*d as i64).ok()
+			}
+			data => data.as_str()?.parse().ok(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::{array, Data};
+
+	#[test]
+	fn test_access() {
+		let data = array!(
+			Data::bulk_string("12"),
+			Data::Double(1.5),
+			Data::Null,
+			Data::simple_string("foo")
+		);
+
+		assert_eq!(data.get(0).and_then(Data::as_i64), Some(12));
+		assert_eq!(data.get(0).and_then(Data::as_f64), Some(12.0));
+		assert_eq!(data.get(1).and_then(Data::as_i64), None);
+		assert_eq!(data.get(1).and_then(Data::as_f64), Some(1.5));
+		assert!(data.get(2).unwrap().is_null());
+		assert_eq!(data.get(3).and_then(Data::as_str), Some("foo"));
+		assert_eq!(data.get(3).and_then(Data::as_bytes), Some(&b"foo"[..]));
+		assert_eq!(data.get(4), None);
+		assert_eq!(data.iter().count(), 4);
+		assert_eq!(Data::Integer(1).iter().count(), 0);
+		assert_eq!(data.into_array().map(|a| a.len()), Ok(4));
+	}
+
+	#[test]
+	fn test_pointer() {
+		let data = array!(
+			1,
+			Data::Map(vec![(
+				Data::simple_string("a/b"),
+				array!(Data::bulk_string("field"), Data::bulk_string("value"))
+			)])
+		);
+
+		assert_eq!(data.pointer(""), Some(&data));
+		assert_eq!(data.pointer("/0"), Some(&Data::Integer(1)));
+		assert_eq!(
+			data.pointer("/1/a~1b/field").and_then(Data::as_str),
+			Some("value")
+		);
+		assert_eq!(
+			data.pointer("/1/a~1b/1").and_then(Data::as_str),
+			Some("value")
+		);
+		assert_eq!(data.pointer("/2"), None);
+		assert_eq!(data.pointer("0"), None);
+
+		let pairs = data.pointer("/1").unwrap().pairs().count();
+		assert_eq!(pairs, 1);
+	}
+}
@@ -1,15 +1,21 @@
-use std::borrow::Cow;
+use alloc::{
+	borrow::{Cow, ToOwned},
+	boxed::Box,
+	format,
+	string::{String, ToString},
+	vec,
+	vec::Vec,
+};
 
 use itertools::Itertools;
 use serde::{
-	de::{
-		self,
-		value::{MapDeserializer, SeqDeserializer},
-	},
+	de::{self, IntoDeserializer},
 	forward_to_deserialize_any, Deserialize,
 };
 
-use crate::{Data, Error};
+use crate::{Data, Error, RedisError, Segment};
+
+use super::token;
 
 /// Deserialize [Data] into `T`.
 pub fn from_data<'de, T>(data: Data<'de>) -> Result<T, Error<'de>>
@@ -24,106 +30,374 @@ impl<'de> de::Deserialize<'de> for Data<'de> {
 	where
 		D: serde::Deserializer<'de>,
 	{
-		struct Visitor;
+		deserializer.deserialize_newtype_struct(token::DATA, Visitor)
+	}
+}
 
-		impl<'de> de::Visitor<'de> for Visitor {
-			type Value = Data<'de>;
+/// Deserializes an element of an aggregate [Data], which can be an error.
+pub(crate) struct Element;
 
-			fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-				write!(formatter, "valid RESP data")
-			}
+impl<'de> de::DeserializeSeed<'de> for Element {
+	type Value = Data<'de>;
 
-			fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
-			where
-				E: de::Error,
-			{
-				Ok(Data::Integer(v))
-			}
+	fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+	where
+		D: serde::Deserializer<'de>,
+	{
+		deserializer.deserialize_newtype_struct(token::ELEMENT, Visitor)
+	}
+}
 
-			fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
-			where
-				E: de::Error,
-			{
-				Ok(Data::SimpleString(Cow::Owned(v)))
-			}
+struct Visitor;
 
-			fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
-			where
-				E: de::Error,
-			{
-				self.visit_string(v.to_owned())
-			}
+impl<'de> de::Visitor<'de> for Visitor {
+	type Value = Data<'de>;
 
-			fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
-			where
-				E: de::Error,
-			{
-				Ok(Data::SimpleString(Cow::Borrowed(v)))
-			}
+	fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+		write!(formatter, "valid RESP data")
+	}
 
-			fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
-			where
-				E: de::Error,
-			{
-				Ok(Data::BulkString(Cow::Owned(v)))
-			}
+	fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+	where
+		E: de::Error,
+	{
+		Ok(Data::Boolean(v))
+	}
 
-			fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
-			where
-				E: de::Error,
-			{
-				self.visit_byte_buf(v.to_owned())
-			}
+	fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+	where
+		E: de::Error,
+	{
+		Ok(Data::Integer(v))
+	}
 
-			fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E>
-			where
-				E: de::Error,
-			{
-				Ok(Data::BulkString(Cow::Borrowed(v)))
-			}
+	fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+	where
+		E: de::Error,
+	{
+		Ok(match v.try_into() {
+			Ok(int) => Data::Integer(int),
+			Err(_) => Data::BigNumber(Cow::Owned(v.to_string())),
+		})
+	}
 
-			fn visit_none<E>(self) -> Result<Self::Value, E>
-			where
-				E: de::Error,
-			{
-				Ok(Data::Null)
-			}
+	fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+	where
+		E: de::Error,
+	{
+		Ok(Data::Double(v))
+	}
 
-			fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
-			where
-				D: serde::Deserializer<'de>,
-			{
-				de::Deserialize::deserialize(deserializer)
-			}
+	fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+	where
+		E: de::Error,
+	{
+		Ok(Data::SimpleString(Cow::Owned(v)))
+	}
 
-			fn visit_unit<E>(self) -> Result<Self::Value, E>
-			where
-				E: de::Error,
-			{
-				self.visit_none()
-			}
+	fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+	where
+		E: de::Error,
+	{
+		self.visit_string(v.to_owned())
+	}
 
-			fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
-			where
-				D: serde::Deserializer<'de>,
-			{
-				de::Deserialize::deserialize(deserializer)
-			}
+	fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+	where
+		E: de::Error,
+	{
+		Ok(Data::SimpleString(Cow::Borrowed(v)))
+	}
 
-			fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
-			where
-				A: de::SeqAccess<'de>,
-			{
-				let mut out = Vec::with_capacity(seq.size_hint().unwrap_or(0));
-				while let Some(v) = seq.next_element()? {
-					out.push(v);
-				}
+	fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+	where
+		E: de::Error,
+	{
+		Ok(Data::BulkString(Cow::Owned(v)))
+	}
+
+	fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+	where
+		E: de::Error,
+	{
+		self.visit_byte_buf(v.to_owned())
+	}
+
+	fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E>
+	where
+		E: de::Error,
+	{
+		Ok(Data::BulkString(Cow::Borrowed(v)))
+	}
+
+	fn visit_none<E>(self) -> Result<Self::Value, E>
+	where
+		E: de::Error,
+	{
+		Ok(Data::Null)
+	}
+
+	fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+	where
+		D: serde::Deserializer<'de>,
+	{
+		de::Deserialize::deserialize(deserializer)
+	}
+
+	fn visit_unit<E>(self) -> Result<Self::Value, E>
+	where
+		E: de::Error,
+	{
+		self.visit_none()
+	}
+
+	fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+	where
+		D: serde::Deserializer<'de>,
+	{
+		deserializer.deserialize_any(self)
+	}
 
-				Ok(Data::Array(out))
+	fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+	where
+		A: de::SeqAccess<'de>,
+	{
+		let mut out = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+		while let Some(v) = seq.next_element_seed(Element)? {
+			out.push(v);
+		}
+
+		Ok(Data::Array(out))
+	}
+
+	fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+	where
+		A: de::MapAccess<'de>,
+	{
+		let mut out = Vec::with_capacity(map.size_hint().unwrap_or(0));
+		while let Some(entry) = map.next_entry_seed(Element, Element)? {
+			out.push(entry);
+		}
+
+		Ok(Data::Map(out))
+	}
+
+	fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+	where
+		A: de::EnumAccess<'de>,
+	{
+		let (variant, content) = data.variant::<u32>()?;
+
+		match (variant, de::VariantAccess::newtype_variant(content)?) {
+			(token::SET_VARIANT, Data::Array(set)) => Ok(Data::Set(set)),
+			(token::PUSH_VARIANT, Data::Array(push)) => Ok(Data::Push(push)),
+			(token::BIG_NUMBER_VARIANT, Data::SimpleString(num)) => Ok(Data::BigNumber(num)),
+			(token::ERROR_VARIANT, Data::SimpleString(msg)) => Ok(Data::Error(msg)),
+			(token::VERBATIM_STRING_VARIANT, Data::Array(arr)) => {
+				match <[Data; 2]>::try_from(arr) {
+					Ok([Data::BulkString(format), Data::BulkString(text)]) => {
+						Ok(Data::VerbatimString {
+							format: (*format)
+								.try_into()
+								.map_err(|_| de::Error::invalid_length(format.len(), &"3"))?,
+							text,
+						})
+					}
+					_ => Err(de::Error::custom("invalid verbatim string")),
+				}
 			}
+			(token::ATTRIBUTE_VARIANT, Data::Array(arr)) => match <[Data; 2]>::try_from(arr) {
+				Ok([Data::Map(attributes), data]) => Ok(Data::Attribute {
+					attributes,
+					data: Box::new(data),
+				}),
+				_ => Err(de::Error::custom("invalid attribute")),
+			},
+			_ => Err(de::Error::custom("invalid RESP3 data")),
 		}
+	}
+}
+
+/// Variant index of `Ok` in [Result].
+pub(crate) const RESULT_OK: u32 = 0;
+/// Variant index of `Err` in [Result].
+pub(crate) const RESULT_ERR: u32 = 1;
+
+/// Whether an enum is [Result], which is deserialized as `Ok` from anything but a Redis error.
+///
+/// Errors are passed to `Err` as [Data::Error], which only deserializes into [RedisError] (using
+/// [token::ERROR]), so other enums with the same name and variants can't swallow them.
+pub(crate) fn is_result(name: &str, variants: &[&str]) -> bool {
+	name == "Result" && variants == ["Ok", "Err"]
+}
+
+/// Passes a RESP3 type without an equivalent in the serde data model to a visitor. The `content`
+/// is the type's representation using only the RESP2 types, which the visitor converts back.
+pub(crate) struct Resp3Access<'de> {
+	pub variant: u32,
+	pub content: Data<'de>,
+}
 
-		deserializer.deserialize_any(Visitor)
+impl<'de> Resp3Access<'de> {
+	fn new(data: Data<'de>) -> Result<Self, Data<'de>> {
+		let (variant, content) = match data {
+			Data::Set(set) => (token::SET_VARIANT, Data::Array(set)),
+			Data::Push(push) => (token::PUSH_VARIANT, Data::Array(push)),
+			Data::BigNumber(num) => (token::BIG_NUMBER_VARIANT, Data::SimpleString(num)),
+			Data::VerbatimString { format, text } => (
+				token::VERBATIM_STRING_VARIANT,
+				Data::Array(vec![
+					Data::BulkString(format.to_vec().into()),
+					Data::BulkString(text),
+				]),
+			),
+			Data::Attribute { attributes, data } => (
+				token::ATTRIBUTE_VARIANT,
+				Data::Array(vec![Data::Map(attributes), *data]),
+			),
+			Data::Error(msg) => (token::ERROR_VARIANT, Data::SimpleString(msg)),
+			other => return Err(other),
+		};
+
+		Ok(Self { variant, content })
+	}
+}
+
+impl<'de> de::EnumAccess<'de> for Resp3Access<'de> {
+	type Error = Error<'de>;
+	type Variant = Data<'de>;
+
+	fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+	where
+		V: de::DeserializeSeed<'de>,
+	{
+		let variant =
+			seed.deserialize(IntoDeserializer::<Error>::into_deserializer(self.variant))?;
+		Ok((variant, self.content))
+	}
+}
+
+impl<'de> de::VariantAccess<'de> for Data<'de> {
+	type Error = Error<'de>;
+
+	fn unit_variant(self) -> Result<(), Error<'de>> {
+		Err(de::Error::invalid_type(
+			de::Unexpected::NewtypeVariant,
+			&"unit variant",
+		))
+	}
+
+	fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Error<'de>>
+	where
+		T: de::DeserializeSeed<'de>,
+	{
+		seed.deserialize(self)
+	}
+
+	fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, Error<'de>>
+	where
+		V: de::Visitor<'de>,
+	{
+		de::Deserializer::deserialize_seq(self, visitor)
+	}
+
+	fn struct_variant<V>(
+		self,
+		_fields: &'static [&'static str],
+		visitor: V,
+	) -> Result<V::Value, Error<'de>>
+	where
+		V: de::Visitor<'de>,
+	{
+		de::Deserializer::deserialize_map(self, visitor)
+	}
+}
+
+/// Access to the elements of an aggregate, adding their index to the path of any error.
+struct SeqAccess<I> {
+	iter: I,
+	index: usize,
+}
+
+impl<'de, I> de::SeqAccess<'de> for SeqAccess<I>
+where
+	I: Iterator<Item = Data<'de>>,
+{
+	type Error = Error<'de>;
+
+	fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Error<'de>>
+	where
+		T: de::DeserializeSeed<'de>,
+	{
+		let data = match self.iter.next() {
+			Some(data) => data,
+			None => return Ok(None),
+		};
+
+		let segment = Segment::Index(self.index);
+		self.index += 1;
+		seed.deserialize(data).map(Some).map_err(|e| e.at(segment))
+	}
+
+	fn size_hint(&self) -> Option<usize> {
+		match self.iter.size_hint() {
+			(lower, Some(upper)) if lower == upper => Some(upper),
+			_ => None,
+		}
+	}
+}
+
+/// Access to the entries of a map, adding their key to the path of any error.
+struct MapAccess<'de, I> {
+	iter: I,
+	index: usize,
+	value: Option<(Segment, Data<'de>)>,
+}
+
+impl<'de, I> de::MapAccess<'de> for MapAccess<'de, I>
+where
+	I: Iterator<Item = (Data<'de>, Data<'de>)>,
+{
+	type Error = Error<'de>;
+
+	fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Error<'de>>
+	where
+		K: de::DeserializeSeed<'de>,
+	{
+		let (key, value) = match self.iter.next() {
+			Some(entry) => entry,
+			None => return Ok(None),
+		};
+
+		let segment = match (&key, key.as_str()) {
+			(_, Some(key)) => Segment::Key(key.to_owned()),
+			(Data::Integer(i), _) => Segment::Key(i.to_string()),
+			_ => Segment::Index(self.index),
+		};
+		self.index += 1;
+
+		let key = seed.deserialize(key).map_err(|e| e.at(segment.clone()))?;
+		self.value = Some((segment, value));
+		Ok(Some(key))
+	}
+
+	fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Error<'de>>
+	where
+		V: de::DeserializeSeed<'de>,
+	{
+		let (segment, value) = self
+			.value
+			.take()
+			.ok_or_else(|| Error::Message("value requested before its key".into()))?;
+
+		seed.deserialize(value).map_err(|e| e.at(segment))
+	}
+
+	fn size_hint(&self) -> Option<usize> {
+		match self.iter.size_hint() {
+			(lower, Some(upper)) if lower == upper => Some(upper),
+			_ => None,
+		}
 	}
 }
 
@@ -134,9 +408,20 @@ fn visit_array<'de, V>(
 where
 	V: de::Visitor<'de>,
 {
-	let mut deserializer = SeqDeserializer::new(array);
-	let seq = visitor.visit_seq(&mut deserializer)?;
-	deserializer.end()?;
+	let mut access = SeqAccess {
+		iter: array,
+		index: 0,
+	};
+	let seq = visitor.visit_seq(&mut access)?;
+
+	let remaining = access.iter.count();
+	if remaining > 0 {
+		return Err(de::Error::invalid_length(
+			access.index + remaining,
+			&format!("{} elements", access.index).as_str(),
+		));
+	}
+
 	Ok(seq)
 }
 
@@ -147,35 +432,80 @@ fn visit_map<'de, V>(
 where
 	V: de::Visitor<'de>,
 {
-	let mut deserializer = MapDeserializer::new(array.tuples::<(_, _)>());
-	let seq = visitor.visit_map(&mut deserializer)?;
-	deserializer.end()?;
-	Ok(seq)
+	visit_pairs(array.tuples::<(_, _)>(), visitor)
+}
+
+fn visit_pairs<'de, V>(
+	pairs: impl Iterator<Item = (Data<'de>, Data<'de>)>,
+	visitor: V,
+) -> Result<V::Value, Error<'de>>
+where
+	V: de::Visitor<'de>,
+{
+	let mut access = MapAccess {
+		iter: pairs,
+		index: 0,
+		value: None,
+	};
+	let map = visitor.visit_map(&mut access)?;
+
+	let remaining = access.iter.count();
+	if remaining > 0 {
+		return Err(de::Error::invalid_length(
+			access.index + remaining,
+			&format!("{} entries", access.index).as_str(),
+		));
+	}
+
+	Ok(map)
+}
+
+fn visit_str<'de, V>(str: Cow<'de, str>, visitor: V) -> Result<V::Value, Error<'de>>
+where
+	V: de::Visitor<'de>,
+{
+	match str {
+		Cow::Owned(s) => visitor.visit_string(s),
+		Cow::Borrowed(s) => visitor.visit_borrowed_str(s),
+	}
+}
+
+fn visit_bytes<'de, V>(bytes: Cow<'de, [u8]>, visitor: V) -> Result<V::Value, Error<'de>>
+where
+	V: de::Visitor<'de>,
+{
+	match bytes {
+		Cow::Owned(b) => visitor.visit_byte_buf(b),
+		Cow::Borrowed(b) => visitor.visit_borrowed_bytes(b),
+	}
 }
 
 impl<'de> de::Deserializer<'de> for Data<'de> {
 	type Error = Error<'de>;
 
-	fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+	fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error<'de>>
 	where
 		V: de::Visitor<'de>,
 	{
 		match self {
-			Data::Array(data) => visit_array(data.into_iter(), visitor),
-			Data::BulkString(bytes) => match bytes {
-				Cow::Owned(b) => visitor.visit_byte_buf(b),
-				Cow::Borrowed(b) => visitor.visit_borrowed_bytes(b),
-			},
+			Data::Array(data) | Data::Set(data) | Data::Push(data) => {
+				visit_array(data.into_iter(), visitor)
+			}
+			Data::BulkString(bytes) | Data::VerbatimString { text: bytes, .. } => {
+				visit_bytes(bytes, visitor)
+			}
 			Data::Integer(i) => visitor.visit_i64(i),
 			Data::Null => visitor.visit_none(),
-			Data::SimpleString(str) => match str {
-				Cow::Owned(s) => visitor.visit_string(s),
-				Cow::Borrowed(s) => visitor.visit_borrowed_str(s),
-			},
+			Data::SimpleString(str) | Data::BigNumber(str) => visit_str(str, visitor),
+			Data::Map(map) => visit_pairs(map.into_iter(), visitor),
+			Data::Double(d) => visitor.visit_f64(d),
+			Data::Boolean(b) => visitor.visit_bool(b),
+			Data::Attribute { data, .. } => data.deserialize_any(visitor),
+			Data::Error(msg) => Err(Error::Redis(RedisError::new(msg))),
 		}
 	}
 
-	fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+	fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Error<'de>>
 	where
 		V: de::Visitor<'de>,
 	{
@@ -188,10 +518,18 @@ impl<'de> de::Deserializer<'de> for Data<'de> {
 
 		match self {
 			Data::Array(data) => visit_map(data.into_iter(), visitor),
-			Data::BulkString(b) => make_err(de::Unexpected::Bytes(&b)),
+			Data::Map(map) => visit_pairs(map.into_iter(), visitor),
+			Data::Attribute { data, .. } => data.deserialize_map(visitor),
+			Data::Set(_) | Data::Push(_) => make_err(de::Unexpected::Seq),
+			Data::BulkString(b) | Data::VerbatimString { text: b, .. } => {
+				make_err(de::Unexpected::Bytes(&b))
+			}
 			Data::Integer(i) => make_err(de::Unexpected::Signed(i)),
 			Data::Null => make_err(de::Unexpected::Unit),
-			Data::SimpleString(str) => make_err(de::Unexpected::Str(&str)),
+			Data::SimpleString(str) | Data::BigNumber(str) => make_err(de::Unexpected::Str(&str)),
+			Data::Double(d) => make_err(de::Unexpected::Float(d)),
+			Data::Boolean(b) => make_err(de::Unexpected::Bool(b)),
+			Data::Error(msg) => Err(Error::Redis(RedisError::new(msg))),
 		}
 	}
 
@@ -200,27 +538,87 @@ impl<'de> de::Deserializer<'de> for Data<'de> {
 		_name: &'static str,
 		_fields: &'static [&'static str],
 		visitor: V,
-	) -> Result<V::Value, Self::Error>
+	) -> Result<V::Value, Error<'de>>
 	where
 		V: de::Visitor<'de>,
 	{
 		self.deserialize_map(visitor)
 	}
 
-	fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+	fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error<'de>>
 	where
 		V: de::Visitor<'de>,
 	{
 		match self {
 			Self::Null => visitor.visit_none(),
+			Self::Attribute { data, .. } => data.deserialize_option(visitor),
 			_ => visitor.visit_some(self),
 		}
 	}
 
+	fn deserialize_newtype_struct<V>(
+		self,
+		name: &'static str,
+		visitor: V,
+	) -> Result<V::Value, Error<'de>>
+	where
+		V: de::Visitor<'de>,
+	{
+		if name == token::ERROR {
+			return match self {
+				Data::Attribute { data, .. } => data.deserialize_newtype_struct(name, visitor),
+				Data::Error(msg) => visit_str(msg, visitor),
+				data => data.deserialize_any(visitor),
+			};
+		}
+
+		if name != token::DATA && name != token::ELEMENT {
+			return self.deserialize_any(visitor);
+		}
+
+		match Resp3Access::new(self) {
+			Ok(access) => visitor.visit_enum(access),
+			Err(data) => data.deserialize_any(visitor),
+		}
+	}
+
+	fn deserialize_enum<V>(
+		self,
+		name: &'static str,
+		variants: &'static [&'static str],
+		visitor: V,
+	) -> Result<V::Value, Error<'de>>
+	where
+		V: de::Visitor<'de>,
+	{
+		if !is_result(name, variants) {
+			return self.deserialize_any(visitor);
+		}
+
+		match self {
+			Data::Attribute { data, .. } => data.deserialize_enum(name, variants, visitor),
+			Data::Error(msg) => visitor.visit_enum(Resp3Access {
+				variant: RESULT_ERR,
+				content: Data::Error(msg),
+			}),
+			data => visitor.visit_enum(Resp3Access {
+				variant: RESULT_OK,
+				content: data,
+			}),
+		}
+	}
+
+	fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Error<'de>>
+	where
+		V: de::Visitor<'de>,
+	{
+		self.deserialize_any(visitor)
+	}
+
 	forward_to_deserialize_any! {
 		bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
-		bytes byte_buf unit unit_struct newtype_struct seq tuple
-		tuple_struct enum identifier ignored_any
+		bytes byte_buf unit unit_struct seq tuple
+		tuple_struct identifier
 	}
 }
 
@@ -234,7 +632,9 @@ impl<'de> de::IntoDeserializer<'de, Error<'de>> for Data<'de> {
 
 #[cfg(test)]
 mod test {
-	use crate::{array, Data};
+	use std::collections::HashMap;
+
+	use crate::{array, to_data, Data, Error, RedisError};
 
 	use super::from_data;
 
@@ -261,4 +661,65 @@ mod test {
 		let res = from_data::<isize>(Data::Integer(42)).unwrap();
 		assert_eq!(res, 42);
 	}
+
+	#[test]
+	fn to_map() {
+		let data = Data::Map(vec![(Data::simple_string("foo"), Data::Boolean(true))]);
+		let res = from_data::<HashMap<&str, bool>>(data).unwrap();
+		assert_eq!(res, HashMap::from([("foo", true)]));
+	}
+
+	#[test]
+	fn to_data_resp3() {
+		let data = array!(
+			Data::Set(vec![Data::Double(1.5)]),
+			Data::Push(vec![Data::BigNumber("1".into())]),
+			Data::VerbatimString {
+				format: *b"mkd",
+				text: b"# foo"[..].into()
+			},
+			Data::Attribute {
+				attributes: vec![(Data::simple_string("foo"), Data::Null)],
+				data: Box::new(Data::Map(vec![])),
+			}
+		);
+
+		let res = from_data::<Data>(data.clone()).unwrap();
+		assert_eq!(res, data);
+
+		let res = to_data(&data).unwrap();
+		assert_eq!(res, data);
+	}
+
+	#[test]
+	fn to_result() {
+		let data = array!(Data::Integer(1), Data::Error("ERR".into()));
+
+		let res = from_data::<Vec<Result<i64, RedisError>>>(data.clone()).unwrap();
+		assert_eq!(res, [Ok(1), Err(RedisError::new("ERR"))]);
+
+		let res = from_data::<Data>(data.clone()).unwrap();
+		assert_eq!(res, data);
+
+		assert!(from_data::<Vec<i64>>(data).is_err());
+	}
+
+	#[test]
+	fn to_error_path() {
+		let data = array!(Data::Map(vec![(
+			Data::simple_string("a/b"),
+			array!(1, Data::simple_string("foo"))
+		)]));
+
+		let err = from_data::<Vec<HashMap<String, Vec<i64>>>>(data.clone()).unwrap_err();
+		assert_eq!(err.path().unwrap().to_string(), "/0/a~1b/1");
+		assert_eq!(
+			data.pointer(&err.path().unwrap().to_string()),
+			Some(&Data::simple_string("foo"))
+		);
+
+		let data = array!(Data::Error("ERR".into()));
+		let err = from_data::<Vec<i64>>(data).unwrap_err();
+		assert!(matches!(err, Error::Redis(_)));
+	}
 }
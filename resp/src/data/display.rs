@@ -0,0 +1,204 @@
+use alloc::{
+	format,
+	string::{String, ToString},
+};
+use core::fmt::{self, Display, Formatter, Write};
+
+use crate::float::Float;
+
+use super::Data;
+
+/// Formats data like `redis-cli`, with numbered aggregates, quoted and escaped bulk strings and
+/// types such as `(integer)` and `(nil)`.
+impl Display for Data<'_> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		write_tty(f, self, "")
+	}
+}
+
+/// Formats data like `redis-cli --raw`, from [Data::display_raw].
+///
+/// Strings are written without quotes or escapes, and the elements of aggregates are written on
+/// separate lines.
+#[derive(Debug, Clone, Copy)]
+pub struct RawDisplay<'b, 'a>(pub(super) &'b Data<'a>);
+
+impl Display for RawDisplay<'_, '_> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		write_raw(f, self.0)
+	}
+}
+
+fn write_tty(f: &mut Formatter<'_>, data: &Data<'_>, prefix: &str) -> fmt::Result {
+	match data {
+		Data::SimpleString(str) => f.write_str(str),
+		Data::BulkString(bytes) => write_repr(f, bytes),
+		Data::VerbatimString { text, .. } => f.write_str(&String::from_utf8_lossy(text)),
+		Data::Integer(i) => write!(f, "(integer) {}", i),
+		Data::Double(d) => write!(f, "(double) {}", Float::new(*d, None)),
+		Data::Boolean(b) => write!(f, "({})", b),
+		Data::BigNumber(num) => write!(f, "(big number) {}", num),
+		Data::Null => f.write_str("(nil)"),
+		Data::Error(msg) => write!(f, "(error) {}", msg),
+		Data::Array(elements) if elements.is_empty() => f.write_str("(empty array)"),
+		Data::Set(elements) if elements.is_empty() => f.write_str("(empty set)"),
+		Data::Push(elements) if elements.is_empty() => f.write_str("(empty push)"),
+		Data::Map(pairs) if pairs.is_empty() => f.write_str("(empty hash)"),
+		Data::Array(elements) | Data::Push(elements) => write_entries(
+			f,
+			prefix,
+			')',
+			elements.len(),
+			elements.iter().map(|e| (e, None)),
+		),
+		Data::Set(elements) => write_entries(
+			f,
+			prefix,
+			'~',
+			elements.len(),
+			elements.iter().map(|e| (e, None)),
+		),
+		// redis-cli sizes the index of maps as if keys and values were counted separately
+		Data::Map(pairs) => write_entries(
+			f,
+			prefix,
+			'#',
+			pairs.len() * 2,
+			pairs.iter().map(|(k, v)| (k, Some(v))),
+		),
+		Data::Attribute { data, .. } => write_tty(f, data, prefix),
+	}
+}
+
+/// Write numbered entries, with the entries after the first on new lines starting with `prefix`.
+fn write_entries<'b, 'a: 'b>(
+	f: &mut Formatter<'_>,
+	prefix: &str,
+	sep: char,
+	len: usize,
+	entries: impl Iterator<Item = (&'b Data<'a>, Option<&'b Data<'a>>)>,
+) -> fmt::Result {
+	let width = len.to_string().len();
+	let nested = format!("{}{:2$}", prefix, "", width + 2);
+
+	for (i, (key, value)) in entries.enumerate() {
+		if i > 0 {
+			write!(f, "\n{}", prefix)?;
+		}
+
+		write!(f, "{:>2$}{} ", i + 1, sep, width)?;
+		write_tty(f, key, &nested)?;
+
+		if let Some(value) = value {
+			f.write_str(" => ")?;
+			write_tty(f, value, &nested)?;
+		}
+	}
+
+	Ok(())
+}
+
+/// Write a quoted string, escaping unprintable bytes like `redis-cli`.
+fn write_repr(f: &mut Formatter<'_>, bytes: &[u8]) -> fmt::Result {
+	f.write_char('"')?;
+	for &b in bytes {
+		match b {
+			b'\\' => f.write_str("\\\\")?,
+			b'"' => f.write_str("\\\"")?,
+			b'\n' => f.write_str("\\n")?,
+			b'\r' => f.write_str("\\r")?,
+			b'\t' => f.write_str("\\t")?,
+			0x07 => f.write_str("\\a")?,
+			0x08 => f.write_str("\\b")?,
+			b' '..=b'~' => f.write_char(b as char)?,
+			b => write!(f, "\\x{:02x}", b)?,
+		}
+	}
+	f.write_char('"')
+}
+
+fn write_raw(f: &mut Formatter<'_>, data: &Data<'_>) -> fmt::Result {
+	match data {
+		Data::SimpleString(str) | Data::BigNumber(str) | Data::Error(str) => f.write_str(str),
+		Data::BulkString(bytes) | Data::VerbatimString { text: bytes, .. } => {
+			f.write_str(&String::from_utf8_lossy(bytes))
+		}
+		Data::Integer(i) => write!(f, "{}", i),
+		Data::Double(d) => write!(f, "{}", Float::new(*d, None)),
+		Data::Boolean(b) => write!(f, "({})", b),
+		Data::Null => Ok(()),
+		Data::Array(elements) | Data::Set(elements) | Data::Push(elements) => {
+			for (i, element) in elements.iter().enumerate() {
+				if i > 0 {
+					f.write_char('\n')?;
+				}
+				write_raw(f, element)?;
+			}
+			Ok(())
+		}
+		Data::Map(pairs) => {
+			for (i, (key, value)) in pairs.iter().enumerate() {
+				if i > 0 {
+					f.write_char('\n')?;
+				}
+				write_raw(f, key)?;
+				f.write_char('\n')?;
+				write_raw(f, value)?;
+			}
+			Ok(())
+		}
+		Data::Attribute { data, .. } => write_raw(f, data),
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::{array, Data};
+
+	#[test]
+	fn test_display() {
+		assert_eq!(Data::bulk_string("foo").to_string(), "\"foo\"");
+		assert_eq!(
+			Data::bulk_string(b"a\"\\\n\x00\xff").to_string(),
+			r#""a\"\\\n\x00\xff""#
+		);
+		assert_eq!(Data::simple_string("OK").to_string(), "OK");
+		assert_eq!(Data::Integer(5).to_string(), "(integer) 5");
+		assert_eq!(Data::Null.to_string(), "(nil)");
+		assert_eq!(Data::Error("ERR foo".into()).to_string(), "(error) ERR foo");
+		assert_eq!(array!().to_string(), "(empty array)");
+	}
+
+	#[test]
+	fn test_display_nested() {
+		let data = array!(
+			Data::bulk_string("foo"),
+			array!(Data::bulk_string("a"), array!(), Data::Null),
+			5
+		);
+		assert_eq!(
+			data.to_string(),
+			"1) \"foo\"\n2) 1) \"a\"\n   2) (empty array)\n   3) (nil)\n3) (integer) 5"
+		);
+
+		let data = Data::Array((1..=10).map(Data::Integer).collect());
+		let expected = data.to_string();
+		assert!(expected.starts_with(" 1) (integer) 1\n 2)"));
+		assert!(expected.ends_with("\n10) (integer) 10"));
+
+		let data = Data::Map(vec![
+			(Data::simple_string("a"), array!(1, 2)),
+			(Data::simple_string("b"), Data::Boolean(true)),
+		]);
+		assert_eq!(
+			data.to_string(),
+			"1# a => 1) (integer) 1\n   2) (integer) 2\n2# b => (true)"
+		);
+	}
+
+	#[test]
+	fn test_display_raw() {
+		let data = array!("foo", array!(1, Data::Null), Data::simple_string("OK"));
+		assert_eq!(data.display_raw().to_string(), "foo\n1\n\nOK");
+	}
+}
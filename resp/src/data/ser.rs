@@ -1,8 +1,18 @@
-use std::{borrow::Cow, num::TryFromIntError};
-
+use alloc::{
+	borrow::{Cow, ToOwned},
+	boxed::Box,
+	string::ToString,
+	vec,
+	vec::Vec,
+};
+use core::num::TryFromIntError;
+
+use itertools::Itertools;
 use serde::{ser, Serialize};
 
-use crate::{array, Data, Error};
+use crate::{array, float::Float, Data, Error};
+
+use super::token;
 
 /// Serialize `T` into [Data].
 pub fn to_data<T>(value: &T) -> Result<Data<'static>, Error<'static>>
@@ -23,10 +33,68 @@ impl<'a> ser::Serialize for Data<'a> {
 			Data::BulkString(bytes) => serde_bytes::serialize(bytes, serializer),
 			Data::Array(arr) => arr.serialize(serializer),
 			Data::Null => serializer.serialize_unit(),
+			Data::Map(map) => serializer.serialize_newtype_struct(token::MAP, &Pairs(map)),
+			Data::Set(set) => serializer.serialize_newtype_struct(token::SET, set),
+			Data::Double(double) => serializer.serialize_newtype_struct(token::DOUBLE, double),
+			Data::Boolean(bool) => serializer.serialize_newtype_struct(token::BOOLEAN, bool),
+			Data::BigNumber(num) => serializer.serialize_newtype_struct(token::BIG_NUMBER, num),
+			Data::VerbatimString { format, text } => serializer.serialize_newtype_struct(
+				token::VERBATIM_STRING,
+				serde_bytes::Bytes::new(&[&format[..], b":", text].concat()),
+			),
+			Data::Attribute { attributes, data } => {
+				serializer.serialize_newtype_struct(token::ATTRIBUTE, &(Pairs(attributes), data))
+			}
+			Data::Push(push) => serializer.serialize_newtype_struct(token::PUSH, push),
+			Data::Error(msg) => serializer.serialize_newtype_struct(token::ERROR, msg),
 		}
 	}
 }
 
+/// Key-value pairs, serialized as a map.
+struct Pairs<'a, 'b>(&'b [(Data<'a>, Data<'a>)]);
+
+impl ser::Serialize for Pairs<'_, '_> {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: serde::Serializer,
+	{
+		serializer.collect_map(self.0.iter().map(|(k, v)| (k, v)))
+	}
+}
+
+/// Restore the RESP3 type of `data`, which was serialized inside a newtype struct named `name`.
+fn from_token(name: &str, data: Data<'static>) -> Result<Data<'static>, Error<'static>> {
+	Ok(match (name, data) {
+		(token::MAP, Data::Array(arr)) => Data::Map(arr.into_iter().tuples().collect()),
+		(token::SET, Data::Array(arr)) => Data::Set(arr),
+		(token::PUSH, Data::Array(arr)) => Data::Push(arr),
+		(token::DOUBLE, Data::SimpleString(str)) => {
+			Data::Double(str.parse().map_err(<Error as ser::Error>::custom)?)
+		}
+		(token::BOOLEAN, Data::SimpleString(str)) => {
+			Data::Boolean(str.parse().map_err(<Error as ser::Error>::custom)?)
+		}
+		(token::BIG_NUMBER, Data::SimpleString(str)) => Data::BigNumber(str),
+		(token::ERROR, Data::SimpleString(msg)) => Data::Error(msg),
+		(token::VERBATIM_STRING, Data::BulkString(bytes)) => match &*bytes {
+			[a, b, c, b':', text @ ..] => Data::VerbatimString {
+				format: [*a, *b, *c],
+				text: text.to_vec().into(),
+			},
+			_ => return Err(ser::Error::custom("invalid verbatim string format")),
+		},
+		(token::ATTRIBUTE, Data::Array(arr)) => match <[Data; 2]>::try_from(arr) {
+			Ok([Data::Array(attributes), data]) => Data::Attribute {
+				attributes: attributes.into_iter().tuples().collect(),
+				data: Box::new(data),
+			},
+			_ => return Err(ser::Error::custom("invalid attribute")),
+		},
+		(_, data) => data,
+	})
+}
+
 struct Serializer;
 
 impl ser::Serializer for Serializer {
@@ -87,11 +155,15 @@ impl ser::Serializer for Serializer {
 	}
 
 	fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
-		Ok(Data::SimpleString(Cow::Owned(v.to_string())))
+		Ok(Data::SimpleString(Cow::Owned(
+			Float::new(v, None).to_string(),
+		)))
 	}
 
 	fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
-		Ok(Data::SimpleString(Cow::Owned(v.to_string())))
+		Ok(Data::SimpleString(Cow::Owned(
+			Float::new(v, None).to_string(),
+		)))
 	}
 
 	fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
@@ -110,9 +182,9 @@ impl ser::Serializer for Serializer {
 		Ok(Data::Null)
 	}
 
-	fn serialize_some<T: ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error>
+	fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
 	where
-		T: Serialize,
+		T: ?Sized + Serialize,
 	{
 		value.serialize(self)
 	}
@@ -134,18 +206,18 @@ impl ser::Serializer for Serializer {
 		self.serialize_str(variant)
 	}
 
-	fn serialize_newtype_struct<T: ?Sized>(
+	fn serialize_newtype_struct<T>(
 		self,
-		_name: &'static str,
+		name: &'static str,
 		value: &T,
 	) -> Result<Self::Ok, Self::Error>
 	where
-		T: Serialize,
+		T: ?Sized + Serialize,
 	{
-		value.serialize(self)
+		from_token(name, value.serialize(self)?)
 	}
 
-	fn serialize_newtype_variant<T: ?Sized>(
+	fn serialize_newtype_variant<T>(
 		self,
 		_name: &'static str,
 		_variant_index: u32,
@@ -153,7 +225,7 @@ impl ser::Serializer for Serializer {
 		value: &T,
 	) -> Result<Self::Ok, Self::Error>
 	where
-		T: Serialize,
+		T: ?Sized + Serialize,
 	{
 		Ok(array!(Data::simple_string(variant), value.serialize(self)?))
 	}
@@ -224,9 +296,9 @@ impl ser::SerializeSeq for SerializeVec {
 
 	type Error = Error<'static>;
 
-	fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+	fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
 	where
-		T: Serialize,
+		T: ?Sized + Serialize,
 	{
 		self.vec.push(value.serialize(Serializer)?);
 		Ok(())
@@ -242,9 +314,9 @@ impl ser::SerializeTuple for SerializeVec {
 
 	type Error = Error<'static>;
 
-	fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+	fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
 	where
-		T: Serialize,
+		T: ?Sized + Serialize,
 	{
 		self.vec.push(value.serialize(Serializer)?);
 		Ok(())
@@ -260,9 +332,9 @@ impl ser::SerializeTupleStruct for SerializeVec {
 
 	type Error = Error<'static>;
 
-	fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+	fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
 	where
-		T: Serialize,
+		T: ?Sized + Serialize,
 	{
 		self.vec.push(value.serialize(Serializer)?);
 		Ok(())
@@ -278,17 +350,17 @@ impl ser::SerializeMap for SerializeVec {
 
 	type Error = Error<'static>;
 
-	fn serialize_key<T: ?Sized>(&mut self, key: &T) -> Result<(), Self::Error>
+	fn serialize_key<T>(&mut self, key: &T) -> Result<(), Self::Error>
 	where
-		T: Serialize,
+		T: ?Sized + Serialize,
 	{
 		self.vec.push(key.serialize(Serializer)?);
 		Ok(())
 	}
 
-	fn serialize_value<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+	fn serialize_value<T>(&mut self, value: &T) -> Result<(), Self::Error>
 	where
-		T: Serialize,
+		T: ?Sized + Serialize,
 	{
 		self.vec.push(value.serialize(Serializer)?);
 		Ok(())
@@ -304,13 +376,9 @@ impl ser::SerializeStruct for SerializeVec {
 
 	type Error = Error<'static>;
 
-	fn serialize_field<T: ?Sized>(
-		&mut self,
-		key: &'static str,
-		value: &T,
-	) -> Result<(), Self::Error>
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
 	where
-		T: Serialize,
+		T: ?Sized + Serialize,
 	{
 		self.vec.push(Data::simple_string(key));
 		self.vec.push(value.serialize(Serializer)?);
@@ -332,9 +400,9 @@ impl ser::SerializeTupleVariant for SerializeVariantVec {
 
 	type Error = Error<'static>;
 
-	fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+	fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
 	where
-		T: Serialize,
+		T: ?Sized + Serialize,
 	{
 		self.vec.push(value.serialize(Serializer)?);
 		Ok(())
@@ -352,13 +420,9 @@ impl ser::SerializeStructVariant for SerializeVariantVec {
 
 	type Error = Error<'static>;
 
-	fn serialize_field<T: ?Sized>(
-		&mut self,
-		key: &'static str,
-		value: &T,
-	) -> Result<(), Self::Error>
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
 	where
-		T: Serialize,
+		T: ?Sized + Serialize,
 	{
 		self.vec.push(Data::simple_string(key));
 		self.vec.push(value.serialize(Serializer)?);
@@ -0,0 +1,26 @@
+/// Carries a [Data](crate::Data) through a deserializer. RESP deserializers respond to this by
+/// passing RESP3 types without an equivalent in the serde data model as enum variants, indexed by
+/// the `*_VARIANT` constants.
+pub const DATA: &str = "$redust_resp::Data";
+/// Like [DATA], for an element of an aggregate. Errors are passed as [ERROR_VARIANT] instead of
+/// failing the whole value.
+pub const ELEMENT: &str = "$redust_resp::Element";
+
+pub const SET_VARIANT: u32 = 0;
+pub const PUSH_VARIANT: u32 = 1;
+pub const BIG_NUMBER_VARIANT: u32 = 2;
+pub const VERBATIM_STRING_VARIANT: u32 = 3;
+pub const ATTRIBUTE_VARIANT: u32 = 4;
+pub const ERROR_VARIANT: u32 = 5;
+
+// Carry RESP3 types through a serializer. Each wraps a value in the serde data model which a RESP
+// serializer writes with the corresponding RESP3 prefix.
+pub const MAP: &str = "$redust_resp::Map";
+pub const SET: &str = "$redust_resp::Set";
+pub const PUSH: &str = "$redust_resp::Push";
+pub const DOUBLE: &str = "$redust_resp::Double";
+pub const BOOLEAN: &str = "$redust_resp::Boolean";
+pub const BIG_NUMBER: &str = "$redust_resp::BigNumber";
+pub const VERBATIM_STRING: &str = "$redust_resp::VerbatimString";
+pub const ATTRIBUTE: &str = "$redust_resp::Attribute";
+pub const ERROR: &str = "$redust_resp::Error";
@@ -1,53 +1,108 @@
-use std::{
-	borrow::Cow,
-	fmt::{Debug, Display},
-};
+use alloc::{borrow::Cow, string::String};
+use core::fmt::{Debug, Display};
 
-use serde::{de, Deserialize};
+use serde::Deserialize;
 
 mod accessor;
 mod deserializer;
+mod frames;
 
 pub(crate) use accessor::*;
 pub use deserializer::*;
+pub use frames::*;
 
 use crate::Error;
 
 /// An error occurred while reading bytes.
+///
+/// When displayed, the error includes its offset and the bytes around it in hex.
 pub struct ReadError<'a> {
 	/// The error which occurred.
 	pub data: Error<'a>,
-	/// Bytes remaining to be read.
+	/// Bytes remaining to be read, starting at the value which caused the error if it was inside
+	/// an aggregate.
 	pub remaining: Cow<'a, [u8]>,
+	/// The offset of the remaining bytes in the input.
+	pub offset: usize,
+	/// A few bytes before the offset, for context.
+	preceding: Cow<'a, [u8]>,
 }
 
-impl ReadError<'_> {
+/// The number of bytes either side of the offset of a [ReadError] to display.
+const EXCERPT_LEN: usize = 8;
+
+impl<'a> ReadError<'a> {
+	fn new(data: Error<'a>, input: &'a [u8], remaining: &'a [u8]) -> Self {
+		let offset = input.len() - remaining.len();
+
+		Self {
+			data,
+			remaining: remaining.into(),
+			offset,
+			preceding: input[offset.saturating_sub(EXCERPT_LEN)..offset].into(),
+		}
+	}
+
+	/// Make an error at `offset`, without any of the input around it.
+	#[cfg(feature = "std")]
+	pub(crate) fn at(data: Error<'static>, offset: usize) -> ReadError<'static> {
+		ReadError {
+			data,
+			remaining: Cow::Borrowed(&[]),
+			offset,
+			preceding: Cow::Borrowed(&[]),
+		}
+	}
+
 	/// Convert this error into an owned error.
 	pub fn into_owned(self) -> ReadError<'static> {
 		ReadError {
 			data: self.data.into_owned(),
 			remaining: self.remaining.into_owned().into(),
+			offset: self.offset,
+			preceding: self.preceding.into_owned().into(),
 		}
 	}
 }
 
 impl Debug for ReadError<'_> {
-	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
 		f.debug_struct("ReadError")
 			.field("data", &self.data)
 			.field("remaining", &String::from_utf8_lossy(&self.remaining))
+			.field("offset", &self.offset)
 			.finish()
 	}
 }
 
 impl Display for ReadError<'_> {
-	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		write!(f, "{}", self.data)
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		write!(f, "{} at byte {}:", self.data, self.offset)?;
+
+		if self.preceding.len() == EXCERPT_LEN && self.offset > EXCERPT_LEN {
+			f.write_str(" ..")?;
+		}
+		for b in self.preceding.iter() {
+			write!(f, " {:02x}", b)?;
+		}
+
+		f.write_str(" >")?;
+		for (i, b) in self.remaining.iter().take(EXCERPT_LEN).enumerate() {
+			if i > 0 {
+				f.write_str(" ")?;
+			}
+			write!(f, "{:02x}", b)?;
+		}
+		if self.remaining.len() > EXCERPT_LEN {
+			f.write_str(" ..")?;
+		}
+
+		Ok(())
 	}
 }
 
-impl std::error::Error for ReadError<'_> {
-	fn cause(&self) -> Option<&dyn de::StdError> {
+impl core::error::Error for ReadError<'_> {
+	fn cause(&self) -> Option<&dyn core::error::Error> {
 		Some(&self.data)
 	}
 }
@@ -58,10 +113,7 @@ pub fn from_bytes<'de, T: Deserialize<'de>>(
 	data: &'de [u8],
 ) -> Result<(T, &'de [u8]), ReadError<'de>> {
 	let mut de = Deserializer { input: data };
-	let res = T::deserialize(&mut de).map_err(|e| ReadError {
-		data: e,
-		remaining: de.input.into(),
-	})?;
+	let res = T::deserialize(&mut de).map_err(|e| ReadError::new(e, data, de.input))?;
 	Ok((res, de.input))
 }
 
@@ -69,9 +121,10 @@ pub fn from_bytes<'de, T: Deserialize<'de>>(
 mod test {
 	use std::collections::HashMap;
 
+	use serde::{de::IgnoredAny, Deserialize};
 	use serde_bytes::Bytes;
 
-	use crate::{array, from_bytes, Data, Error};
+	use crate::{array, from_bytes, Data, Error, RedisError};
 
 	#[test]
 	fn de_int() {
@@ -79,7 +132,7 @@ mod test {
 		let (res, rem) = from_bytes::<u8>(data).unwrap();
 
 		assert_eq!(res, 1);
-		assert_eq!(rem, []);
+		assert_eq!(rem, b"");
 	}
 
 	#[test]
@@ -88,7 +141,7 @@ mod test {
 		let (res, rem) = from_bytes::<&str>(data).unwrap();
 
 		assert_eq!(res, "foo");
-		assert_eq!(rem, []);
+		assert_eq!(rem, b"");
 	}
 
 	#[test]
@@ -108,7 +161,7 @@ mod test {
 		let (res, rem) = from_bytes::<&[u8]>(data).unwrap();
 
 		assert_eq!(res, b"foo");
-		assert_eq!(rem, []);
+		assert_eq!(rem, b"");
 	}
 
 	#[test]
@@ -117,7 +170,7 @@ mod test {
 		let (res, rem) = from_bytes::<Option<&[u8]>>(data).unwrap();
 
 		assert_eq!(res, None);
-		assert_eq!(rem, []);
+		assert_eq!(rem, b"");
 	}
 
 	#[test]
@@ -126,7 +179,7 @@ mod test {
 		let (res, rem) = from_bytes::<Vec<String>>(data).unwrap();
 
 		assert_eq!(res, ["foo"]);
-		assert_eq!(rem, []);
+		assert_eq!(rem, b"");
 	}
 
 	#[test]
@@ -135,7 +188,7 @@ mod test {
 		let (res, rem) = from_bytes::<Option<Vec<i64>>>(data).unwrap();
 
 		assert_eq!(res, None);
-		assert_eq!(rem, []);
+		assert_eq!(rem, b"");
 	}
 
 	#[test]
@@ -144,7 +197,7 @@ mod test {
 		let (res, rem) = from_bytes::<(&str, Vec<&[u8]>)>(data).unwrap();
 
 		assert_eq!(res, ("foo", [&b"bar"[..]].to_vec()));
-		assert_eq!(rem, []);
+		assert_eq!(rem, b"");
 	}
 
 	#[test]
@@ -153,7 +206,7 @@ mod test {
 		let (res, rem) = from_bytes::<(&Bytes, &Bytes, usize)>(data).unwrap();
 
 		assert_eq!(res, (Bytes::new(b"subscribe"), Bytes::new(b"foo"), 1));
-		assert_eq!(rem, []);
+		assert_eq!(rem, b"");
 	}
 
 	#[test]
@@ -165,7 +218,7 @@ mod test {
 		exp.insert("foo", vec![Bytes::new(b"bar")]);
 
 		assert_eq!(res, exp);
-		assert_eq!(rem, []);
+		assert_eq!(rem, b"");
 	}
 
 	#[test]
@@ -174,7 +227,7 @@ mod test {
 		let (data, rem) = from_bytes::<Data>(bytes).unwrap();
 
 		assert_eq!(data, Data::SimpleString("OK".into()));
-		assert_eq!(rem, []);
+		assert_eq!(rem, b"");
 	}
 
 	#[test]
@@ -188,13 +241,64 @@ mod test {
 		}
 	}
 
+	#[test]
+	fn de_data_nested_err() {
+		let bytes = b"*3\r\n+OK\r\n-WRONGTYPE wrong\r\n%1\r\n+foo\r\n!3\r\nERR\r\n";
+		let (data, rem) = from_bytes::<Data>(bytes).unwrap();
+
+		assert_eq!(
+			data,
+			array!(
+				Data::simple_string("OK"),
+				Data::Error("WRONGTYPE wrong".into()),
+				Data::Map(vec![(
+					Data::simple_string("foo"),
+					Data::Error("ERR".into())
+				)])
+			)
+		);
+		assert_eq!(rem, b"");
+	}
+
+	#[test]
+	fn de_result() {
+		let bytes = b"*3\r\n:1\r\n-WRONGTYPE wrong\r\n$1\r\n2\r\n";
+		let (res, rem) = from_bytes::<Vec<Result<i64, RedisError>>>(bytes).unwrap();
+
+		assert_eq!(res, [Ok(1), Err(RedisError::new("WRONGTYPE wrong")), Ok(2)]);
+		assert_eq!(rem, b"");
+
+		let (res, _) = from_bytes::<Result<&str, RedisError>>(b"!3\r\nERR\r\n").unwrap();
+		assert_eq!(res, Err(RedisError::new("ERR")));
+
+		let err = from_bytes::<Vec<i64>>(bytes).unwrap_err();
+		assert!(matches!(err.data, Error::Redis(msg) if msg == "WRONGTYPE wrong"));
+
+		let err = from_bytes::<Vec<IgnoredAny>>(bytes).unwrap_err();
+		assert!(matches!(err.data, Error::Redis(msg) if msg == "WRONGTYPE wrong"));
+	}
+
+	#[test]
+	fn de_result_named_enum() {
+		// only RedisError can be deserialized from an error
+		#[derive(Debug, Deserialize)]
+		#[allow(dead_code)]
+		enum Result {
+			Ok(i64),
+			Err(String),
+		}
+
+		let err = from_bytes::<Result>(b"-ERR\r\n").unwrap_err();
+		assert!(matches!(err.data, Error::Redis(msg) if msg == "ERR"));
+	}
+
 	#[test]
 	fn de_data_int() {
 		let bytes = b":123\r\n";
 		let (data, rem) = from_bytes::<Data>(bytes).unwrap();
 
 		assert_eq!(data, Data::Integer(123));
-		assert_eq!(rem, []);
+		assert_eq!(rem, b"");
 	}
 
 	#[test]
@@ -203,7 +307,7 @@ mod test {
 		let (data, rem) = from_bytes::<Data>(bytes).unwrap();
 
 		assert_eq!(data, Data::bulk_string("foo"));
-		assert_eq!(rem, []);
+		assert_eq!(rem, b"");
 	}
 
 	#[test]
@@ -215,7 +319,7 @@ mod test {
 			data,
 			array!(Data::bulk_string("hello"), Data::bulk_string("world"))
 		);
-		assert_eq!(rem, []);
+		assert_eq!(rem, b"");
 	}
 
 	#[test]
@@ -224,7 +328,7 @@ mod test {
 		let (res, rem) = from_bytes::<Data>(data).unwrap();
 
 		assert_eq!(res, ());
-		assert_eq!(rem, []);
+		assert_eq!(rem, b"");
 	}
 
 	#[test]
@@ -233,6 +337,218 @@ mod test {
 		let (res, rem) = from_bytes::<Option<Data>>(data).unwrap();
 
 		assert_eq!(res, Some(array!()));
-		assert_eq!(rem, []);
+		assert_eq!(rem, b"");
+	}
+
+	#[test]
+	fn de_data_map() {
+		let bytes = b"%2\r\n+first\r\n:1\r\n+second\r\n:2\r\n";
+		let (data, rem) = from_bytes::<Data>(bytes).unwrap();
+
+		assert_eq!(
+			data,
+			Data::Map(vec![
+				(Data::simple_string("first"), Data::Integer(1)),
+				(Data::simple_string("second"), Data::Integer(2)),
+			])
+		);
+		assert_eq!(rem, b"");
+	}
+
+	#[test]
+	fn de_map_resp3() {
+		let data = b"%2\r\n+first\r\n:1\r\n+second\r\n:2\r\n";
+		let (res, rem) = from_bytes::<HashMap<&str, i64>>(data).unwrap();
+
+		assert_eq!(res, HashMap::from([("first", 1), ("second", 2)]));
+		assert_eq!(rem, b"");
+	}
+
+	#[test]
+	fn de_data_set() {
+		let bytes = b"~2\r\n+foo\r\n:1\r\n";
+		let (data, rem) = from_bytes::<Data>(bytes).unwrap();
+
+		assert_eq!(
+			data,
+			Data::Set(vec![Data::simple_string("foo"), Data::Integer(1)])
+		);
+		assert_eq!(rem, b"");
+	}
+
+	#[test]
+	fn de_set() {
+		let data = b"~2\r\n$3\r\nfoo\r\n$3\r\nbar\r\n";
+		let (res, rem) = from_bytes::<Vec<&str>>(data).unwrap();
+
+		assert_eq!(res, ["foo", "bar"]);
+		assert_eq!(rem, b"");
+	}
+
+	#[test]
+	fn de_data_push() {
+		let bytes = b">3\r\n$7\r\nmessage\r\n$3\r\nfoo\r\n$3\r\nbar\r\n";
+		let (data, rem) = from_bytes::<Data>(bytes).unwrap();
+
+		assert_eq!(
+			data,
+			Data::Push(vec![
+				Data::bulk_string("message"),
+				Data::bulk_string("foo"),
+				Data::bulk_string("bar")
+			])
+		);
+		assert_eq!(rem, b"");
+	}
+
+	#[test]
+	fn de_data_scalars() {
+		let bytes = b"*5\r\n,1.5\r\n#t\r\n_\r\n(12345678901234567890\r\n=7\r\ntxt:foo\r\n";
+		let (data, rem) = from_bytes::<Data>(bytes).unwrap();
+
+		assert_eq!(
+			data,
+			array!(
+				Data::Double(1.5),
+				Data::Boolean(true),
+				Data::Null,
+				Data::BigNumber("12345678901234567890".into()),
+				Data::VerbatimString {
+					format: *b"txt",
+					text: b"foo"[..].into()
+				}
+			)
+		);
+		assert_eq!(rem, b"");
+	}
+
+	#[test]
+	fn de_scalars() {
+		let bytes = b"*5\r\n,inf\r\n#f\r\n_\r\n(-1\r\n=7\r\ntxt:foo\r\n";
+		let (res, rem) = from_bytes::<(f64, bool, Option<i64>, i64, &str)>(bytes).unwrap();
+
+		assert_eq!(res, (f64::INFINITY, false, None, -1, "foo"));
+		assert_eq!(rem, b"");
+	}
+
+	#[test]
+	fn de_floats() {
+		let bytes =
+			b"*5\r\n$3\r\ninf\r\n+-inf\r\n$4\r\n+inf\r\n,-nan\r\n$20\r\n13.36138933897018433\r\n";
+		let (res, rem) = from_bytes::<(f64, f64, f32, f64, f64)>(bytes).unwrap();
+
+		assert_eq!(res.0, f64::INFINITY);
+		assert_eq!(res.1, f64::NEG_INFINITY);
+		assert_eq!(res.2, f32::INFINITY);
+		assert!(res.3.is_nan());
+		assert_eq!(res.4, 13.361389338970184);
+		assert_eq!(rem, b"");
+	}
+
+	#[test]
+	fn de_data_attribute() {
+		let bytes = b"|1\r\n+ttl\r\n:3600\r\n*1\r\n:1\r\n";
+		let (data, rem) = from_bytes::<Data>(bytes).unwrap();
+
+		assert_eq!(
+			data,
+			Data::Attribute {
+				attributes: vec![(Data::simple_string("ttl"), Data::Integer(3600))],
+				data: Box::new(array!(1)),
+			}
+		);
+		assert_eq!(rem, b"");
+	}
+
+	#[test]
+	fn de_attribute() {
+		let bytes = b"|1\r\n+ttl\r\n:3600\r\n*1\r\n:1\r\n";
+		let (res, rem) = from_bytes::<Vec<i64>>(bytes).unwrap();
+
+		assert_eq!(res, [1]);
+		assert_eq!(rem, b"");
+	}
+
+	#[test]
+	fn de_blob_error() {
+		let bytes = b"!21\r\nSYNTAX invalid syntax\r\n";
+		let err = from_bytes::<Data>(bytes).unwrap_err();
+
+		match err.data {
+			Error::Redis(msg) if msg == "SYNTAX invalid syntax" => {}
+			_ => panic!(),
+		}
+	}
+
+	#[test]
+	fn de_streamed_str() {
+		let data = b"$?\r\n;3\r\nfoo\r\n;3\r\nbar\r\n;0\r\n";
+		let (res, rem) = from_bytes::<String>(data).unwrap();
+
+		assert_eq!(res, "foobar");
+		assert_eq!(rem, b"");
+	}
+
+	#[test]
+	fn de_data_streamed() {
+		let bytes = b"*?\r\n$?\r\n;3\r\nfoo\r\n;0\r\n%?\r\n+a\r\n:1\r\n.\r\n~?\r\n.\r\n.\r\n";
+		let (data, rem) = from_bytes::<Data>(bytes).unwrap();
+
+		assert_eq!(
+			data,
+			array!(
+				Data::bulk_string("foo"),
+				Data::Map(vec![(Data::simple_string("a"), Data::Integer(1))]),
+				Data::Set(vec![])
+			)
+		);
+		assert_eq!(rem, b"");
+	}
+
+	#[test]
+	fn de_streamed_tuple() {
+		let data = b"*?\r\n:1\r\n+foo\r\n.\r\n:2\r\n";
+		let (res, rem) = from_bytes::<(i64, &str)>(data).unwrap();
+
+		assert_eq!(res, (1, "foo"));
+		assert_eq!(rem, b":2\r\n");
+	}
+
+	#[test]
+	fn de_streamed_map() {
+		let data = b"%?\r\n+foo\r\n:1\r\n.\r\n";
+		let (res, rem) = from_bytes::<HashMap<&str, i64>>(data).unwrap();
+
+		assert_eq!(res, HashMap::from([("foo", 1)]));
+		assert_eq!(rem, b"");
+	}
+
+	#[test]
+	fn de_incomplete_streamed() {
+		let data = b"*?\r\n:1\r\n";
+		let err = from_bytes::<Vec<i64>>(data).unwrap_err();
+
+		assert!(matches!(err.data, Error::Parse(nom::Err::Incomplete(_))));
+	}
+
+	#[test]
+	fn de_error_context() {
+		#[derive(Debug, serde::Deserialize)]
+		#[allow(dead_code)]
+		struct Entry {
+			id: i64,
+			#[serde(default)]
+			fields: Vec<i64>,
+		}
+
+		let data = b"*2\r\n%1\r\n+id\r\n:1\r\n%2\r\n+id\r\n:2\r\n+fields\r\n*2\r\n:3\r\n#t\r\n";
+		let err = from_bytes::<Vec<Entry>>(data).unwrap_err();
+
+		assert_eq!(err.data.path().unwrap().to_string(), "/1/fields/1");
+		assert_eq!(err.offset, data.len() - 4);
+		assert_eq!(err.remaining, &b"#t\r\n"[..]);
+		assert!(err
+			.to_string()
+			.ends_with("at /1/fields/1 at byte 47: .. 2a 32 0d 0a 3a 33 0d 0a >23 74 0d 0a"));
 	}
 }
@@ -1,13 +1,66 @@
-use serde::de;
+use serde::de::{self, IntoDeserializer};
+
+use crate::{parser::parse_end, Segment};
 
 use super::Deserializer;
 
 use super::Error;
 
+/// Access to the elements of an aggregate. A `len` of `None` means the aggregate is streamed and
+/// its elements continue until the end marker.
 pub struct WithLen<'a, 'de: 'a> {
 	pub de: &'a mut Deserializer<'de>,
 	pub cur: i64,
-	pub len: i64,
+	pub len: Option<i64>,
+	pub done: bool,
+	/// The input starting at the key of the current map entry.
+	pub key: &'de [u8],
+}
+
+impl<'a, 'de> WithLen<'a, 'de> {
+	pub fn new(de: &'a mut Deserializer<'de>, len: Option<i64>) -> Self {
+		Self {
+			de,
+			cur: 0,
+			len,
+			done: false,
+			key: &[],
+		}
+	}
+
+	/// Whether all elements have been read, consuming the end marker of a streamed aggregate.
+	fn is_done(&mut self) -> Result<bool, Error<'de>> {
+		if !self.done {
+			self.done = match self.len {
+				Some(len) => self.cur == len,
+				None if self.de.input.first() == Some(&b'.') => {
+					self.de.input = parse_end(self.de.input)?.0;
+					true
+				}
+				None => false,
+			};
+		}
+
+		Ok(self.done)
+	}
+
+	/// The path segment of the current map entry, which is its key if it is a string.
+	fn key_segment(&self) -> Segment {
+		match (Deserializer { input: self.key }).parse_str() {
+			Ok(key) => Segment::Key(key.into_owned()),
+			Err(_) => Segment::Index(self.cur as usize - 1),
+		}
+	}
+
+	/// Finish reading a streamed aggregate. Its end marker must follow the elements which were
+	/// read.
+	pub fn end(self) -> Result<(), Error<'de>> {
+		if self.len.is_none() && !self.done {
+			self.de.input = parse_end(self.de.input)?.0;
+		}
+
+		Ok(())
+	}
 }
 
 impl<'a, 'de> de::SeqAccess<'de> for WithLen<'a, 'de> {
@@ -17,16 +70,21 @@ impl<'a, 'de> de::SeqAccess<'de> for WithLen<'a, 'de> {
 	where
 		T: de::DeserializeSeed<'de>,
 	{
-		if self.cur == self.len {
+		if self.is_done()? {
 			Ok(None)
 		} else {
+			let segment = Segment::Index(self.cur as usize);
+			let start = self.de.input;
 			self.cur += 1;
-			seed.deserialize(&mut *self.de).map(Some)
+
+			seed.deserialize(&mut *self.de)
+				.map(Some)
+				.map_err(|e| self.de.error_at(start, e, segment))
 		}
 	}
 
 	fn size_hint(&self) -> Option<usize> {
-		(self.len - self.cur).try_into().ok()
+		(self.len? - self.cur).try_into().ok()
 	}
 }
 
@@ -37,11 +95,16 @@ impl<'a, 'de> de::MapAccess<'de> for WithLen<'a, 'de> {
 	where
 		K: de::DeserializeSeed<'de>,
 	{
-		if self.cur == self.len {
+		if self.is_done()? {
 			Ok(None)
 		} else {
+			self.key = self.de.input;
 			self.cur += 1;
-			seed.deserialize(&mut *self.de).map(Some)
+
+			seed.deserialize(&mut *self.de).map(Some).map_err(|e| {
+				let segment = self.key_segment();
+				self.de.error_at(self.key, e, segment)
+			})
 		}
 	}
 
@@ -49,11 +112,15 @@ impl<'a, 'de> de::MapAccess<'de> for WithLen<'a, 'de> {
 	where
 		V: de::DeserializeSeed<'de>,
 	{
-		seed.deserialize(&mut *self.de)
+		let start = self.de.input;
+		seed.deserialize(&mut *self.de).map_err(|e| {
+			let segment = self.key_segment();
+			self.de.error_at(start, e, segment)
+		})
 	}
 
 	fn size_hint(&self) -> Option<usize> {
-		(self.len - self.cur).try_into().ok()
+		(self.len? - self.cur).try_into().ok()
 	}
 }
 
@@ -106,3 +173,22 @@ impl<'de, 'a> de::VariantAccess<'de> for Enum<'a, 'de> {
 		de::Deserializer::deserialize_map(self.de, visitor)
 	}
 }
+
+/// An enum variant which is known before reading its content.
+pub struct Variant<'a, 'de: 'a> {
+	pub de: &'a mut Deserializer<'de>,
+	pub variant: u32,
+}
+
+impl<'de, 'a> de::EnumAccess<'de> for Variant<'a, 'de> {
+	type Error = Error<'de>;
+	type Variant = Enum<'a, 'de>;
+
+	fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+	where
+		V: de::DeserializeSeed<'de>,
+	{
+		let val = seed.deserialize(IntoDeserializer::<Error>::into_deserializer(self.variant))?;
+		Ok((val, Enum { de: self.de }))
+	}
+}
@@ -1,10 +1,26 @@
-use std::{borrow::Cow, str::FromStr};
-
-use serde::de::{self, Unexpected};
-
-use crate::parser::{self, parse_array, parse_bytes, parse_err, parse_int_loose, parse_str_loose};
-
-use super::{Enum, Error, WithLen};
+use alloc::{borrow::Cow, boxed::Box, string::String, vec::Vec};
+use core::str::{from_utf8, FromStr};
+
+use nom::IResult;
+use serde::{
+	de::{self, DeserializeSeed, Unexpected},
+	Deserialize,
+};
+
+use crate::{
+	data::{
+		de::{is_result, Element, Resp3Access, RESULT_ERR, RESULT_OK},
+		token,
+	},
+	parser::{
+		self, parse_array, parse_attribute, parse_big_number, parse_blob_err, parse_bool,
+		parse_bytes, parse_double, parse_err, parse_int_loose, parse_map, parse_null, parse_push,
+		parse_set, parse_str_loose, parse_streamed_bytes, parse_streamed_header, parse_verbatim,
+	},
+	Data, RedisError, Segment,
+};
+
+use super::{Enum, Error, Variant, WithLen};
 
 /// RESP deserializer.
 pub struct Deserializer<'de> {
@@ -12,26 +28,57 @@ pub struct Deserializer<'de> {
 }
 
 impl<'de> Deserializer<'de> {
-	fn parse_error(&mut self) -> Result<&'de str, Error<'de>> {
-		let (rem, str) = parse_err(self.input)?;
+	fn parse<T>(
+		&mut self,
+		parser: impl FnOnce(&'de [u8]) -> IResult<&'de [u8], T>,
+	) -> Result<T, Error<'de>> {
+		let (rem, out) = parser(self.input)?;
 		self.input = rem;
 
-		Ok(str)
+		Ok(out)
 	}
 
-	fn parse_str(&mut self) -> Result<&'de str, Error<'de>> {
+	/// Whether the next value is a streamed RESP3 string or aggregate.
+	fn is_streamed(&self) -> bool {
+		self.input.get(1) == Some(&b'?')
+	}
+
+	/// Add `segment` to the path of an error from a value which started at `start`. If the value
+	/// was read before the error, the input is moved back to its start so the error points at it.
+	pub(crate) fn error_at(
+		&mut self,
+		start: &'de [u8],
+		err: Error<'de>,
+		segment: Segment,
+	) -> Error<'de> {
+		if matches!(err, Error::Message(_)) {
+			self.input = start;
+		}
+
+		err.at(segment)
+	}
+
+	pub(crate) fn parse_str(&mut self) -> Result<Cow<'de, str>, Error<'de>> {
 		self.check_error()?;
 
-		let (rem, str) = parse_str_loose(self.input)?;
-		self.input = rem;
+		if self.is_streamed() {
+			return match self.parse(parse_streamed_bytes)? {
+				Cow::Borrowed(bytes) => from_utf8(bytes)
+					.map(Cow::Borrowed)
+					.map_err(de::Error::custom),
+				Cow::Owned(bytes) => String::from_utf8(bytes)
+					.map(Cow::Owned)
+					.map_err(de::Error::custom),
+			};
+		}
 
-		Ok(str)
+		self.parse(parse_str_loose).map(Cow::Borrowed)
 	}
 
 	fn parse_str_into<T>(&mut self) -> Result<T, Error<'de>>
 	where
 		T: FromStr,
-		<T as FromStr>::Err: std::fmt::Display,
+		<T as FromStr>::Err: core::fmt::Display,
 	{
 		self.parse_str()?
 			.parse()
@@ -41,76 +88,213 @@ impl<'de> Deserializer<'de> {
 	fn parse_int(&mut self) -> Result<i64, Error<'de>> {
 		self.check_error()?;
 
-		let (rem, int) = parse_int_loose(self.input)?;
-		self.input = rem;
+		if self.is_streamed() {
+			return self.parse_str_into();
+		}
 
-		Ok(int)
+		self.parse(parse_int_loose)
 	}
 
 	fn parse_int_into<T>(&mut self) -> Result<T, Error<'de>>
 	where
 		T: TryFrom<i64>,
-		<T as TryFrom<i64>>::Error: std::fmt::Display,
+		<T as TryFrom<i64>>::Error: core::fmt::Display,
 	{
 		self.parse_int()?
 			.try_into()
 			.map_err::<Error, _>(de::Error::custom)
 	}
 
-	fn parse_bytes(&mut self) -> Result<Option<&'de [u8]>, Error<'de>> {
+	fn parse_bytes(&mut self) -> Result<Option<Cow<'de, [u8]>>, Error<'de>> {
 		self.check_error()?;
 
-		let (rem, bytes) = parse_bytes(self.input)?;
-		self.input = rem;
+		match self.input.first() {
+			Some(b'$') if self.is_streamed() => self.parse(parse_streamed_bytes).map(Some),
+			Some(b'=') => Ok(Some(Cow::Borrowed(self.parse(parse_verbatim)?.1))),
+			Some(b'_') => self.parse(parse_null).map(|_| None),
+			_ => Ok(self.parse(parse_bytes)?.map(Cow::Borrowed)),
+		}
+	}
 
-		Ok(bytes)
+	/// Parse the length of an aggregate, which is `None` if the aggregate is streamed.
+	fn parse_len(
+		&mut self,
+		parser: impl FnOnce(&'de [u8]) -> IResult<&'de [u8], i64>,
+	) -> Result<Option<i64>, Error<'de>> {
+		if self.is_streamed() {
+			self.parse(parse_streamed_header)?;
+			Ok(None)
+		} else {
+			self.parse(parser).map(Some)
+		}
 	}
 
-	fn parse_array(&mut self) -> Result<i64, Error<'de>> {
+	/// Parse the length of any aggregate type. Maps are treated as a flat array of their keys and
+	/// values, and nulls have a negative length.
+	fn parse_array(&mut self) -> Result<Option<i64>, Error<'de>> {
 		self.check_error()?;
 
-		let (rem, len) = parse_array(self.input)?;
-		self.input = rem;
-
-		Ok(len)
+		match self.input.first() {
+			Some(b'%') => Ok(self.parse_len(parse_map)?.map(|len| len.max(-1) * 2)),
+			Some(b'~') => self.parse_len(parse_set),
+			Some(b'>') => self.parse_len(parse_push),
+			Some(b'_') => self.parse(parse_null).map(|_| Some(-1)),
+			_ => self.parse_len(parse_array),
+		}
 	}
 
 	fn parse_array_len(
 		&mut self,
 		exp: usize,
 		visitor: &impl de::Visitor<'de>,
-	) -> Result<i64, Error<'de>> {
-		let len = self.parse_array()?;
+	) -> Result<Option<i64>, Error<'de>> {
+		let len = match self.parse_array()? {
+			Some(len) => len,
+			None => return Ok(None),
+		};
 		let maybe_exp_signed: Result<i64, _> = exp.try_into();
 
 		match maybe_exp_signed {
-			Ok(exp_signed) if exp_signed == len => Ok(len),
+			Ok(exp_signed) if exp_signed == len => Ok(Some(len)),
 			_ => Err(de::Error::invalid_length(len as usize, visitor)),
 		}
 	}
 
+	fn parse_elements(&mut self, len: Option<i64>) -> Result<Vec<Data<'de>>, Error<'de>> {
+		let mut access = WithLen::new(self, len);
+		let mut out = Vec::with_capacity(de::SeqAccess::size_hint(&access).unwrap_or(0));
+		while let Some(data) = de::SeqAccess::next_element_seed(&mut access, Element)? {
+			out.push(data);
+		}
+
+		access.end()?;
+		Ok(out)
+	}
+
+	fn visit_seq<V>(&mut self, len: Option<i64>, visitor: V) -> Result<V::Value, Error<'de>>
+	where
+		V: de::Visitor<'de>,
+	{
+		let mut access = WithLen::new(self, len);
+		let seq = visitor.visit_seq(&mut access)?;
+		access.end()?;
+
+		Ok(seq)
+	}
+
+	fn visit_map<V>(&mut self, len: Option<i64>, visitor: V) -> Result<V::Value, Error<'de>>
+	where
+		V: de::Visitor<'de>,
+	{
+		let mut access = WithLen::new(self, len);
+		let map = visitor.visit_map(&mut access)?;
+		access.end()?;
+
+		Ok(map)
+	}
+
+	fn parse_pairs(&mut self, len: i64) -> Result<Vec<(Data<'de>, Data<'de>)>, Error<'de>> {
+		(0..len)
+			.map(|_| {
+				Ok((
+					Element.deserialize(&mut *self)?,
+					Element.deserialize(&mut *self)?,
+				))
+			})
+			.collect()
+	}
+
+	/// Parse the RESP3 types which can't be passed to a visitor without losing their type. Errors
+	/// are parsed as [Data::Error] if the data is an `element` of an aggregate.
+	fn parse_resp3(&mut self, element: bool) -> Result<Option<Data<'de>>, Error<'de>> {
+		let data = match self.input.first() {
+			Some(b'-' | b'!') if element => Data::Error(self.parse_error()?.unwrap_or_default()),
+			Some(b'~') => {
+				let len = self.parse_len(parse_set)?;
+				Data::Set(self.parse_elements(len)?)
+			}
+			Some(b'>') => {
+				let len = self.parse_len(parse_push)?;
+				Data::Push(self.parse_elements(len)?)
+			}
+			Some(b'(') => Data::BigNumber(Cow::Borrowed(self.parse(parse_big_number)?)),
+			Some(b'=') => {
+				let (format, text) = self.parse(parse_verbatim)?;
+				Data::VerbatimString {
+					format,
+					text: Cow::Borrowed(text),
+				}
+			}
+			Some(b'|') => {
+				let len = self.parse(parse_attribute)?;
+				Data::Attribute {
+					attributes: self.parse_pairs(len)?,
+					data: Box::new(match element {
+						true => Element.deserialize(&mut *self)?,
+						false => Data::deserialize(&mut *self)?,
+					}),
+				}
+			}
+			_ => return Ok(None),
+		};
+
+		Ok(Some(data))
+	}
+
+	/// Skip any RESP3 attributes, which are not relevant outside of [Data].
+	fn skip_attributes(&mut self) -> Result<(), Error<'de>> {
+		while self.input.first() == Some(&b'|') {
+			let len = self.parse(parse_attribute)?;
+			for _ in 0..len.saturating_mul(2) {
+				de::IgnoredAny::deserialize(&mut *self)?;
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Parse a simple or blob error, if the next value is one.
+	fn parse_error(&mut self) -> Result<Option<Cow<'de, str>>, Error<'de>> {
+		let msg = match self.input.first() {
+			Some(b'-') => self.parse(parse_err)?,
+			Some(b'!') => self.parse(parse_blob_err)?,
+			_ => return Ok(None),
+		};
+
+		Ok(Some(Cow::Borrowed(msg)))
+	}
+
 	fn check_error(&mut self) -> Result<(), Error<'de>> {
-		if self.input.get(0).copied() == Some(b'-') {
-			Err(Error::Redis(Cow::Borrowed(self.parse_error()?)))
-		} else {
-			Ok(())
+		self.skip_attributes()?;
+
+		match self.parse_error()? {
+			Some(msg) => Err(Error::Redis(RedisError::new(msg))),
+			None => Ok(()),
 		}
 	}
 }
 
-impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
 	type Error = Error<'de>;
 
 	fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
 	where
 		V: de::Visitor<'de>,
 	{
-		match self.input.get(0) {
-			Some(b'+') => self.deserialize_str(visitor),
-			Some(b'-') => Err(Error::Redis(Cow::Borrowed(self.parse_error()?))),
+		self.check_error()?;
+
+		match self.input.first() {
+			Some(b'+') | Some(b'(') => self.deserialize_str(visitor),
 			Some(b':') => self.deserialize_i64(visitor),
-			Some(b'$') => self.deserialize_bytes(visitor),
-			Some(b'*') => self.deserialize_seq(visitor),
+			Some(b'$') | Some(b'=') => self.deserialize_bytes(visitor),
+			Some(b'*') | Some(b'~') | Some(b'>') => self.deserialize_seq(visitor),
+			Some(b'%') => self.deserialize_map(visitor),
+			Some(b',') => self.deserialize_f64(visitor),
+			Some(b'#') => self.deserialize_bool(visitor),
+			Some(b'_') => {
+				self.parse(parse_null)?;
+				visitor.visit_none()
+			}
 			Some(b) => Err(de::Error::invalid_value(
 				Unexpected::Unsigned(*b as u64),
 				&visitor,
@@ -125,7 +309,12 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
 	where
 		V: de::Visitor<'de>,
 	{
-		visitor.visit_bool(self.parse_str_into()?)
+		self.check_error()?;
+
+		match self.input.first() {
+			Some(b'#') => visitor.visit_bool(self.parse(parse_bool)?),
+			_ => visitor.visit_bool(self.parse_str_into()?),
+		}
 	}
 
 	fn deserialize_i8<V>(self, visitor: V) -> Result<V::Value, Self::Error>
@@ -188,14 +377,24 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
 	where
 		V: de::Visitor<'de>,
 	{
-		visitor.visit_f32(self.parse_str_into()?)
+		self.check_error()?;
+
+		match self.input.first() {
+			Some(b',') => visitor.visit_f32(self.parse(parse_double)? as f32),
+			_ => visitor.visit_f32(self.parse_str_into()?),
+		}
 	}
 
 	fn deserialize_f64<V>(self, visitor: V) -> Result<V::Value, Self::Error>
 	where
 		V: de::Visitor<'de>,
 	{
-		visitor.visit_f64(self.parse_str_into()?)
+		self.check_error()?;
+
+		match self.input.first() {
+			Some(b',') => visitor.visit_f64(self.parse(parse_double)?),
+			_ => visitor.visit_f64(self.parse_str_into()?),
+		}
 	}
 
 	fn deserialize_char<V>(self, visitor: V) -> Result<V::Value, Self::Error>
@@ -209,7 +408,10 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
 	where
 		V: de::Visitor<'de>,
 	{
-		visitor.visit_borrowed_str(self.parse_str()?)
+		match self.parse_str()? {
+			Cow::Borrowed(str) => visitor.visit_borrowed_str(str),
+			Cow::Owned(str) => visitor.visit_string(str),
+		}
 	}
 
 	fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, Self::Error>
@@ -224,7 +426,8 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
 		V: de::Visitor<'de>,
 	{
 		match self.parse_bytes()? {
-			Some(d) => visitor.visit_borrowed_bytes(d),
+			Some(Cow::Borrowed(d)) => visitor.visit_borrowed_bytes(d),
+			Some(Cow::Owned(d)) => visitor.visit_byte_buf(d),
 			None => visitor.visit_none(),
 		}
 	}
@@ -240,11 +443,17 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
 	where
 		V: de::Visitor<'de>,
 	{
+		self.skip_attributes()?;
+
 		match self.input.get(0..5) {
 			Some(b"*-1\r\n") | Some(b"$-1\r\n") => {
 				self.input = &self.input[5..];
 				visitor.visit_none()
 			}
+			_ if self.input.starts_with(b"_\r\n") => {
+				self.input = &self.input[3..];
+				visitor.visit_none()
+			}
 			_ => visitor.visit_some(self),
 		}
 	}
@@ -270,12 +479,27 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
 
 	fn deserialize_newtype_struct<V>(
 		self,
-		_name: &'static str,
+		name: &'static str,
 		visitor: V,
 	) -> Result<V::Value, Self::Error>
 	where
 		V: de::Visitor<'de>,
 	{
+		if name == token::DATA || name == token::ELEMENT {
+			return match self.parse_resp3(name == token::ELEMENT)? {
+				Some(data) => data.deserialize_newtype_struct(token::DATA, visitor),
+				None => self.deserialize_any(visitor),
+			};
+		}
+
+		if name == token::ERROR {
+			self.skip_attributes()?;
+			return match self.parse_error()? {
+				Some(msg) => Data::Error(msg).deserialize_newtype_struct(name, visitor),
+				None => self.deserialize_str(visitor),
+			};
+		}
+
 		self.check_error()?;
 		visitor.visit_newtype_struct(self)
 	}
@@ -284,16 +508,9 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
 	where
 		V: de::Visitor<'de>,
 	{
-		let len = self.parse_array()?;
-
-		if len < 0 {
-			visitor.visit_none()
-		} else {
-			visitor.visit_seq(WithLen {
-				de: self,
-				cur: 0,
-				len,
-			})
+		match self.parse_array()? {
+			Some(len) if len < 0 => visitor.visit_none(),
+			len => self.visit_seq(len, visitor),
 		}
 	}
 
@@ -302,12 +519,7 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
 		V: de::Visitor<'de>,
 	{
 		let len = self.parse_array_len(len, &visitor)?;
-
-		visitor.visit_seq(WithLen {
-			de: self,
-			cur: 0,
-			len,
-		})
+		self.visit_seq(len, visitor)
 	}
 
 	fn deserialize_tuple_struct<V>(
@@ -320,28 +532,16 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
 		V: de::Visitor<'de>,
 	{
 		let len = self.parse_array_len(len, &visitor)?;
-
-		visitor.visit_seq(WithLen {
-			de: self,
-			cur: 0,
-			len,
-		})
+		self.visit_seq(len, visitor)
 	}
 
 	fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Self::Error>
 	where
 		V: de::Visitor<'de>,
 	{
-		let len = self.parse_array()?;
-
-		if len < 0 {
-			visitor.visit_none()
-		} else {
-			visitor.visit_map(WithLen {
-				de: self,
-				cur: 0,
-				len: len / 2,
-			})
+		match self.parse_array()? {
+			Some(len) if len < 0 => visitor.visit_none(),
+			len => self.visit_map(len.map(|len| len / 2), visitor),
 		}
 	}
 
@@ -359,14 +559,28 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
 
 	fn deserialize_enum<V>(
 		self,
-		_name: &'static str,
-		_variants: &'static [&'static str],
+		name: &'static str,
+		variants: &'static [&'static str],
 		visitor: V,
 	) -> Result<V::Value, Self::Error>
 	where
 		V: de::Visitor<'de>,
 	{
-		visitor.visit_enum(Enum { de: self })
+		if !is_result(name, variants) {
+			return visitor.visit_enum(Enum { de: self });
+		}
+
+		self.skip_attributes()?;
+		match self.parse_error()? {
+			Some(msg) => visitor.visit_enum(Resp3Access {
+				variant: RESULT_ERR,
+				content: Data::Error(msg),
+			}),
+			None => visitor.visit_enum(Variant {
+				de: self,
+				variant: RESULT_OK,
+			}),
+		}
 	}
 
 	fn deserialize_identifier<V>(self, visitor: V) -> Result<V::Value, Self::Error>
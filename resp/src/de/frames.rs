@@ -0,0 +1,250 @@
+#[cfg(feature = "std")]
+use std::io::{self, Read};
+
+use serde::de::DeserializeSeed;
+
+use crate::{data::de::Element, Data};
+#[cfg(feature = "std")]
+use crate::{parser, Error};
+
+use super::{Deserializer, ReadError};
+
+/// The smallest number of bytes to read at once in [FrameReader].
+#[cfg(feature = "std")]
+const MIN_READ_LEN: usize = 8 * 1024;
+
+/// Read one value from the start of `input`, returning it with the number of bytes it used.
+fn read_frame(input: &[u8]) -> Result<(Data<'_>, usize), ReadError<'_>> {
+	let mut de = Deserializer { input };
+	match Element.deserialize(&mut de) {
+		Ok(data) => Ok((data, input.len() - de.input.len())),
+		Err(err) => Err(ReadError::new(err, input, de.input)),
+	}
+}
+
+/// Iterator over the values in a byte slice, such as a captured byte dump, with the offset of
+/// each value.
+///
+/// Error replies are yielded as [Data::Error], so an error from the iterator means the input is
+/// invalid. The iterator ends after an error. If the input ends part way through a value, the
+/// error is [incomplete](crate::Error::is_incomplete); otherwise the iterator ends with the input.
+#[derive(Debug, Clone)]
+pub struct Frames<'a> {
+	input: &'a [u8],
+	offset: usize,
+	done: bool,
+}
+
+impl<'a> Frames<'a> {
+	/// Iterate over the values in `input`.
+	pub fn new(input: &'a [u8]) -> Self {
+		Self {
+			input,
+			offset: 0,
+			done: false,
+		}
+	}
+
+	/// The offset of the next value.
+	pub fn offset(&self) -> usize {
+		self.offset
+	}
+
+	/// The bytes which have not been read.
+	pub fn remaining(&self) -> &'a [u8] {
+		&self.input[self.offset..]
+	}
+}
+
+impl<'a> Iterator for Frames<'a> {
+	type Item = Result<(usize, Data<'a>), ReadError<'a>>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.done || self.offset == self.input.len() {
+			return None;
+		}
+
+		let offset = self.offset;
+		match read_frame(&self.input[offset..]) {
+			Ok((data, len)) => {
+				self.offset += len;
+				Some(Ok((offset, data)))
+			}
+			Err(mut err) => {
+				self.done = true;
+				err.offset += offset;
+				Some(Err(err))
+			}
+		}
+	}
+}
+
+/// Iterator over the values read from an [io::Read], with the offset of each value. See
+/// [Frames].
+///
+/// Reads are buffered, so the reader doesn't need to be.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct FrameReader<R> {
+	reader: R,
+	buf: Vec<u8>,
+	/// The start of the unread bytes in `buf`.
+	start: usize,
+	/// The offset of `buf` in the input.
+	offset: usize,
+	done: bool,
+}
+
+#[cfg(feature = "std")]
+impl<R> FrameReader<R> {
+	/// Iterate over the values read from `reader`.
+	pub fn new(reader: R) -> Self {
+		Self {
+			reader,
+			buf: Vec::new(),
+			start: 0,
+			offset: 0,
+			done: false,
+		}
+	}
+
+	/// The offset of the next value.
+	pub fn offset(&self) -> usize {
+		self.offset + self.start
+	}
+
+	/// Get the inner reader. Any bytes which have been read from it but not yet parsed are lost.
+	pub fn into_inner(self) -> R {
+		self.reader
+	}
+
+	/// Fail with `err` at the current offset, ending the iterator.
+	fn fail(&mut self, err: Error<'static>) -> ReadError<'static> {
+		self.done = true;
+
+		let input = &self.buf[self.start..];
+		let mut err = ReadError::new(err, input, input).into_owned();
+		err.offset = self.offset();
+		err
+	}
+}
+
+#[cfg(feature = "std")]
+impl<R> Iterator for FrameReader<R>
+where
+	R: Read,
+{
+	type Item = Result<(usize, Data<'static>), ReadError<'static>>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		while !self.done {
+			if self.start < self.buf.len() {
+				let offset = self.offset();
+				match read_frame(&self.buf[self.start..]) {
+					Ok((data, len)) => {
+						let data = data.into_owned();
+						self.start += len;
+						return Some(Ok((offset, data)));
+					}
+					Err(err) if err.data.is_incomplete() => {}
+					Err(err) => {
+						let mut err = err.into_owned();
+						err.offset += offset;
+						self.done = true;
+						return Some(Err(err));
+					}
+				}
+			}
+
+			// read at least as many bytes as are buffered, so large values aren't parsed
+			// repeatedly
+			self.buf.drain(..self.start);
+			self.offset += self.start;
+			self.start = 0;
+
+			let len = self.buf.len();
+			self.buf.resize(len + len.max(MIN_READ_LEN), 0);
+			let read = self.reader.read(&mut self.buf[len..]);
+			self.buf.truncate(len + *read.as_ref().unwrap_or(&0));
+
+			match read {
+				Ok(0) if len == 0 => self.done = true,
+				Ok(0) => {
+					let err = Error::Parse(parser::Error::Incomplete(nom::Needed::Unknown));
+					return Some(Err(self.fail(err)));
+				}
+				Ok(_) => {}
+				Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+				Err(err) => return Some(Err(self.fail(err.into()))),
+			}
+		}
+
+		None
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::io::{self, Read};
+
+	use crate::{array, Data};
+
+	use super::{FrameReader, Frames};
+
+	const INPUT: &[u8] = b"*2\r\n$3\r\nfoo\r\n:1\r\n-ERR foo\r\n+OK\r\n";
+
+	/// Reader which returns a few bytes at a time.
+	struct Chunked<'a>(&'a [u8]);
+
+	impl Read for Chunked<'_> {
+		fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+			let len = buf.len().min(self.0.len()).min(3);
+			buf[..len].copy_from_slice(&self.0[..len]);
+			self.0 = &self.0[len..];
+			Ok(len)
+		}
+	}
+
+	fn expected() -> [(usize, Data<'static>); 3] {
+		[
+			(0, array!(Data::bulk_string("foo"), 1)),
+			(17, Data::Error("ERR foo".into())),
+			(27, Data::simple_string("OK")),
+		]
+	}
+
+	#[test]
+	fn test_frames() {
+		let frames = Frames::new(INPUT).collect::<Result<Vec<_>, _>>().unwrap();
+		assert_eq!(frames, expected());
+
+		let mut frames = Frames::new(&INPUT[..20]);
+		assert_eq!(frames.next().unwrap().unwrap(), expected()[0]);
+
+		let err = frames.next().unwrap().unwrap_err();
+		assert!(err.data.is_incomplete());
+		assert_eq!(err.offset, 17);
+		assert!(frames.next().is_none());
+
+		let mut frames = Frames::new(b":1\r\n?\r\n:2\r\n");
+		assert!(frames.next().unwrap().is_ok());
+		assert!(!frames.next().unwrap().unwrap_err().data.is_incomplete());
+		assert!(frames.next().is_none());
+	}
+
+	#[test]
+	fn test_frame_reader() {
+		let frames = FrameReader::new(Chunked(INPUT))
+			.collect::<Result<Vec<_>, _>>()
+			.unwrap();
+		assert_eq!(frames, expected());
+
+		let mut frames = FrameReader::new(Chunked(&INPUT[..20]));
+		assert_eq!(frames.next().unwrap().unwrap(), expected()[0]);
+
+		let err = frames.next().unwrap().unwrap_err();
+		assert!(err.data.is_incomplete());
+		assert_eq!(err.offset, 17);
+		assert!(frames.next().is_none());
+	}
+}
@@ -1,10 +1,16 @@
-use std::borrow::Cow;
+use alloc::{borrow::Cow, boxed::Box, string::ToString, vec};
 
 use serde::{de, ser};
 use thiserror::Error;
 
 use crate::parser;
 
+mod path;
+mod redis;
+
+pub use path::*;
+pub use redis::*;
+
 type NomError<T> = nom::error::Error<T>;
 
 fn transform_parse_err<T, I>(
@@ -30,6 +36,7 @@ pub enum Error<'a> {
 	#[error("{0}")]
 	Message(Cow<'a, str>),
 	/// An IO error occured when writing to the buffer.
+	#[cfg(feature = "std")]
 	#[error("io error: {0}")]
 	Io(#[from] std::io::Error),
 	/// Invalid RESP syntax.
@@ -37,7 +44,34 @@ pub enum Error<'a> {
 	Parse(parser::Error<'a>),
 	/// An error was indicated by the data.
 	#[error("Redis error: {0}")]
-	Redis(Cow<'a, str>),
+	Redis(RedisError<'a>),
+	/// The data exceeded a protocol limit.
+	#[error("limit exceeded: {0}")]
+	Limit(#[from] Limit),
+	/// An error inside nested data, at `path`.
+	#[error("{error} at {path}")]
+	Path { path: Path, error: Box<Error<'a>> },
+}
+
+/// Protocol limits which can be exceeded when reading RESP. Each variant contains the maximum
+/// which was exceeded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum Limit {
+	/// A bulk string was too long.
+	#[error("bulk string longer than {0} bytes")]
+	BulkLen(usize),
+	/// An aggregate had too many elements.
+	#[error("aggregate with more than {0} elements")]
+	AggregateLen(usize),
+	/// Aggregates were nested too deeply.
+	#[error("aggregates nested more than {0} deep")]
+	Depth(usize),
+	/// A value was too long.
+	#[error("value longer than {0} bytes")]
+	FrameLen(usize),
+	/// An inline command was too long.
+	#[error("inline command longer than {0} bytes")]
+	InlineLen(usize),
 }
 
 impl Error<'_> {
@@ -45,12 +79,49 @@ impl Error<'_> {
 	pub fn into_owned(self) -> Error<'static> {
 		match self {
 			Self::Message(msg) => Error::Message(msg.into_owned().into()),
+			#[cfg(feature = "std")]
 			Self::Io(err) => Error::Io(err),
 			Self::Parse(err) => Error::Parse(transform_parse_err(err, |i| i.into_owned().into())),
-			Self::Redis(msg) => Error::Redis(msg.into_owned().into()),
+			Self::Redis(err) => Error::Redis(err.into_owned()),
+			Self::Limit(limit) => Error::Limit(limit),
+			Self::Path { path, error } => Error::Path {
+				path,
+				error: Box::new(error.into_owned()),
+			},
+		}
+	}
+
+	/// The path to the nested data which caused this error, if it was inside an aggregate.
+	pub fn path(&self) -> Option<&Path> {
+		match self {
+			Self::Path { path, .. } => Some(path),
+			_ => None,
 		}
 	}
 
+	/// Add `segment` to the start of this error's path. Errors from Redis, IO, limits and
+	/// incomplete input are returned as they are, so they can still be matched.
+	pub(crate) fn at(self, segment: Segment) -> Self {
+		match self {
+			Self::Path { mut path, error } => {
+				path.0.insert(0, segment);
+				Self::Path { path, error }
+			}
+			Self::Message(_) | Self::Parse(nom::Err::Error(_) | nom::Err::Failure(_)) => {
+				Self::Path {
+					path: Path(vec![segment]),
+					error: Box::new(self),
+				}
+			}
+			err => err,
+		}
+	}
+
+	/// Whether this error is because the input ended part way through a value.
+	pub fn is_incomplete(&self) -> bool {
+		matches!(self, Self::Parse(nom::Err::Incomplete(_)))
+	}
+
 	/// Whether this error is transient (i.e. the source is still valid)
 	pub fn is_transient(&self) -> bool {
 		matches!(self, Self::Redis(_))
@@ -60,7 +131,7 @@ impl Error<'_> {
 impl ser::Error for Error<'_> {
 	fn custom<T>(msg: T) -> Self
 	where
-		T: std::fmt::Display,
+		T: core::fmt::Display,
 	{
 		Self::Message(msg.to_string().into())
 	}
@@ -69,7 +140,7 @@ impl ser::Error for Error<'_> {
 impl de::Error for Error<'_> {
 	fn custom<T>(msg: T) -> Self
 	where
-		T: std::fmt::Display,
+		T: core::fmt::Display,
 	{
 		Self::Message(msg.to_string().into())
 	}
@@ -88,4 +159,4 @@ impl<'a> From<parser::RawError<'a>> for Error<'a> {
 }
 
 /// Result with an error type defaulting to [enum@Error].
-pub type Result<'a, T, E = Error<'a>> = std::result::Result<T, E>;
+pub type Result<'a, T, E = Error<'a>> = core::result::Result<T, E>;
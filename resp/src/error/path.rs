@@ -0,0 +1,44 @@
+use alloc::{string::String, vec::Vec};
+use core::fmt::{self, Display, Formatter};
+
+/// A step into nested data, as part of a [Path].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Segment {
+	/// The index of an element in an aggregate.
+	Index(usize),
+	/// The key of a map entry, or the name of a struct field.
+	Key(String),
+}
+
+impl Display for Segment {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Index(index) => write!(f, "{}", index),
+			Self::Key(key) => f.write_str(&key.replace('~', "~0").replace('/', "~1")),
+		}
+	}
+}
+
+/// The location of an error inside nested data, from the outermost value inwards.
+///
+/// Paths are displayed as [JSON pointers](https://www.rfc-editor.org/rfc/rfc6901) such as
+/// `/0/foo/1`, which can be passed to [Data::pointer](crate::Data::pointer).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Path(pub(crate) Vec<Segment>);
+
+impl Path {
+	/// The segments of this path.
+	pub fn segments(&self) -> &[Segment] {
+		&self.0
+	}
+}
+
+impl Display for Path {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		for segment in &self.0 {
+			write!(f, "/{}", segment)?;
+		}
+
+		Ok(())
+	}
+}
@@ -0,0 +1,328 @@
+use alloc::{
+	borrow::{Cow, ToOwned},
+	string::String,
+};
+use core::fmt::{self, Display, Formatter};
+
+use serde::{de, Deserialize, Serialize};
+
+use crate::data::token;
+
+use super::Error;
+
+/// The kind of a [RedisError], from the code at the start of its message.
+///
+/// More kinds may be added, so codes which are [Other](Self::Other) now may get their own kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+	/// A generic error, such as a syntax error or an unknown command.
+	Err,
+	/// The command was used against a key holding the wrong kind of value.
+	WrongType,
+	/// No script matched the SHA1 given to `EVALSHA`.
+	NoScript,
+	/// A script or function is running.
+	Busy,
+	/// The server is loading its dataset into memory.
+	Loading,
+	/// The key's slot is served by another node in the cluster. See [RedisError::redirect].
+	Moved,
+	/// The key's slot is being migrated to another node in the cluster. See
+	/// [RedisError::redirect].
+	Ask,
+	/// The keys of a multi-key command are being migrated between nodes in the cluster.
+	TryAgain,
+	/// The cluster is down.
+	ClusterDown,
+	/// A write was sent to a read-only replica.
+	ReadOnly,
+	/// Authentication is required.
+	NoAuth,
+	/// The username or password was invalid.
+	WrongPass,
+	/// The user doesn't have permission to run the command.
+	NoPerm,
+	/// The command needs more memory than `maxmemory` allows.
+	Oom,
+	/// A transaction was discarded because of an earlier error.
+	ExecAbort,
+	/// Any other code, or no code.
+	Other,
+}
+
+impl ErrorKind {
+	/// Get the kind for an error code, such as `WRONGTYPE`.
+	pub fn from_code(code: &str) -> Self {
+		match code {
+			"ERR" => Self::Err,
+			"WRONGTYPE" => Self::WrongType,
+			"NOSCRIPT" => Self::NoScript,
+			"BUSY" => Self::Busy,
+			"LOADING" => Self::Loading,
+			"MOVED" => Self::Moved,
+			"ASK" => Self::Ask,
+			"TRYAGAIN" => Self::TryAgain,
+			"CLUSTERDOWN" => Self::ClusterDown,
+			"READONLY" => Self::ReadOnly,
+			"NOAUTH" => Self::NoAuth,
+			"WRONGPASS" => Self::WrongPass,
+			"NOPERM" => Self::NoPerm,
+			"OOM" => Self::Oom,
+			"EXECABORT" => Self::ExecAbort,
+			_ => Self::Other,
+		}
+	}
+}
+
+/// A redirect to another node in a Redis cluster, from a [RedisError].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Redirect<'a> {
+	/// The slot has moved to the node at `addr`, which should be used for it from now on.
+	Moved { slot: u16, addr: &'a str },
+	/// The slot is being migrated to the node at `addr`. Only the next command should be sent
+	/// there, preceded by `ASKING`.
+	Ask { slot: u16, addr: &'a str },
+}
+
+impl<'a> Redirect<'a> {
+	/// The slot which was redirected.
+	pub fn slot(&self) -> u16 {
+		match self {
+			Self::Moved { slot, .. } | Self::Ask { slot, .. } => *slot,
+		}
+	}
+
+	/// The `host:port` of the node to redirect to.
+	pub fn addr(&self) -> &'a str {
+		match self {
+			Self::Moved { addr, .. } | Self::Ask { addr, .. } => addr,
+		}
+	}
+}
+
+/// An error returned by Redis, such as `WRONGTYPE Operation against a key holding the wrong kind
+/// of value`.
+///
+/// The code at the start of the error is parsed into an [ErrorKind], and the rest of the error
+/// is available from [RedisError::message].
+///
+/// Errors inside aggregates, such as the reply to `EXEC`, can be deserialized as
+/// `Result<T, RedisError>` to keep the other elements.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RedisError<'a> {
+	line: Cow<'a, str>,
+	kind: ErrorKind,
+}
+
+impl<'a> RedisError<'a> {
+	/// Make a new error from the full error line, such as `ERR unknown command`.
+	pub fn new(line: impl Into<Cow<'a, str>>) -> Self {
+		let line = line.into();
+		let kind = code(&line).map_or(ErrorKind::Other, ErrorKind::from_code);
+
+		Self { line, kind }
+	}
+
+	/// The kind of this error.
+	pub fn kind(&self) -> ErrorKind {
+		self.kind
+	}
+
+	/// The code at the start of this error, such as `ERR`. Codes are in upper case.
+	pub fn code(&self) -> Option<&str> {
+		code(&self.line)
+	}
+
+	/// The message after the code.
+	pub fn message(&self) -> &str {
+		match self.code() {
+			Some(code) => self.line[code.len()..].trim_start(),
+			None => &self.line,
+		}
+	}
+
+	/// The full error line, including the code.
+	pub fn as_str(&self) -> &str {
+		&self.line
+	}
+
+	/// Whether the same command may succeed if it is retried after a short delay. This is true
+	/// while the server is loading or busy, and while a cluster is down or migrating slots.
+	///
+	/// Redirects to other nodes are not included, see [RedisError::redirect].
+	pub fn is_retryable(&self) -> bool {
+		matches!(
+			self.kind,
+			ErrorKind::Loading | ErrorKind::Busy | ErrorKind::TryAgain | ErrorKind::ClusterDown
+		)
+	}
+
+	/// The node in a Redis cluster which the command should be sent to, if this is a `MOVED` or
+	/// `ASK` error.
+	pub fn redirect(&self) -> Option<Redirect<'_>> {
+		let (slot, addr) = self.message().split_once(' ')?;
+		let slot = slot.parse().ok()?;
+
+		match self.kind {
+			ErrorKind::Moved => Some(Redirect::Moved { slot, addr }),
+			ErrorKind::Ask => Some(Redirect::Ask { slot, addr }),
+			_ => None,
+		}
+	}
+
+	/// Convert this error into an owned error.
+	pub fn into_owned(self) -> RedisError<'static> {
+		RedisError {
+			line: self.line.into_owned().into(),
+			kind: self.kind,
+		}
+	}
+}
+
+/// Get the code at the start of an error line. Codes are upper case words.
+fn code(line: &str) -> Option<&str> {
+	let code = line.split(' ').next()?;
+	let is_code = code.starts_with(|c: char| c.is_ascii_uppercase())
+		&& code
+			.chars()
+			.all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+
+	is_code.then_some(code)
+}
+
+impl Display for RedisError<'_> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.write_str(&self.line)
+	}
+}
+
+impl core::error::Error for RedisError<'_> {}
+
+impl PartialEq<str> for RedisError<'_> {
+	fn eq(&self, other: &str) -> bool {
+		self.line == other
+	}
+}
+
+impl PartialEq<&str> for RedisError<'_> {
+	fn eq(&self, other: &&str) -> bool {
+		self.line == *other
+	}
+}
+
+impl<'a> From<RedisError<'a>> for Error<'a> {
+	fn from(err: RedisError<'a>) -> Self {
+		Self::Redis(err)
+	}
+}
+
+impl<'a> From<RedisError<'a>> for Cow<'a, str> {
+	fn from(err: RedisError<'a>) -> Self {
+		err.line
+	}
+}
+
+impl Serialize for RedisError<'_> {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: serde::Serializer,
+	{
+		serializer.serialize_newtype_struct(token::ERROR, &self.line)
+	}
+}
+
+impl<'de> Deserialize<'de> for RedisError<'de> {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: serde::Deserializer<'de>,
+	{
+		struct Visitor;
+
+		impl<'de> de::Visitor<'de> for Visitor {
+			type Value = RedisError<'de>;
+
+			fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+				f.write_str("an error message")
+			}
+
+			fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+			where
+				E: de::Error,
+			{
+				Ok(RedisError::new(v))
+			}
+
+			fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+			where
+				E: de::Error,
+			{
+				Ok(RedisError::new(v.to_owned()))
+			}
+
+			fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+			where
+				E: de::Error,
+			{
+				Ok(RedisError::new(v))
+			}
+
+			fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+			where
+				D: serde::Deserializer<'de>,
+			{
+				deserializer.deserialize_str(self)
+			}
+		}
+
+		deserializer.deserialize_newtype_struct(token::ERROR, Visitor)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::{ErrorKind, Redirect, RedisError};
+
+	#[test]
+	fn test_kind() {
+		let err = RedisError::new("WRONGTYPE Operation against a key holding the wrong kind");
+		assert_eq!(err.kind(), ErrorKind::WrongType);
+		assert_eq!(err.code(), Some("WRONGTYPE"));
+		assert_eq!(
+			err.message(),
+			"Operation against a key holding the wrong kind"
+		);
+		assert!(!err.is_retryable());
+
+		let err = RedisError::new("LOADING Redis is loading the dataset in memory");
+		assert_eq!(err.kind(), ErrorKind::Loading);
+		assert!(err.is_retryable());
+
+		let err = RedisError::new("CUSTOM module error");
+		assert_eq!(err.kind(), ErrorKind::Other);
+		assert_eq!(err.code(), Some("CUSTOM"));
+
+		let err = RedisError::new("Error without a code");
+		assert_eq!(err.kind(), ErrorKind::Other);
+		assert_eq!(err.code(), None);
+		assert_eq!(err.message(), "Error without a code");
+	}
+
+	#[test]
+	fn test_redirect() {
+		let err = RedisError::new("MOVED 3999 127.0.0.1:6381");
+		assert_eq!(
+			err.redirect(),
+			Some(Redirect::Moved {
+				slot: 3999,
+				addr: "127.0.0.1:6381"
+			})
+		);
+
+		let err = RedisError::new("ASK 3999 127.0.0.1:6381");
+		assert_eq!(err.redirect().map(|r| r.slot()), Some(3999));
+		assert!(matches!(err.redirect(), Some(Redirect::Ask { .. })));
+
+		assert_eq!(RedisError::new("ERR 3999 foo").redirect(), None);
+	}
+}
@@ -0,0 +1,124 @@
+use alloc::format;
+use core::fmt::{self, Display, Formatter, LowerExp};
+
+/// A float formatted the way Redis formats doubles.
+///
+/// The special values are spelled `inf`, `-inf` and `nan`. Other values are written with
+/// `precision` significant digits like `%.*g` in C. If there is no precision, they're written like
+/// `%.17g`, which Redis uses, but with the fewest digits which parse back to the same value.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Float<T> {
+	value: T,
+	precision: Option<usize>,
+}
+
+impl<T> Float<T> {
+	pub(crate) fn new(value: T, precision: Option<usize>) -> Self {
+		Self { value, precision }
+	}
+}
+
+impl<T> Display for Float<T>
+where
+	T: Display + LowerExp + Into<f64> + Copy,
+{
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		let value = self.value.into();
+
+		if value.is_nan() {
+			f.write_str("nan")
+		} else if value.is_infinite() {
+			f.write_str(if value > 0.0 { "inf" } else { "-inf" })
+		} else if let Some(precision) = self.precision {
+			write_general(f, value, precision.max(1))
+		} else {
+			write_shortest(f, self.value)
+		}
+	}
+}
+
+/// Write a finite `value` like `%.17g`, but with the fewest digits which parse back to the same
+/// value.
+fn write_shortest<T>(f: &mut Formatter<'_>, value: T) -> fmt::Result
+where
+	T: Display + LowerExp,
+{
+	let exponential = format!("{:e}", value);
+	let (mantissa, exp) = split_exponent(&exponential);
+
+	if !(-4..17).contains(&exp) {
+		write_exponent(f, mantissa, exp)
+	} else {
+		write!(f, "{}", value)
+	}
+}
+
+/// Write a finite `value` like `%.{precision}g`.
+fn write_general(f: &mut Formatter<'_>, value: f64, precision: usize) -> fmt::Result {
+	let exponential = format!("{:.*e}", precision - 1, value);
+	let (mantissa, exp) = split_exponent(&exponential);
+
+	if exp < -4 || exp >= precision as i64 {
+		write_exponent(f, mantissa, exp)
+	} else {
+		let decimals = (precision as i64 - 1 - exp) as usize;
+		f.write_str(trim_zeros(&format!("{:.*}", decimals, value)))
+	}
+}
+
+/// Split a float formatted with `{:e}` into its mantissa and exponent.
+fn split_exponent(exponential: &str) -> (&str, i64) {
+	let (mantissa, exp) = exponential
+		.split_once('e')
+		.expect("exponential format should have an exponent");
+	let exp = exp.parse().expect("exponent should be an integer");
+	(mantissa, exp)
+}
+
+/// Write a mantissa and exponent like C, such as `1.5e+07`.
+fn write_exponent(f: &mut Formatter<'_>, mantissa: &str, exp: i64) -> fmt::Result {
+	let sign = if exp < 0 { '-' } else { '+' };
+	write!(f, "{}e{}{:02}", trim_zeros(mantissa), sign, exp.abs())
+}
+
+/// Remove trailing zeros after the decimal point, and the point itself if nothing is left.
+fn trim_zeros(str: &str) -> &str {
+	if str.contains('.') {
+		str.trim_end_matches('0').trim_end_matches('.')
+	} else {
+		str
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::Float;
+
+	#[test]
+	fn test_float() {
+		let fmt = |value: f64, precision| Float::new(value, precision).to_string();
+
+		assert_eq!(fmt(1.5, None), "1.5");
+		assert_eq!(fmt(f64::INFINITY, None), "inf");
+		assert_eq!(fmt(f64::NEG_INFINITY, Some(17)), "-inf");
+		assert_eq!(fmt(f64::NAN, None), "nan");
+		assert_eq!(fmt(0.1 + 0.2, None), "0.30000000000000004");
+		assert_eq!(Float::new(0.1f32, None).to_string(), "0.1");
+		assert_eq!(fmt(1e300, None), "1e+300");
+		assert_eq!(fmt(-1e-300, None), "-1e-300");
+		assert_eq!(fmt(1.5e17, None), "1.5e+17");
+		assert_eq!(fmt(1e16, None), "10000000000000000");
+		assert_eq!(fmt(0.0001, None), "0.0001");
+		assert_eq!(fmt(0.00001, None), "1e-05");
+		assert_eq!(Float::new(3e38f32, None).to_string(), "3e+38");
+
+		assert_eq!(fmt(0.1 + 0.2, Some(17)), "0.30000000000000004");
+		assert_eq!(fmt(1e300, Some(17)), "1.0000000000000001e+300");
+		assert_eq!(fmt(100.0, Some(17)), "100");
+		assert_eq!(fmt(-0.0001, Some(3)), "-0.0001");
+		assert_eq!(fmt(0.00001, Some(3)), "1e-05");
+		assert_eq!(fmt(123456.0, Some(3)), "1.23e+05");
+		assert_eq!(fmt(999.9, Some(3)), "1e+03");
+		assert_eq!(fmt(13.361389338970184, Some(5)), "13.361");
+	}
+}
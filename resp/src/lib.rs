@@ -1,22 +1,47 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[doc(hidden)]
+pub use alloc::vec as __vec;
+#[cfg(feature = "futures-io")]
+pub use codec::{framed_io, FramedIo};
 #[cfg(feature = "codec")]
-pub use codec::Codec;
-pub use data::{de::from_data, ser::to_data, Data};
-pub use de::from_bytes;
-pub use error::{Error, Result};
+pub use codec::{
+	AggregateKind, Codec, CodecOptions, Event, EventCodec, Frame, ServerCodec, StreamCodec,
+	Streamed,
+};
+#[cfg(feature = "json")]
+pub use convert::BytesPolicy;
+pub use convert::{FromData, ToArgs};
+pub use data::{de::from_data, display::RawDisplay, ser::to_data, Data};
+#[cfg(feature = "std")]
+pub use de::FrameReader;
+pub use de::{from_bytes, Frames};
+pub use error::{Error, ErrorKind, Limit, Path, Redirect, RedisError, Result, Segment};
 pub use nom;
 pub use ser::to_bytes;
 
+/// Reading and writing AOF files.
+#[cfg(feature = "std")]
+pub mod aof;
 /// Stream RESP.
 #[cfg(feature = "codec")]
 mod codec;
+/// Conversions between RESP data and Rust types without serde.
+mod convert;
 /// General form of RESP data.
 mod data;
 /// RESP deserialization.
 pub mod de;
 /// RESP errors.
 mod error;
+/// Formatting floats like Redis.
+mod float;
 /// RESP parsing.
 pub mod parser;
+/// Reading RDB files and `DUMP` payloads, and writing `RESTORE` payloads.
+pub mod rdb;
 /// RESP serialization.
 pub mod ser;
 /// Utils for RESP (de)serialization.
@@ -1,12 +1,13 @@
-use std::{borrow::Cow, str::from_utf8};
+use alloc::borrow::Cow;
+use core::str::from_utf8;
 
 use nom::{
 	branch::alt,
 	bytes::streaming::take,
-	character::streaming::{char, crlf, i64, not_line_ending},
-	combinator::{map, map_res},
+	character::streaming::{char, crlf, digit1, i64, not_line_ending, one_of, u64},
+	combinator::{map, map_res, opt, recognize, value},
 	error::ErrorKind,
-	sequence::{delimited, terminated},
+	sequence::{delimited, pair, terminated},
 	IResult,
 };
 
@@ -31,7 +32,134 @@ pub fn parse_int(data: &[u8]) -> IResult<&[u8], i64> {
 
 /// Parse a RESP bulk string.
 pub fn parse_bytes(data: &[u8]) -> IResult<&[u8], Option<&[u8]>> {
-	let (data, len) = delimited(char('$'), i64, crlf)(data)?;
+	parse_blob('$', data)
+}
+
+/// Parse the length of a RESP array. Parsing the array elements is handled handled by the other
+/// parsers.
+pub fn parse_array(data: &[u8]) -> IResult<&[u8], i64> {
+	delimited(char('*'), i64, crlf)(data)
+}
+
+/// Parse the number of entries in a RESP3 map. Each entry is a key followed by a value, both of
+/// which are handled by the other parsers.
+pub fn parse_map(data: &[u8]) -> IResult<&[u8], i64> {
+	delimited(char('%'), i64, crlf)(data)
+}
+
+/// Parse the length of a RESP3 set. Parsing the set elements is handled by the other parsers.
+pub fn parse_set(data: &[u8]) -> IResult<&[u8], i64> {
+	delimited(char('~'), i64, crlf)(data)
+}
+
+/// Parse the length of a RESP3 push. Parsing the push elements is handled by the other parsers.
+pub fn parse_push(data: &[u8]) -> IResult<&[u8], i64> {
+	delimited(char('>'), i64, crlf)(data)
+}
+
+/// Parse the number of entries in a RESP3 attribute. Attributes are followed by the data they
+/// describe, which is handled by the other parsers.
+pub fn parse_attribute(data: &[u8]) -> IResult<&[u8], i64> {
+	delimited(char('|'), i64, crlf)(data)
+}
+
+/// Parse a RESP3 null.
+pub fn parse_null(data: &[u8]) -> IResult<&[u8], ()> {
+	value((), pair(char('_'), crlf))(data)
+}
+
+/// Parse a RESP3 boolean.
+pub fn parse_bool(data: &[u8]) -> IResult<&[u8], bool> {
+	delimited(
+		char('#'),
+		alt((value(true, char('t')), value(false, char('f')))),
+		crlf,
+	)(data)
+}
+
+/// Parse a RESP3 double, including `inf`, `-inf` and `nan`.
+pub fn parse_double(data: &[u8]) -> IResult<&[u8], f64> {
+	map_res(
+		map_res(delimited(char(','), not_line_ending, crlf), from_utf8),
+		str::parse,
+	)(data)
+}
+
+/// Parse a RESP3 big number. The number is returned as a string of digits with an optional sign,
+/// since it may not fit into any Rust integer type.
+pub fn parse_big_number(data: &[u8]) -> IResult<&[u8], &str> {
+	map_res(
+		delimited(char('('), recognize(pair(opt(one_of("+-")), digit1)), crlf),
+		from_utf8,
+	)(data)
+}
+
+/// Parse a RESP3 verbatim string, returning the three byte format (such as `txt` or `mkd`) and
+/// the text.
+pub fn parse_verbatim(data: &[u8]) -> IResult<&[u8], ([u8; 3], &[u8])> {
+	let (rem, blob) = parse_blob('=', data)?;
+
+	match blob {
+		Some([a, b, c, b':', text @ ..]) => Ok((rem, ([*a, *b, *c], text))),
+		_ => Err(nom::Err::Failure(nom::error::Error::new(
+			data,
+			ErrorKind::Verify,
+		))),
+	}
+}
+
+/// Parse a RESP3 blob error.
+pub fn parse_blob_err(data: &[u8]) -> IResult<&[u8], &str> {
+	map_res(
+		map(|d| parse_blob('!', d), Option::unwrap_or_default),
+		from_utf8,
+	)(data)
+}
+
+/// Parse the header of a streamed RESP3 string or aggregate, returning its prefix. A streamed
+/// string is followed by its chunks (see [parse_chunk]), and a streamed aggregate is followed by
+/// its elements and then an end marker (see [parse_end]).
+pub fn parse_streamed_header(data: &[u8]) -> IResult<&[u8], u8> {
+	map(terminated(one_of("$*%~>"), pair(char('?'), crlf)), |c| {
+		c as u8
+	})(data)
+}
+
+/// Parse a chunk of a streamed RESP3 string. The string ends with an empty chunk.
+pub fn parse_chunk(data: &[u8]) -> IResult<&[u8], &[u8]> {
+	let (data, len) = delimited(char(';'), u64, crlf)(data)?;
+	match len {
+		0 => Ok((data, &data[..0])),
+		_ => terminated(take(len), crlf)(data),
+	}
+}
+
+/// Parse the end marker of a streamed RESP3 aggregate.
+pub fn parse_end(data: &[u8]) -> IResult<&[u8], ()> {
+	value((), pair(char('.'), crlf))(data)
+}
+
+/// Parse a streamed RESP3 string, joining its chunks. The result only needs to be copied if the
+/// string has more than one chunk.
+pub fn parse_streamed_bytes(data: &[u8]) -> IResult<&[u8], Cow<'_, [u8]>> {
+	let (mut data, _) = pair(char('$'), pair(char('?'), crlf))(data)?;
+	let mut out = Cow::Borrowed(&data[..0]);
+
+	loop {
+		let (rem, chunk) = parse_chunk(data)?;
+		data = rem;
+
+		match chunk {
+			[] => return Ok((data, out)),
+			chunk if out.is_empty() => out = Cow::Borrowed(chunk),
+			chunk => out.to_mut().extend_from_slice(chunk),
+		}
+	}
+}
+
+/// Parse a length-prefixed blob, such as a bulk string.
+fn parse_blob(prefix: char, data: &[u8]) -> IResult<&[u8], Option<&[u8]>> {
+	let (data, len) = delimited(char(prefix), i64, crlf)(data)?;
 	Ok(match len {
 		-1 => (data, None),
 		0.. => map(terminated(take(len as usize), crlf), Some)(data)?,
@@ -44,17 +172,14 @@ pub fn parse_bytes(data: &[u8]) -> IResult<&[u8], Option<&[u8]>> {
 	})
 }
 
-/// Parse the length of a RESP array. Parsing the array elements is handled handled by the other
-/// parsers.
-pub fn parse_array(data: &[u8]) -> IResult<&[u8], i64> {
-	delimited(char('*'), i64, crlf)(data)
-}
-
-/// Parse a RESP string, including bulk string if the bytes are valid UTF-8.
+/// Parse a RESP string, including bulk strings, verbatim strings and big numbers if the bytes are
+/// valid UTF-8.
 pub fn parse_str_loose(data: &[u8]) -> IResult<&[u8], &str> {
 	alt((
 		parse_str,
 		map_res(map(parse_bytes, Option::unwrap_or_default), from_utf8),
+		map_res(map(parse_verbatim, |(_, text)| text), from_utf8),
+		parse_big_number,
 	))(data)
 }
 
@@ -138,4 +263,105 @@ mod test {
 		assert_eq!(0, rem.len());
 		assert_eq!(-1, res);
 	}
+
+	#[test]
+	fn test_parse_map() {
+		let resp = "%1\r\n+foo\r\n:1\r\n".as_bytes();
+		let (rem, res) = parse_map(resp).expect("Parsed map");
+
+		assert_eq!(10, rem.len());
+		assert_eq!(1, res);
+	}
+
+	#[test]
+	fn test_parse_null() {
+		let resp = "_\r\n".as_bytes();
+		let (rem, _) = parse_null(resp).expect("Parsed null");
+
+		assert_eq!(0, rem.len());
+	}
+
+	#[test]
+	fn test_parse_bool() {
+		let (rem, res) = parse_bool(b"#t\r\n").expect("Parsed bool");
+		assert_eq!(0, rem.len());
+		assert!(res);
+
+		let (rem, res) = parse_bool(b"#f\r\n").expect("Parsed bool");
+		assert_eq!(0, rem.len());
+		assert!(!res);
+	}
+
+	#[test]
+	fn test_parse_double() {
+		let (rem, res) = parse_double(b",1.23\r\n").expect("Parsed double");
+		assert_eq!(0, rem.len());
+		assert_eq!(1.23, res);
+
+		let (_, res) = parse_double(b",-inf\r\n").expect("Parsed double");
+		assert_eq!(f64::NEG_INFINITY, res);
+
+		let (_, res) = parse_double(b",nan\r\n").expect("Parsed double");
+		assert!(res.is_nan());
+	}
+
+	#[test]
+	fn test_parse_big_number() {
+		let resp = "(3492890328409238509324850943850943825024385\r\n".as_bytes();
+		let (rem, res) = parse_big_number(resp).expect("Parsed big number");
+
+		assert_eq!(0, rem.len());
+		assert_eq!("3492890328409238509324850943850943825024385", res);
+	}
+
+	#[test]
+	fn test_parse_verbatim() {
+		let resp = "=15\r\ntxt:Some string\r\n".as_bytes();
+		let (rem, (format, text)) = parse_verbatim(resp).expect("Parsed verbatim");
+
+		assert_eq!(0, rem.len());
+		assert_eq!(b"txt", &format);
+		assert_eq!(b"Some string", text);
+	}
+
+	#[test]
+	fn test_parse_streamed_header() {
+		let (rem, res) = parse_streamed_header(b"*?\r\n:1\r\n.\r\n").expect("Parsed header");
+
+		assert_eq!(7, rem.len());
+		assert_eq!(b'*', res);
+	}
+
+	#[test]
+	fn test_parse_end() {
+		let (rem, _) = parse_end(b".\r\n").expect("Parsed end");
+
+		assert_eq!(0, rem.len());
+	}
+
+	#[test]
+	fn test_parse_streamed_bytes() {
+		let resp = "$?\r\n;4\r\nHell\r\n;5\r\no wor\r\n;1\r\nd\r\n;0\r\n".as_bytes();
+		let (rem, res) = parse_streamed_bytes(resp).expect("Parsed streamed bytes");
+
+		assert_eq!(0, rem.len());
+		assert_eq!(b"Hello word", &*res);
+	}
+
+	#[test]
+	fn test_parse_incomplete_streamed_bytes() {
+		let resp = "$?\r\n;4\r\nHell\r\n;5\r\no w".as_bytes();
+		let res = parse_streamed_bytes(resp);
+
+		assert!(matches!(res, Err(nom::Err::Incomplete(_))));
+	}
+
+	#[test]
+	fn test_parse_blob_err() {
+		let resp = "!21\r\nSYNTAX invalid syntax\r\n".as_bytes();
+		let (rem, res) = parse_blob_err(resp).expect("Parsed blob error");
+
+		assert_eq!(0, rem.len());
+		assert_eq!("SYNTAX invalid syntax", res);
+	}
 }
@@ -0,0 +1,314 @@
+use alloc::{string::ToString, vec, vec::Vec};
+
+use crate::{Data, Error};
+
+/// CRC-64 checksums used by RDB files and `DUMP` payloads.
+mod crc64;
+/// Decoding RDB values.
+mod de;
+/// Compact encodings nested in RDB values: listpacks, ziplists and intsets.
+mod encoding;
+/// LZF decompression for compressed strings.
+mod lzf;
+/// Encoding RDB values.
+mod ser;
+mod stream;
+
+pub use stream::*;
+
+/// The RDB version written in `DUMP` payloads by [to_dump], which is accepted by Redis 5 and
+/// later.
+pub const DUMP_VERSION: u16 = 9;
+
+// value types
+const TYPE_STRING: u8 = 0;
+const TYPE_LIST: u8 = 1;
+const TYPE_SET: u8 = 2;
+const TYPE_ZSET: u8 = 3;
+const TYPE_HASH: u8 = 4;
+const TYPE_ZSET_2: u8 = 5;
+const TYPE_LIST_ZIPLIST: u8 = 10;
+const TYPE_SET_INTSET: u8 = 11;
+const TYPE_ZSET_ZIPLIST: u8 = 12;
+const TYPE_HASH_ZIPLIST: u8 = 13;
+const TYPE_LIST_QUICKLIST: u8 = 14;
+const TYPE_STREAM_LISTPACKS: u8 = 15;
+const TYPE_HASH_LISTPACK: u8 = 16;
+const TYPE_ZSET_LISTPACK: u8 = 17;
+const TYPE_LIST_QUICKLIST_2: u8 = 18;
+const TYPE_STREAM_LISTPACKS_2: u8 = 19;
+const TYPE_SET_LISTPACK: u8 = 20;
+const TYPE_STREAM_LISTPACKS_3: u8 = 21;
+
+// opcodes in RDB files
+const OPCODE_SLOT_INFO: u8 = 0xf4;
+const OPCODE_FUNCTION2: u8 = 0xf5;
+const OPCODE_IDLE: u8 = 0xf8;
+const OPCODE_FREQ: u8 = 0xf9;
+const OPCODE_AUX: u8 = 0xfa;
+const OPCODE_RESIZEDB: u8 = 0xfb;
+const OPCODE_EXPIRETIME_MS: u8 = 0xfc;
+const OPCODE_EXPIRETIME: u8 = 0xfd;
+const OPCODE_SELECTDB: u8 = 0xfe;
+const OPCODE_EOF: u8 = 0xff;
+
+// flags of stream entries
+const STREAM_FLAG_DELETED: i64 = 1;
+const STREAM_FLAG_SAMEFIELDS: i64 = 2;
+
+/// A value stored in Redis.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+	String(Vec<u8>),
+	List(Vec<Vec<u8>>),
+	Set(Vec<Vec<u8>>),
+	/// Members and their scores, ordered by score.
+	SortedSet(Vec<(Vec<u8>, f64)>),
+	/// Fields and their values.
+	Hash(Vec<(Vec<u8>, Vec<u8>)>),
+	Stream(Stream),
+}
+
+/// Converts a value into the data Redis would reply with when reading all of it: sorted sets
+/// become arrays of `[member, score]` pairs like `ZRANGE .. WITHSCORES`, and streams become the
+/// entries from `XRANGE`.
+impl From<Value> for Data<'static> {
+	fn from(value: Value) -> Self {
+		fn bulk(bytes: Vec<u8>) -> Data<'static> {
+			Data::BulkString(bytes.into())
+		}
+
+		match value {
+			Value::String(bytes) => bulk(bytes),
+			Value::List(elements) => Data::Array(elements.into_iter().map(bulk).collect()),
+			Value::Set(elements) => Data::Set(elements.into_iter().map(bulk).collect()),
+			Value::SortedSet(members) => Data::Array(
+				members
+					.into_iter()
+					.map(|(member, score)| Data::Array(vec![bulk(member), Data::Double(score)]))
+					.collect(),
+			),
+			Value::Hash(fields) => Data::Map(
+				fields
+					.into_iter()
+					.map(|(field, value)| (bulk(field), bulk(value)))
+					.collect(),
+			),
+			Value::Stream(stream) => Data::Array(
+				stream
+					.entries
+					.into_iter()
+					.map(|entry| {
+						let fields = entry
+							.fields
+							.into_iter()
+							.flat_map(|(field, value)| [bulk(field), bulk(value)]);
+						Data::Array(vec![
+							bulk(entry.id.to_string().into_bytes()),
+							Data::Array(fields.collect()),
+						])
+					})
+					.collect(),
+			),
+		}
+	}
+}
+
+/// A key in an RDB file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+	/// The database the key is in.
+	pub db: u64,
+	pub key: Vec<u8>,
+	pub value: Value,
+	/// When the key expires, in milliseconds since the Unix epoch.
+	pub expire_at: Option<u64>,
+	/// The LRU idle time of the key, in seconds.
+	pub idle: Option<u64>,
+	/// The LFU frequency of the key.
+	pub freq: Option<u8>,
+}
+
+/// The contents of an RDB file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Rdb {
+	/// The RDB version of the file.
+	pub version: u32,
+	/// Auxiliary fields, such as `redis-ver` and `ctime`.
+	pub aux: Vec<(Vec<u8>, Vec<u8>)>,
+	/// The code of each function library.
+	pub functions: Vec<Vec<u8>>,
+	/// The keys, in the order they are stored.
+	pub entries: Vec<Entry>,
+}
+
+/// Decode an RDB file, such as `dump.rdb`, verifying its checksum if it has one.
+///
+/// Module types and hash field expiration aren't supported.
+pub fn from_bytes(input: &[u8]) -> Result<Rdb, Error<'static>> {
+	de::rdb(input)
+}
+
+/// Decode the payload returned by `DUMP`, verifying its checksum.
+pub fn from_dump(payload: &[u8]) -> Result<Value, Error<'static>> {
+	de::dump(payload)
+}
+
+/// Encode `value` as a payload for `RESTORE`.
+pub fn to_dump(value: &Value) -> Vec<u8> {
+	ser::dump(value)
+}
+
+#[cfg(test)]
+mod test {
+	use crate::{array, Data};
+
+	use super::{
+		crc64::crc64, from_bytes, from_dump, to_dump, Consumer, ConsumerGroup, Entry, PendingEntry,
+		Stream, StreamEntry, StreamId, Value,
+	};
+
+	fn pairs(pairs: &[(&str, &str)]) -> Vec<(Vec<u8>, Vec<u8>)> {
+		pairs
+			.iter()
+			.map(|(a, b)| (a.as_bytes().to_vec(), b.as_bytes().to_vec()))
+			.collect()
+	}
+
+	/// A listpack containing `f` and `1`.
+	const LISTPACK: &[u8] = b"\x0c\x00\x00\x00\x02\x00\x81f\x02\x01\x01\xff";
+
+	#[test]
+	fn test_dump() {
+		let stream = Stream {
+			entries: vec![
+				StreamEntry {
+					id: StreamId(1, 0),
+					fields: pairs(&[("a", "1"), ("b", "2")]),
+				},
+				StreamEntry {
+					id: StreamId(1, 1),
+					fields: pairs(&[("a", "3"), ("b", "4")]),
+				},
+				StreamEntry {
+					id: StreamId(2, 0),
+					fields: pairs(&[("c", "5")]),
+				},
+			],
+			last_id: StreamId(3, 0),
+			meta: None,
+			groups: vec![ConsumerGroup {
+				name: b"group".to_vec(),
+				last_id: StreamId(1, 1),
+				entries_read: None,
+				pending: vec![PendingEntry {
+					id: StreamId(1, 1),
+					delivery_time: 1_700_000_000_000,
+					delivery_count: 2,
+				}],
+				consumers: vec![Consumer {
+					name: b"consumer".to_vec(),
+					seen_time: 1_700_000_000_000,
+					active_time: None,
+					pending: vec![StreamId(1, 1)],
+				}],
+			}],
+		};
+
+		let values = [
+			Value::String(vec![b'x'; 100]),
+			Value::List(vec![b"a".to_vec(), b"b".to_vec()]),
+			Value::Set(vec![b"a".to_vec()]),
+			Value::SortedSet(vec![(b"a".to_vec(), -1.5), (b"b".to_vec(), f64::INFINITY)]),
+			Value::Hash(pairs(&[("f", "v")])),
+			Value::Stream(stream),
+		];
+
+		for value in values {
+			let payload = to_dump(&value);
+			assert_eq!(&payload[payload.len() - 10..][..2], b"\x09\x00");
+			assert_eq!(from_dump(&payload).unwrap(), value);
+		}
+
+		let mut payload = to_dump(&Value::String(b"foo".to_vec()));
+		assert_eq!(&payload[..5], b"\x00\x03foo");
+		payload[2] = b'g';
+		assert!(from_dump(&payload).is_err());
+
+		// a listpack hash, as dumped by Redis 7
+		let mut payload = vec![16, LISTPACK.len() as u8];
+		payload.extend_from_slice(LISTPACK);
+		payload.extend_from_slice(b"\x0b\x00");
+		payload.extend_from_slice(&crc64(&payload).to_le_bytes());
+		let value = from_dump(&payload).unwrap();
+		assert_eq!(value, Value::Hash(pairs(&[("f", "1")])));
+		assert_eq!(
+			Data::from(value),
+			Data::Map(vec![(Data::bulk_string("f"), Data::bulk_string("1"))])
+		);
+	}
+
+	#[test]
+	fn test_rdb() {
+		let mut input = b"REDIS0011\xfa\x09redis-ver\x057.2.0\xfa\x05ctime\xc2\x64\x00\x00\x00\
+			\xfe\x00\xfb\x03\x01\
+			\xfc\x00\x68\xe5\xcf\x8b\x01\x00\x00\x00\x03foo\xc3\x09\x10\x02abc\xe0\x01\x02\x20\x00\
+			\x0b\x01s\x0c\x02\x00\x00\x00\x02\x00\x00\x00\x01\x00\xff\xff\
+			\x10\x01h\x0c"
+			.to_vec();
+		input.extend_from_slice(LISTPACK);
+		input.push(0xff);
+		input.extend_from_slice(&crc64(&input).to_le_bytes());
+
+		let rdb = from_bytes(&input).unwrap();
+		assert_eq!(rdb.version, 11);
+		assert_eq!(rdb.aux, pairs(&[("redis-ver", "7.2.0"), ("ctime", "100")]));
+		assert_eq!(
+			rdb.entries,
+			[
+				Entry {
+					db: 0,
+					key: b"foo".to_vec(),
+					value: Value::String(b"abcabcabcabcaaaa".to_vec()),
+					expire_at: Some(1_700_000_000_000),
+					idle: None,
+					freq: None,
+				},
+				Entry {
+					db: 0,
+					key: b"s".to_vec(),
+					value: Value::Set(vec![b"1".to_vec(), b"-1".to_vec()]),
+					expire_at: None,
+					idle: None,
+					freq: None,
+				},
+				Entry {
+					db: 0,
+					key: b"h".to_vec(),
+					value: Value::Hash(pairs(&[("f", "1")])),
+					expire_at: None,
+					idle: None,
+					freq: None,
+				},
+			]
+		);
+
+		let len = input.len();
+		input[len - 1] ^= 1;
+		assert!(from_bytes(&input).is_err());
+		assert!(from_bytes(&input[..len - 20]).is_err());
+		assert!(from_bytes(b"REDIX0011\xff").is_err());
+
+		// an LZF string claiming to be far larger than it is
+		let mut input = b"REDIS0003\xfa\x01k\xc3\x01\x81".to_vec();
+		input.extend_from_slice(&(1u64 << 62).to_be_bytes());
+		input.extend_from_slice(b"\x00\xff");
+		assert!(from_bytes(&input).is_err());
+
+		let zset = Value::SortedSet(vec![(b"a".to_vec(), 1.0)]);
+		assert_eq!(
+			Data::from(zset),
+			array!(array!(Data::bulk_string("a"), Data::Double(1.0)))
+		);
+	}
+}
@@ -0,0 +1,40 @@
+/// The reflected Jones polynomial, which Redis uses.
+const POLY: u64 = 0x95ac_9329_ac4b_c9b5;
+
+const TABLE: [u64; 256] = {
+	let mut table = [0; 256];
+	let mut i = 0;
+	while i < 256 {
+		let mut crc = i as u64;
+		let mut bit = 0;
+		while bit < 8 {
+			crc = if crc & 1 == 1 {
+				(crc >> 1) ^ POLY
+			} else {
+				crc >> 1
+			};
+			bit += 1;
+		}
+		table[i] = crc;
+		i += 1;
+	}
+	table
+};
+
+/// Checksum `bytes`, like `crc64(0, bytes, len)` in Redis.
+pub(crate) fn crc64(bytes: &[u8]) -> u64 {
+	bytes.iter().fold(0, |crc, &b| {
+		TABLE[((crc ^ b as u64) & 0xff) as usize] ^ (crc >> 8)
+	})
+}
+
+#[cfg(test)]
+mod test {
+	use super::crc64;
+
+	#[test]
+	fn test_crc64() {
+		assert_eq!(crc64(b"123456789"), 0xe9c6_d914_c4b8_d9ca);
+		assert_eq!(crc64(b""), 0);
+	}
+}
@@ -0,0 +1,433 @@
+use alloc::{borrow::Cow, format, string::ToString, vec::Vec};
+use core::{fmt::Display, str::from_utf8};
+
+use crate::Error;
+
+use super::{
+	crc64::crc64,
+	encoding::{self, Element},
+	lzf, *,
+};
+
+/// Fields and values of a hash.
+type Pairs = Vec<(Vec<u8>, Vec<u8>)>;
+
+/// The length of an RDB string, or the encoding of a special string.
+enum Length {
+	Len(u64),
+	Encoded(u8),
+}
+
+/// Cursor over RDB bytes.
+struct Input<'a> {
+	bytes: &'a [u8],
+	pos: usize,
+}
+
+impl<'a> Input<'a> {
+	fn new(bytes: &'a [u8]) -> Self {
+		Self { bytes, pos: 0 }
+	}
+
+	fn error(&self, msg: impl Display) -> Error<'static> {
+		Error::Message(format!("invalid RDB at byte {}: {}", self.pos, msg).into())
+	}
+
+	fn take(&mut self, len: u64) -> Result<&'a [u8], Error<'static>> {
+		let bytes = usize::try_from(len)
+			.ok()
+			.and_then(|len| self.bytes[self.pos..].get(..len))
+			.ok_or_else(|| self.error("unexpected end of input"))?;
+
+		self.pos += bytes.len();
+		Ok(bytes)
+	}
+
+	fn array<const N: usize>(&mut self) -> Result<[u8; N], Error<'static>> {
+		Ok(self.take(N as u64)?.try_into().unwrap())
+	}
+
+	fn u8(&mut self) -> Result<u8, Error<'static>> {
+		Ok(self.array::<1>()?[0])
+	}
+
+	/// A little-endian timestamp in milliseconds.
+	fn millis(&mut self) -> Result<u64, Error<'static>> {
+		Ok(u64::from_le_bytes(self.array()?))
+	}
+
+	fn length(&mut self) -> Result<Length, Error<'static>> {
+		let b = self.u8()?;
+		Ok(match b >> 6 {
+			0 => Length::Len((b & 0x3f) as u64),
+			1 => Length::Len(((b as u64 & 0x3f) << 8) | self.u8()? as u64),
+			3 => Length::Encoded(b & 0x3f),
+			_ if b == 0x80 => Length::Len(u32::from_be_bytes(self.array()?) as u64),
+			_ if b == 0x81 => Length::Len(u64::from_be_bytes(self.array()?)),
+			_ => return Err(self.error("invalid length")),
+		})
+	}
+
+	fn len(&mut self) -> Result<u64, Error<'static>> {
+		match self.length()? {
+			Length::Len(len) => Ok(len),
+			Length::Encoded(_) => Err(self.error("unexpected string encoding")),
+		}
+	}
+
+	fn string(&mut self) -> Result<Cow<'a, [u8]>, Error<'static>> {
+		let int = match self.length()? {
+			Length::Len(len) => return Ok(self.take(len)?.into()),
+			Length::Encoded(0) => self.u8()? as i8 as i64,
+			Length::Encoded(1) => i16::from_le_bytes(self.array()?) as i64,
+			Length::Encoded(2) => i32::from_le_bytes(self.array()?) as i64,
+			Length::Encoded(3) => {
+				let (compressed_len, len) = (self.len()?, self.len()?);
+				let compressed = self.take(compressed_len)?;
+				return usize::try_from(len)
+					.ok()
+					.and_then(|len| lzf::decompress(compressed, len))
+					.map(Cow::Owned)
+					.ok_or_else(|| self.error("invalid compressed string"));
+			}
+			Length::Encoded(_) => return Err(self.error("invalid string encoding")),
+		};
+
+		Ok(int.to_string().into_bytes().into())
+	}
+
+	fn strings(&mut self) -> Result<Vec<Vec<u8>>, Error<'static>> {
+		(0..self.len()?)
+			.map(|_| Ok(self.string()?.into_owned()))
+			.collect()
+	}
+
+	fn pairs(&mut self) -> Result<Pairs, Error<'static>> {
+		(0..self.len()?)
+			.map(|_| Ok((self.string()?.into_owned(), self.string()?.into_owned())))
+			.collect()
+	}
+
+	/// A double as text, which is only used by old sorted sets.
+	fn text_double(&mut self) -> Result<f64, Error<'static>> {
+		match self.u8()? {
+			253 => Ok(f64::NAN),
+			254 => Ok(f64::INFINITY),
+			255 => Ok(f64::NEG_INFINITY),
+			len => {
+				let text = self.take(len as u64)?;
+				from_utf8(text)
+					.ok()
+					.and_then(|text| text.parse().ok())
+					.ok_or_else(|| self.error("invalid double"))
+			}
+		}
+	}
+
+	fn stream_id(&mut self) -> Result<StreamId, Error<'static>> {
+		Ok(StreamId(self.len()?, self.len()?))
+	}
+
+	fn raw_stream_id(&mut self) -> Result<StreamId, Error<'static>> {
+		Ok(StreamId::from_be_bytes(&self.array()?))
+	}
+
+	/// Read a string and decode it with `decode`, failing if it returns `None`.
+	fn blob<T>(
+		&mut self,
+		name: &str,
+		decode: impl FnOnce(&[u8]) -> Option<T>,
+	) -> Result<T, Error<'static>> {
+		let blob = self.string()?;
+		decode(&blob).ok_or_else(|| self.error(format_args!("invalid {}", name)))
+	}
+}
+
+fn to_vecs(elements: Vec<Element<'_>>) -> Vec<Vec<u8>> {
+	elements.into_iter().map(Element::to_vec).collect()
+}
+
+fn to_pairs(elements: Vec<Element<'_>>) -> Option<Pairs> {
+	if !elements.len().is_multiple_of(2) {
+		return None;
+	}
+
+	Some(
+		elements
+			.chunks_exact(2)
+			.map(|pair| (pair[0].to_vec(), pair[1].to_vec()))
+			.collect(),
+	)
+}
+
+fn to_scores(elements: Vec<Element<'_>>) -> Option<Vec<(Vec<u8>, f64)>> {
+	if !elements.len().is_multiple_of(2) {
+		return None;
+	}
+
+	elements
+		.chunks_exact(2)
+		.map(|pair| Some((pair[0].to_vec(), pair[1].to_f64()?)))
+		.collect()
+}
+
+fn value(input: &mut Input<'_>, ty: u8) -> Result<Value, Error<'static>> {
+	let compact: fn(&[u8]) -> Option<Vec<Element<'_>>> = match ty {
+		TYPE_LIST_ZIPLIST | TYPE_ZSET_ZIPLIST | TYPE_HASH_ZIPLIST => encoding::ziplist,
+		_ => encoding::listpack,
+	};
+
+	Ok(match ty {
+		TYPE_STRING => Value::String(input.string()?.into_owned()),
+		TYPE_LIST => Value::List(input.strings()?),
+		TYPE_LIST_ZIPLIST => {
+			Value::List(input.blob("ziplist", |blob| Some(to_vecs(compact(blob)?)))?)
+		}
+		TYPE_LIST_QUICKLIST => {
+			let mut elements = Vec::new();
+			for _ in 0..input.len()? {
+				elements.extend(input.blob("ziplist", |blob| Some(to_vecs(compact(blob)?)))?);
+			}
+			Value::List(elements)
+		}
+		TYPE_LIST_QUICKLIST_2 => {
+			let mut elements = Vec::new();
+			for _ in 0..input.len()? {
+				match input.len()? {
+					// a plain node with one large element
+					1 => elements.push(input.string()?.into_owned()),
+					2 => elements
+						.extend(input.blob("listpack", |blob| Some(to_vecs(compact(blob)?)))?),
+					_ => return Err(input.error("invalid quicklist container")),
+				}
+			}
+			Value::List(elements)
+		}
+		TYPE_SET => Value::Set(input.strings()?),
+		TYPE_SET_INTSET => Value::Set(input.blob("intset", |blob| {
+			let ints = encoding::intset(blob)?;
+			Some(ints.iter().map(|i| i.to_string().into_bytes()).collect())
+		})?),
+		TYPE_SET_LISTPACK => {
+			Value::Set(input.blob("listpack", |blob| Some(to_vecs(compact(blob)?)))?)
+		}
+		TYPE_ZSET | TYPE_ZSET_2 => {
+			let mut members = Vec::new();
+			for _ in 0..input.len()? {
+				let member = input.string()?.into_owned();
+				let score = match ty {
+					TYPE_ZSET => input.text_double()?,
+					_ => f64::from_le_bytes(input.array()?),
+				};
+				members.push((member, score));
+			}
+
+			// Redis stores members from the highest score, or unordered before Redis 5
+			members.sort_by(|(a, x), (b, y)| x.total_cmp(y).then_with(|| a.cmp(b)));
+			Value::SortedSet(members)
+		}
+		TYPE_ZSET_ZIPLIST | TYPE_ZSET_LISTPACK => {
+			Value::SortedSet(input.blob("sorted set", |blob| to_scores(compact(blob)?))?)
+		}
+		TYPE_HASH => Value::Hash(input.pairs()?),
+		TYPE_HASH_ZIPLIST | TYPE_HASH_LISTPACK => {
+			Value::Hash(input.blob("hash", |blob| to_pairs(compact(blob)?))?)
+		}
+		TYPE_STREAM_LISTPACKS | TYPE_STREAM_LISTPACKS_2 | TYPE_STREAM_LISTPACKS_3 => {
+			Value::Stream(stream(input, ty)?)
+		}
+		_ => return Err(input.error(format_args!("unsupported type {}", ty))),
+	})
+}
+
+fn next_int<'a>(elements: &mut impl Iterator<Item = Element<'a>>) -> Option<i64> {
+	elements.next()?.to_int()
+}
+
+/// Decode the entries in the listpack of a stream node.
+fn stream_entries(master: StreamId, elements: Vec<Element<'_>>) -> Option<Vec<StreamEntry>> {
+	let mut elements = elements.into_iter();
+
+	// the master entry: counts of valid and deleted entries, and the master fields
+	next_int(&mut elements)?;
+	next_int(&mut elements)?;
+	let master_fields = (0..next_int(&mut elements)?)
+		.map(|_| Some(elements.next()?.to_vec()))
+		.collect::<Option<Vec<_>>>()?;
+	next_int(&mut elements)?;
+
+	let mut entries = Vec::new();
+	while let Some(flags) = elements.next() {
+		let flags = flags.to_int()?;
+		let id = StreamId(
+			master.0.wrapping_add(next_int(&mut elements)? as u64),
+			master.1.wrapping_add(next_int(&mut elements)? as u64),
+		);
+
+		let fields = if flags & STREAM_FLAG_SAMEFIELDS != 0 {
+			master_fields
+				.iter()
+				.map(|field| Some((field.clone(), elements.next()?.to_vec())))
+				.collect::<Option<Vec<_>>>()?
+		} else {
+			(0..next_int(&mut elements)?)
+				.map(|_| Some((elements.next()?.to_vec(), elements.next()?.to_vec())))
+				.collect::<Option<Vec<_>>>()?
+		};
+
+		// the number of elements in the entry, for iterating backwards
+		next_int(&mut elements)?;
+
+		if flags & STREAM_FLAG_DELETED == 0 {
+			entries.push(StreamEntry { id, fields });
+		}
+	}
+
+	Some(entries)
+}
+
+fn stream(input: &mut Input<'_>, ty: u8) -> Result<Stream, Error<'static>> {
+	let mut stream = Stream::default();
+
+	for _ in 0..input.len()? {
+		let master = input.blob("stream node ID", |id| id.try_into().ok())?;
+		let master = StreamId::from_be_bytes(&master);
+		let entries = input.blob("stream node", |blob| {
+			stream_entries(master, encoding::listpack(blob)?)
+		})?;
+		stream.entries.extend(entries);
+	}
+
+	// the number of entries, which we already know
+	input.len()?;
+	stream.last_id = input.stream_id()?;
+	if ty >= TYPE_STREAM_LISTPACKS_2 {
+		stream.meta = Some((input.stream_id()?, input.stream_id()?, input.len()?));
+	}
+
+	for _ in 0..input.len()? {
+		let mut group = ConsumerGroup {
+			name: input.string()?.into_owned(),
+			last_id: input.stream_id()?,
+			..Default::default()
+		};
+		if ty >= TYPE_STREAM_LISTPACKS_2 {
+			group.entries_read = Some(input.len()?);
+		}
+
+		for _ in 0..input.len()? {
+			group.pending.push(PendingEntry {
+				id: input.raw_stream_id()?,
+				delivery_time: input.millis()?,
+				delivery_count: input.len()?,
+			});
+		}
+
+		for _ in 0..input.len()? {
+			let mut consumer = Consumer {
+				name: input.string()?.into_owned(),
+				seen_time: input.millis()?,
+				..Default::default()
+			};
+			if ty >= TYPE_STREAM_LISTPACKS_3 {
+				consumer.active_time = Some(input.millis()?);
+			}
+
+			for _ in 0..input.len()? {
+				consumer.pending.push(input.raw_stream_id()?);
+			}
+			group.consumers.push(consumer);
+		}
+
+		stream.groups.push(group);
+	}
+
+	Ok(stream)
+}
+
+pub(super) fn rdb(bytes: &[u8]) -> Result<Rdb, Error<'static>> {
+	let mut input = Input::new(bytes);
+	let version = bytes
+		.get(..9)
+		.and_then(|header| header.strip_prefix(b"REDIS"))
+		.and_then(|version| from_utf8(version).ok()?.parse().ok())
+		.ok_or_else(|| Error::Message("invalid RDB header".into()))?;
+	input.pos = 9;
+
+	let mut rdb = Rdb {
+		version,
+		..Default::default()
+	};
+	let (mut db, mut expire_at, mut idle, mut freq) = (0, None, None, None);
+
+	loop {
+		match input.u8()? {
+			OPCODE_EOF => break,
+			OPCODE_AUX => {
+				let key = input.string()?.into_owned();
+				rdb.aux.push((key, input.string()?.into_owned()));
+			}
+			OPCODE_FUNCTION2 => rdb.functions.push(input.string()?.into_owned()),
+			OPCODE_SELECTDB => db = input.len()?,
+			OPCODE_RESIZEDB => {
+				input.len()?;
+				input.len()?;
+			}
+			OPCODE_SLOT_INFO => {
+				input.len()?;
+				input.len()?;
+				input.len()?;
+			}
+			OPCODE_EXPIRETIME_MS => expire_at = Some(input.millis()?),
+			OPCODE_EXPIRETIME => {
+				expire_at = Some(u32::from_le_bytes(input.array()?) as u64 * 1000);
+			}
+			OPCODE_IDLE => idle = Some(input.len()?),
+			OPCODE_FREQ => freq = Some(input.u8()?),
+			ty @ TYPE_STRING..=TYPE_STREAM_LISTPACKS_3 => {
+				let key = input.string()?.into_owned();
+				rdb.entries.push(Entry {
+					db,
+					key,
+					value: value(&mut input, ty)?,
+					expire_at: expire_at.take(),
+					idle: idle.take(),
+					freq: freq.take(),
+				});
+			}
+			op => return Err(input.error(format_args!("unsupported opcode {:#04x}", op))),
+		}
+	}
+
+	// checksums were added in version 5, and are 0 if disabled
+	if version >= 5 {
+		let end = input.pos;
+		let checksum = u64::from_le_bytes(input.array()?);
+		if checksum != 0 && checksum != crc64(&bytes[..end]) {
+			return Err(Error::Message("RDB checksum mismatch".into()));
+		}
+	}
+
+	Ok(rdb)
+}
+
+pub(super) fn dump(payload: &[u8]) -> Result<Value, Error<'static>> {
+	// the value is followed by a 2 byte RDB version and an 8 byte checksum
+	let (body, checksum) = match payload.len().checked_sub(10) {
+		Some(len) if len > 0 => (&payload[..len], &payload[len + 2..]),
+		_ => return Err(Error::Message("DUMP payload too short".into())),
+	};
+
+	if checksum != crc64(&payload[..payload.len() - 8]).to_le_bytes() {
+		return Err(Error::Message("DUMP payload checksum mismatch".into()));
+	}
+
+	let mut input = Input::new(body);
+	let ty = input.u8()?;
+	let value = value(&mut input, ty)?;
+	if input.pos != body.len() {
+		return Err(input.error("unexpected trailing bytes"));
+	}
+
+	Ok(value)
+}
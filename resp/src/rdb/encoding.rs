@@ -0,0 +1,295 @@
+use alloc::{string::ToString, vec, vec::Vec};
+use core::str::from_utf8;
+
+/// An element of a listpack or ziplist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Element<'a> {
+	Int(i64),
+	Str(&'a [u8]),
+}
+
+impl Element<'_> {
+	/// The bytes of this element, with integers as decimal strings like Redis replies with.
+	pub fn to_vec(self) -> Vec<u8> {
+		match self {
+			Self::Int(i) => i.to_string().into_bytes(),
+			Self::Str(bytes) => bytes.to_vec(),
+		}
+	}
+
+	pub fn to_int(self) -> Option<i64> {
+		match self {
+			Self::Int(i) => Some(i),
+			Self::Str(bytes) => from_utf8(bytes).ok()?.parse().ok(),
+		}
+	}
+
+	pub fn to_f64(self) -> Option<f64> {
+		match self {
+			Self::Int(i) => Some(i as f64),
+			Self::Str(bytes) => from_utf8(bytes).ok()?.parse().ok(),
+		}
+	}
+}
+
+/// Take `len` bytes from the start of `input`.
+fn take<'a>(input: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+	if input.len() < len {
+		return None;
+	}
+
+	let (bytes, rest) = input.split_at(len);
+	*input = rest;
+	Some(bytes)
+}
+
+fn le_int(bytes: &[u8]) -> i64 {
+	let mut buf = [0; 8];
+	buf[..bytes.len()].copy_from_slice(bytes);
+	let shift = 64 - bytes.len() * 8;
+	(i64::from_le_bytes(buf) << shift) >> shift
+}
+
+/// Sign extend the lowest `bits` of `value`.
+fn sign_extend(value: u64, bits: u32) -> i64 {
+	let shift = 64 - bits;
+	((value << shift) as i64) >> shift
+}
+
+/// The number of bytes used to store the length `len` of a listpack entry after it.
+fn backlen_size(len: usize) -> usize {
+	match len {
+		0..=127 => 1,
+		128..=16382 => 2,
+		16383..=2097150 => 3,
+		2097151..=268435454 => 4,
+		_ => 5,
+	}
+}
+
+/// Decode the elements of a listpack.
+pub(crate) fn listpack(blob: &[u8]) -> Option<Vec<Element<'_>>> {
+	let mut input = blob;
+	let total = u32::from_le_bytes(take(&mut input, 4)?.try_into().unwrap());
+	if total as usize != blob.len() {
+		return None;
+	}
+
+	// the count saturates, so it's only a hint
+	let count = u16::from_le_bytes(take(&mut input, 2)?.try_into().unwrap());
+	let mut elements = Vec::with_capacity(count as usize);
+
+	loop {
+		let entry = input;
+		let b = *input.first()?;
+		let (element, len) = match b {
+			0xff => break,
+			0x00..=0x7f => (Element::Int(b as i64), 1),
+			0x80..=0xbf => {
+				let len = (b & 0x3f) as usize;
+				(Element::Str(entry.get(1..1 + len)?), 1 + len)
+			}
+			0xc0..=0xdf => {
+				let value = ((b as u64 & 0x1f) << 8) | *entry.get(1)? as u64;
+				(Element::Int(sign_extend(value, 13)), 2)
+			}
+			0xe0..=0xef => {
+				let len = ((b as usize & 0x0f) << 8) | *entry.get(1)? as usize;
+				(Element::Str(entry.get(2..2 + len)?), 2 + len)
+			}
+			0xf0 => {
+				let len = u32::from_le_bytes(entry.get(1..5)?.try_into().unwrap()) as usize;
+				(Element::Str(entry.get(5..5 + len)?), 5 + len)
+			}
+			0xf1..=0xf4 => {
+				let size = [2, 3, 4, 8][(b - 0xf1) as usize];
+				(Element::Int(le_int(entry.get(1..1 + size)?)), 1 + size)
+			}
+			_ => return None,
+		};
+
+		take(&mut input, len + backlen_size(len))?;
+		elements.push(element);
+	}
+
+	Some(elements)
+}
+
+/// Decode the elements of a ziplist, which Redis 6 and earlier use instead of listpacks.
+pub(crate) fn ziplist(blob: &[u8]) -> Option<Vec<Element<'_>>> {
+	let mut input = blob;
+	let total = u32::from_le_bytes(take(&mut input, 4)?.try_into().unwrap());
+	if total as usize != blob.len() {
+		return None;
+	}
+
+	take(&mut input, 4)?;
+	let count = u16::from_le_bytes(take(&mut input, 2)?.try_into().unwrap());
+	let mut elements = Vec::with_capacity(count as usize);
+
+	loop {
+		// skip the length of the previous entry
+		match *input.first()? {
+			0xff => break,
+			0xfe => take(&mut input, 5)?,
+			_ => take(&mut input, 1)?,
+		};
+
+		let b = take(&mut input, 1)?[0];
+		let element = match b {
+			0x00..=0x3f => Element::Str(take(&mut input, (b & 0x3f) as usize)?),
+			0x40..=0x7f => {
+				let len = ((b as usize & 0x3f) << 8) | take(&mut input, 1)?[0] as usize;
+				Element::Str(take(&mut input, len)?)
+			}
+			0x80 => {
+				let len = u32::from_be_bytes(take(&mut input, 4)?.try_into().unwrap());
+				Element::Str(take(&mut input, len as usize)?)
+			}
+			0xc0 => Element::Int(le_int(take(&mut input, 2)?)),
+			0xd0 => Element::Int(le_int(take(&mut input, 4)?)),
+			0xe0 => Element::Int(le_int(take(&mut input, 8)?)),
+			0xf0 => Element::Int(le_int(take(&mut input, 3)?)),
+			0xfe => Element::Int(le_int(take(&mut input, 1)?)),
+			0xf1..=0xfd => Element::Int((b & 0x0f) as i64 - 1),
+			_ => return None,
+		};
+
+		elements.push(element);
+	}
+
+	Some(elements)
+}
+
+/// Decode the integers of an intset.
+pub(crate) fn intset(blob: &[u8]) -> Option<Vec<i64>> {
+	let mut input = blob;
+	let size = u32::from_le_bytes(take(&mut input, 4)?.try_into().unwrap()) as usize;
+	let len = u32::from_le_bytes(take(&mut input, 4)?.try_into().unwrap()) as usize;
+	if !matches!(size, 2 | 4 | 8) || input.len() != size.checked_mul(len)? {
+		return None;
+	}
+
+	Some(input.chunks_exact(size).map(le_int).collect())
+}
+
+/// Writer for listpacks.
+#[derive(Debug)]
+pub(crate) struct ListpackWriter {
+	buf: Vec<u8>,
+	count: usize,
+}
+
+impl ListpackWriter {
+	pub fn new() -> Self {
+		Self {
+			buf: vec![0; 6],
+			count: 0,
+		}
+	}
+
+	fn push_backlen(&mut self, len: usize) {
+		let size = backlen_size(len);
+		self.buf.push((len >> (7 * (size - 1))) as u8);
+		for i in (0..size - 1).rev() {
+			self.buf.push((len >> (7 * i)) as u8 & 0x7f | 0x80);
+		}
+
+		self.count += 1;
+	}
+
+	pub fn push_int(&mut self, i: i64) {
+		if (0..=0x7f).contains(&i) {
+			self.buf.push(i as u8);
+			self.push_backlen(1);
+		} else {
+			self.buf.push(0xf4);
+			self.buf.extend_from_slice(&i.to_le_bytes());
+			self.push_backlen(9);
+		}
+	}
+
+	pub fn push_str(&mut self, bytes: &[u8]) {
+		let len = bytes.len();
+		let header = match len {
+			0..=0x3f => 1,
+			0x40..=0xfff => 2,
+			_ => 5,
+		};
+
+		match header {
+			1 => self.buf.push(0x80 | len as u8),
+			2 => self
+				.buf
+				.extend_from_slice(&[0xe0 | (len >> 8) as u8, len as u8]),
+			_ => {
+				self.buf.push(0xf0);
+				self.buf.extend_from_slice(&(len as u32).to_le_bytes());
+			}
+		}
+
+		self.buf.extend_from_slice(bytes);
+		self.push_backlen(header + len);
+	}
+
+	pub fn finish(mut self) -> Vec<u8> {
+		self.buf.push(0xff);
+
+		let total = self.buf.len() as u32;
+		let count = self.count.min(u16::MAX as usize) as u16;
+		self.buf[..4].copy_from_slice(&total.to_le_bytes());
+		self.buf[4..6].copy_from_slice(&count.to_le_bytes());
+		self.buf
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::{intset, listpack, ziplist, Element, ListpackWriter};
+
+	#[test]
+	fn test_listpack() {
+		let long = vec![b'x'; 200];
+		let mut writer = ListpackWriter::new();
+		writer.push_int(5);
+		writer.push_int(-1000);
+		writer.push_str(b"foo");
+		writer.push_str(&long);
+		let blob = writer.finish();
+
+		assert_eq!(
+			listpack(&blob).unwrap(),
+			[
+				Element::Int(5),
+				Element::Int(-1000),
+				Element::Str(b"foo"),
+				Element::Str(&long)
+			]
+		);
+
+		// as written by Redis: 13 and 24 bit integers
+		let blob = b"\x0f\x00\x00\x00\x02\x00\xdf\xff\x02\xf2\x00\x00\x80\x04\xff";
+		assert_eq!(
+			listpack(blob).unwrap(),
+			[Element::Int(-1), Element::Int(-0x80_0000)]
+		);
+		assert_eq!(listpack(&blob[..10]), None);
+	}
+
+	#[test]
+	fn test_ziplist() {
+		let blob =
+			b"\x16\x00\x00\x00\x11\x00\x00\x00\x03\x00\x00\x03foo\x05\xf2\x02\xc0\x00\x01\xff";
+		assert_eq!(
+			ziplist(blob).unwrap(),
+			[Element::Str(b"foo"), Element::Int(1), Element::Int(256)]
+		);
+	}
+
+	#[test]
+	fn test_intset() {
+		let blob = b"\x02\x00\x00\x00\x02\x00\x00\x00\x01\x00\xff\xff";
+		assert_eq!(intset(blob).unwrap(), [1, -1]);
+		assert_eq!(intset(&blob[..11]), None);
+	}
+}
@@ -0,0 +1,55 @@
+use alloc::vec::Vec;
+
+/// The most bytes that each input byte can decompress to, from a 3 byte back reference of 264
+/// bytes.
+const MAX_RATIO: usize = 88;
+
+/// Decompress LZF `input` into `len` bytes, or `None` if it is invalid.
+pub(crate) fn decompress(input: &[u8], len: usize) -> Option<Vec<u8>> {
+	// `len` comes from the input, so don't trust it any more than the input can back up
+	let mut out = Vec::with_capacity(len.min(input.len().saturating_mul(MAX_RATIO)));
+	let mut input = input.iter().copied();
+
+	while let Some(ctrl) = input.next() {
+		let ctrl = ctrl as usize;
+		if ctrl < 32 {
+			// a run of ctrl + 1 literal bytes
+			for _ in 0..=ctrl {
+				out.push(input.next()?);
+			}
+		} else {
+			// a back reference of at least 3 bytes
+			let mut run = ctrl >> 5;
+			if run == 7 {
+				run += input.next()? as usize;
+			}
+
+			let back = ((ctrl & 0x1f) << 8) + input.next()? as usize + 1;
+			let start = out.len().checked_sub(back)?;
+			for i in start..start + run + 2 {
+				out.push(out[i]);
+			}
+		}
+
+		if out.len() > len {
+			return None;
+		}
+	}
+
+	(out.len() == len).then_some(out)
+}
+
+#[cfg(test)]
+mod test {
+	use super::decompress;
+
+	#[test]
+	fn test_lzf() {
+		let input = [0x02, b'a', b'b', b'c', 0xe0, 0x01, 0x02, 0x20, 0x00];
+		assert_eq!(decompress(&input, 16).unwrap(), b"abcabcabcabcaaaa");
+
+		assert_eq!(decompress(&input, 15), None);
+		assert_eq!(decompress(&[0x20, 0x05], 3), None);
+		assert_eq!(decompress(&input, 1 << 62), None);
+	}
+}
@@ -0,0 +1,160 @@
+use alloc::vec::Vec;
+
+use super::{crc64::crc64, encoding::ListpackWriter, *};
+
+fn write_len(buf: &mut Vec<u8>, len: u64) {
+	match len {
+		0..=0x3f => buf.push(len as u8),
+		0x40..=0x3fff => buf.extend_from_slice(&[0x40 | (len >> 8) as u8, len as u8]),
+		_ => match u32::try_from(len) {
+			Ok(len) => {
+				buf.push(0x80);
+				buf.extend_from_slice(&len.to_be_bytes());
+			}
+			Err(_) => {
+				buf.push(0x81);
+				buf.extend_from_slice(&len.to_be_bytes());
+			}
+		},
+	}
+}
+
+fn write_string(buf: &mut Vec<u8>, bytes: &[u8]) {
+	write_len(buf, bytes.len() as u64);
+	buf.extend_from_slice(bytes);
+}
+
+/// Encode the entries of a stream into a single listpack node, with the fields of the first entry
+/// as the master fields.
+fn stream_node(entries: &[StreamEntry]) -> Vec<u8> {
+	let master = &entries[0];
+	let mut lp = ListpackWriter::new();
+	lp.push_int(entries.len() as i64);
+	lp.push_int(0);
+	lp.push_int(master.fields.len() as i64);
+	for (field, _) in &master.fields {
+		lp.push_str(field);
+	}
+	lp.push_int(0);
+
+	for entry in entries {
+		let same_fields = entry.fields.len() == master.fields.len()
+			&& entry
+				.fields
+				.iter()
+				.zip(&master.fields)
+				.all(|((a, _), (b, _))| a == b);
+
+		lp.push_int(if same_fields {
+			STREAM_FLAG_SAMEFIELDS
+		} else {
+			0
+		});
+		lp.push_int(entry.id.0.wrapping_sub(master.id.0) as i64);
+		lp.push_int(entry.id.1.wrapping_sub(master.id.1) as i64);
+
+		let len = entry.fields.len() as i64;
+		if same_fields {
+			for (_, value) in &entry.fields {
+				lp.push_str(value);
+			}
+			lp.push_int(3 + len);
+		} else {
+			lp.push_int(len);
+			for (field, value) in &entry.fields {
+				lp.push_str(field);
+				lp.push_str(value);
+			}
+			lp.push_int(4 + len * 2);
+		}
+	}
+
+	lp.finish()
+}
+
+fn write_stream(buf: &mut Vec<u8>, stream: &Stream) {
+	match stream.entries.first() {
+		Some(first) => {
+			write_len(buf, 1);
+			write_string(buf, &first.id.to_be_bytes());
+			write_string(buf, &stream_node(&stream.entries));
+		}
+		None => write_len(buf, 0),
+	}
+
+	write_len(buf, stream.entries.len() as u64);
+	write_len(buf, stream.last_id.0);
+	write_len(buf, stream.last_id.1);
+
+	write_len(buf, stream.groups.len() as u64);
+	for group in &stream.groups {
+		write_string(buf, &group.name);
+		write_len(buf, group.last_id.0);
+		write_len(buf, group.last_id.1);
+
+		write_len(buf, group.pending.len() as u64);
+		for pending in &group.pending {
+			buf.extend_from_slice(&pending.id.to_be_bytes());
+			buf.extend_from_slice(&pending.delivery_time.to_le_bytes());
+			write_len(buf, pending.delivery_count);
+		}
+
+		write_len(buf, group.consumers.len() as u64);
+		for consumer in &group.consumers {
+			write_string(buf, &consumer.name);
+			buf.extend_from_slice(&consumer.seen_time.to_le_bytes());
+			write_len(buf, consumer.pending.len() as u64);
+			for id in &consumer.pending {
+				buf.extend_from_slice(&id.to_be_bytes());
+			}
+		}
+	}
+}
+
+/// Encode `value` with the types supported by [DUMP_VERSION]. The Redis 7 metadata of streams
+/// isn't encoded, so Redis recalculates it.
+pub(super) fn dump(value: &Value) -> Vec<u8> {
+	let mut buf = Vec::new();
+
+	match value {
+		Value::String(bytes) => {
+			buf.push(TYPE_STRING);
+			write_string(&mut buf, bytes);
+		}
+		Value::List(elements) | Value::Set(elements) => {
+			buf.push(match value {
+				Value::List(_) => TYPE_LIST,
+				_ => TYPE_SET,
+			});
+			write_len(&mut buf, elements.len() as u64);
+			for element in elements {
+				write_string(&mut buf, element);
+			}
+		}
+		Value::SortedSet(members) => {
+			buf.push(TYPE_ZSET_2);
+			write_len(&mut buf, members.len() as u64);
+			for (member, score) in members {
+				write_string(&mut buf, member);
+				buf.extend_from_slice(&score.to_le_bytes());
+			}
+		}
+		Value::Hash(fields) => {
+			buf.push(TYPE_HASH);
+			write_len(&mut buf, fields.len() as u64);
+			for (field, value) in fields {
+				write_string(&mut buf, field);
+				write_string(&mut buf, value);
+			}
+		}
+		Value::Stream(stream) => {
+			buf.push(TYPE_STREAM_LISTPACKS);
+			write_stream(&mut buf, stream);
+		}
+	}
+
+	buf.extend_from_slice(&DUMP_VERSION.to_le_bytes());
+	let checksum = crc64(&buf);
+	buf.extend_from_slice(&checksum.to_le_bytes());
+	buf
+}
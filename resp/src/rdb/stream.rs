@@ -0,0 +1,90 @@
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter};
+
+/// A stream ID, displayed like `1526919030474-55`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StreamId(
+	/// The timestamp, in milliseconds
+	pub u64,
+	/// The sequence number
+	pub u64,
+);
+
+impl StreamId {
+	pub(crate) fn from_be_bytes(bytes: &[u8; 16]) -> Self {
+		let (ms, seq) = bytes.split_at(8);
+		Self(
+			u64::from_be_bytes(ms.try_into().unwrap()),
+			u64::from_be_bytes(seq.try_into().unwrap()),
+		)
+	}
+
+	pub(crate) fn to_be_bytes(self) -> [u8; 16] {
+		let mut bytes = [0; 16];
+		bytes[..8].copy_from_slice(&self.0.to_be_bytes());
+		bytes[8..].copy_from_slice(&self.1.to_be_bytes());
+		bytes
+	}
+}
+
+impl Display for StreamId {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		write!(f, "{}-{}", self.0, self.1)
+	}
+}
+
+/// An entry in a [Stream].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StreamEntry {
+	pub id: StreamId,
+	/// Fields and their values.
+	pub fields: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+/// A stream and its consumer groups.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Stream {
+	/// The entries which haven't been deleted, in order.
+	pub entries: Vec<StreamEntry>,
+	/// The ID of the last entry added, which may have been deleted.
+	pub last_id: StreamId,
+	/// The ID of the first entry, the ID of the last deleted entry and the number of entries ever
+	/// added. These are only stored by Redis 7 and later.
+	pub meta: Option<(StreamId, StreamId, u64)>,
+	pub groups: Vec<ConsumerGroup>,
+}
+
+/// A consumer group of a [Stream].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ConsumerGroup {
+	pub name: Vec<u8>,
+	/// The ID of the last entry delivered to the group.
+	pub last_id: StreamId,
+	/// The number of entries read by the group, which is only stored by Redis 7 and later.
+	pub entries_read: Option<u64>,
+	/// The entries which have been delivered but not acknowledged.
+	pub pending: Vec<PendingEntry>,
+	pub consumers: Vec<Consumer>,
+}
+
+/// An entry which has been delivered to a [ConsumerGroup] but not acknowledged.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct PendingEntry {
+	pub id: StreamId,
+	/// When the entry was last delivered, in milliseconds since the Unix epoch.
+	pub delivery_time: u64,
+	/// The number of times the entry has been delivered.
+	pub delivery_count: u64,
+}
+
+/// A consumer in a [ConsumerGroup].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Consumer {
+	pub name: Vec<u8>,
+	/// When the consumer was last seen, in milliseconds since the Unix epoch.
+	pub seen_time: u64,
+	/// When the consumer last read an entry, which is only stored by Redis 7.2 and later.
+	pub active_time: Option<u64>,
+	/// The IDs of the group's pending entries which were delivered to this consumer.
+	pub pending: Vec<StreamId>,
+}
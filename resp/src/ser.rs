@@ -1,24 +1,87 @@
-use std::io::Write;
+use core::fmt;
 
 use serde::Serialize;
 
-use crate::Result;
+use crate::{Error, Result};
 
 mod serializer;
 
 pub use serializer::*;
 
+/// A buffer which RESP can be serialized into.
+///
+/// With the `std` feature, this is implemented for every [std::io::Write]. Without it, this is
+/// implemented for [`Vec<u8>`](alloc::vec::Vec) and [BytesMut](bytes::BytesMut).
+pub trait Write {
+	/// Write all of `bytes` to the buffer.
+	fn write_all(&mut self, bytes: &[u8]) -> Result<'static, ()>;
+
+	/// Write formatted text to the buffer, so that [write!] can be used.
+	fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> Result<'static, ()> {
+		struct Adapter<'w, W: ?Sized> {
+			inner: &'w mut W,
+			error: Option<Error<'static>>,
+		}
+
+		impl<W: Write + ?Sized> fmt::Write for Adapter<'_, W> {
+			fn write_str(&mut self, s: &str) -> fmt::Result {
+				self.inner.write_all(s.as_bytes()).map_err(|err| {
+					self.error = Some(err);
+					fmt::Error
+				})
+			}
+		}
+
+		let mut adapter = Adapter {
+			inner: self,
+			error: None,
+		};
+		fmt::write(&mut adapter, args).map_err(|_| {
+			adapter
+				.error
+				.unwrap_or_else(|| Error::Message("formatter error".into()))
+		})
+	}
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write + ?Sized> Write for W {
+	fn write_all(&mut self, bytes: &[u8]) -> Result<'static, ()> {
+		Ok(std::io::Write::write_all(self, bytes)?)
+	}
+}
+
+#[cfg(not(feature = "std"))]
+impl Write for alloc::vec::Vec<u8> {
+	fn write_all(&mut self, bytes: &[u8]) -> Result<'static, ()> {
+		self.extend_from_slice(bytes);
+		Ok(())
+	}
+}
+
+#[cfg(not(feature = "std"))]
+impl Write for bytes::BytesMut {
+	fn write_all(&mut self, bytes: &[u8]) -> Result<'static, ()> {
+		self.extend_from_slice(bytes);
+		Ok(())
+	}
+}
+
+#[cfg(not(feature = "std"))]
+impl<W: Write + ?Sized> Write for &mut W {
+	fn write_all(&mut self, bytes: &[u8]) -> Result<'static, ()> {
+		(**self).write_all(bytes)
+	}
+}
+
 /// Serialize to a writer using RESP.
 #[tracing::instrument(level = "trace", err, skip_all)]
-pub fn to_bytes<T, W>(value: &T, output: W) -> Result<()>
+pub fn to_bytes<T, W>(value: &T, output: W) -> Result<'_, ()>
 where
 	T: Serialize,
 	W: Write,
 {
-	let mut serializer = Serializer {
-		output,
-		options: Options::default(),
-	};
+	let mut serializer = Serializer::new(output, Options::default());
 	value.serialize(&mut serializer)?;
 	Ok(())
 }
@@ -27,9 +90,11 @@ where
 mod test {
 	use bytes::{BufMut, BytesMut};
 
-	use crate::Data;
+	use serde::Serialize;
 
-	use super::to_bytes;
+	use crate::{array, from_bytes, Data};
+
+	use super::{to_bytes, Options, Serializer};
 
 	#[test]
 	fn ser_str() {
@@ -39,4 +104,67 @@ mod test {
 
 		assert_eq!(writer.get_ref(), &b"+OK\r\n"[..]);
 	}
+
+	#[test]
+	fn ser_null() {
+		let mut writer = BytesMut::new().writer();
+		to_bytes(&Data::Null, &mut writer).unwrap();
+
+		assert_eq!(writer.get_ref(), &b"$-1\r\n"[..]);
+	}
+
+	#[test]
+	fn ser_float() {
+		let mut serializer = Serializer::new(Vec::new(), Options::default());
+		(1.0 / 3.0, f64::NAN, f32::NEG_INFINITY, 0.1f32)
+			.serialize(&mut serializer)
+			.unwrap();
+		assert_eq!(
+			serializer.output,
+			b"*4\r\n+0.3333333333333333\r\n+nan\r\n+-inf\r\n+0.1\r\n"
+		);
+
+		let options = Options {
+			float_precision: Some(17),
+			..Default::default()
+		};
+		let mut serializer = Serializer::new(Vec::new(), options);
+		(0.1 + 0.2, 1e300, Data::Double(2.5))
+			.serialize(&mut serializer)
+			.unwrap();
+		assert_eq!(
+			serializer.output,
+			&b"*3\r\n+0.30000000000000004\r\n+1.0000000000000001e+300\r\n,2.5\r\n"[..]
+		);
+
+		let (res, _) = from_bytes::<(f64, f64, f64)>(&serializer.output).unwrap();
+		assert_eq!(res, (0.1 + 0.2, 1e300, 2.5));
+	}
+
+	#[test]
+	fn ser_resp3() {
+		let data = array!(
+			Data::Map(vec![(Data::simple_string("foo"), Data::Double(1.5))]),
+			Data::Set(vec![Data::Boolean(true)]),
+			Data::Push(vec![Data::BigNumber("-123".into())]),
+			Data::VerbatimString {
+				format: *b"txt",
+				text: b"bar"[..].into()
+			},
+			Data::Attribute {
+				attributes: vec![(Data::simple_string("ttl"), Data::Integer(1))],
+				data: Box::new(Data::Double(f64::NEG_INFINITY)),
+			},
+			Data::Error("ERR".into())
+		);
+		let mut writer = BytesMut::new().writer();
+		to_bytes(&data, &mut writer).unwrap();
+
+		let expected = b"*6\r\n%1\r\n+foo\r\n,1.5\r\n~1\r\n#t\r\n>1\r\n(-123\r\n=7\r\ntxt:bar\r\n|1\r\n+ttl\r\n:1\r\n,-inf\r\n-ERR\r\n";
+		assert_eq!(writer.get_ref(), &expected[..]);
+
+		let (res, rem) = from_bytes::<Data>(writer.get_ref()).unwrap();
+		assert_eq!(res, data);
+		assert_eq!(rem, b"");
+	}
 }
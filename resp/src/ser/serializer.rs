@@ -1,20 +1,19 @@
-use std::{fmt::Display, io::Write};
+use alloc::string::ToString;
+use core::fmt::{Display, LowerExp};
 
 use serde::ser;
 
-use crate::Error;
+use super::Write;
+use crate::{data::token, float::Float, Error};
 
 /// Null types available in RESP.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub enum NullType {
+	#[default]
 	BulkString,
 	Array,
-}
-
-impl Default for NullType {
-	fn default() -> Self {
-		Self::BulkString
-	}
+	/// The RESP3 null.
+	Null,
 }
 
 /// Options for the RESP [Serializer].
@@ -22,6 +21,12 @@ impl Default for NullType {
 pub struct Options {
 	/// The type to use for serializing missing Optional values.
 	pub null_type: NullType,
+	/// The number of significant digits to write floats with, like `%.{precision}g` in C. If
+	/// `None`, floats are written like `%.17g`, as Redis does, but with the fewest digits which
+	/// parse back to the same value, such as `0.1` or `1e+300`.
+	///
+	/// Either way, the special values are written as `inf`, `-inf` and `nan`, like Redis.
+	pub float_precision: Option<usize>,
 }
 
 /// RESP serializer.
@@ -29,6 +34,26 @@ pub struct Options {
 pub struct Serializer<W> {
 	pub output: W,
 	pub options: Options,
+	/// The RESP3 prefix to use for the next value, set when serializing [Data](crate::Data).
+	prefix: Option<u8>,
+}
+
+impl<W> Serializer<W> {
+	/// Make a new serializer.
+	pub fn new(output: W, options: Options) -> Self {
+		Self {
+			output,
+			options,
+			prefix: None,
+		}
+	}
+
+	fn take_prefix(&mut self, allowed: &[u8], default: u8) -> u8 {
+		self.prefix
+			.take()
+			.filter(|prefix| allowed.contains(prefix))
+			.unwrap_or(default)
+	}
 }
 
 impl<W> Serializer<W>
@@ -41,9 +66,22 @@ where
 	{
 		Ok(write!(self.output, ":{}\r\n", v)?)
 	}
+
+	fn serialize_float<T>(&mut self, v: T) -> crate::Result<'static, ()>
+	where
+		T: Display + LowerExp + Into<f64> + Copy,
+	{
+		let float = Float::new(v, self.options.float_precision);
+		match self.take_prefix(b",", b'+') {
+			b',' => write!(self.output, ",{}\r\n", float)?,
+			_ => write!(self.output, "+{}\r\n", float)?,
+		}
+
+		Ok(())
+	}
 }
 
-impl<'a, W> ser::Serializer for &'a mut Serializer<W>
+impl<W> ser::Serializer for &mut Serializer<W>
 where
 	W: Write,
 {
@@ -66,7 +104,13 @@ where
 	type SerializeStructVariant = Self;
 
 	fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
-		self.serialize_str(&v.to_string())
+		match self.take_prefix(b"#", b'+') {
+			b'#' if v => self.output.write_all(b"#t\r\n")?,
+			b'#' => self.output.write_all(b"#f\r\n")?,
+			_ => write!(self.output, "+{}\r\n", v)?,
+		}
+
+		Ok(())
 	}
 
 	fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
@@ -102,11 +146,11 @@ where
 	}
 
 	fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
-		self.serialize_str(&v.to_string())
+		self.serialize_float(v)
 	}
 
 	fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
-		self.serialize_str(&v.to_string())
+		self.serialize_float(v)
 	}
 
 	fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
@@ -114,12 +158,20 @@ where
 	}
 
 	fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
-		write!(self.output, "+{}\r\n", v)?;
+		let prefix = self.take_prefix(b"(-", b'+') as char;
+		if prefix == '-' && v.contains(['\r', '\n']) {
+			// simple errors end at the first line break, so these are written as blob errors
+			write!(self.output, "!{}\r\n{}\r\n", v.len(), v)?;
+		} else {
+			write!(self.output, "{}{}\r\n", prefix, v)?;
+		}
+
 		Ok(())
 	}
 
 	fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
-		write!(self.output, "${}\r\n", v.len())?;
+		let prefix = self.take_prefix(b"=", b'$') as char;
+		write!(self.output, "{}{}\r\n", prefix, v.len())?;
 		self.output.write_all(v)?;
 		self.output.write_all(b"\r\n")?;
 
@@ -130,17 +182,18 @@ where
 		self.serialize_unit()
 	}
 
-	fn serialize_some<T: ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error>
+	fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
 	where
-		T: serde::Serialize,
+		T: ?Sized + serde::Serialize,
 	{
 		value.serialize(self)
 	}
 
 	fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
 		match self.options.null_type {
-			NullType::Array => self.output.write_all(b"*-1\r\n\r\n")?,
-			NullType::BulkString => self.output.write_all(b"$-1\r\n\r\n")?,
+			NullType::Array => self.output.write_all(b"*-1\r\n")?,
+			NullType::BulkString => self.output.write_all(b"$-1\r\n")?,
+			NullType::Null => self.output.write_all(b"_\r\n")?,
 		}
 
 		Ok(())
@@ -159,18 +212,31 @@ where
 		self.serialize_str(variant)
 	}
 
-	fn serialize_newtype_struct<T: ?Sized>(
+	fn serialize_newtype_struct<T>(
 		self,
-		_name: &'static str,
+		name: &'static str,
 		value: &T,
 	) -> Result<Self::Ok, Self::Error>
 	where
-		T: serde::Serialize,
+		T: ?Sized + serde::Serialize,
 	{
+		self.prefix = match name {
+			token::MAP => Some(b'%'),
+			token::SET => Some(b'~'),
+			token::PUSH => Some(b'>'),
+			token::DOUBLE => Some(b','),
+			token::BOOLEAN => Some(b'#'),
+			token::BIG_NUMBER => Some(b'('),
+			token::VERBATIM_STRING => Some(b'='),
+			token::ATTRIBUTE => Some(b'|'),
+			token::ERROR => Some(b'-'),
+			_ => None,
+		};
+
 		value.serialize(self)
 	}
 
-	fn serialize_newtype_variant<T: ?Sized>(
+	fn serialize_newtype_variant<T>(
 		self,
 		_name: &'static str,
 		_variant_index: u32,
@@ -178,7 +244,7 @@ where
 		value: &T,
 	) -> Result<Self::Ok, Self::Error>
 	where
-		T: serde::Serialize,
+		T: ?Sized + serde::Serialize,
 	{
 		write!(self.output, "*2\r\n{}\r\n", variant)?;
 		value.serialize(self)?;
@@ -189,12 +255,19 @@ where
 	fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
 		let len =
 			len.ok_or_else::<Self::Error, _>(|| ser::Error::custom("sequence length required"))?;
-		write!(self.output, "*{}\r\n", len)?;
+		let prefix = self.take_prefix(b"~>", b'*') as char;
+		write!(self.output, "{}{}\r\n", prefix, len)?;
 
 		Ok(self)
 	}
 
 	fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+		// An attribute is a tuple of its entries and the data it's attached to, but the entries
+		// are written as a map with the attribute prefix and are directly followed by the data.
+		if self.prefix == Some(b'|') {
+			return Ok(self);
+		}
+
 		self.serialize_seq(Some(len))
 	}
 
@@ -219,7 +292,16 @@ where
 	}
 
 	fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
-		self.serialize_seq(len.map(|l| l * 2))
+		match self.take_prefix(b"%|", b'*') {
+			b'*' => self.serialize_seq(len.map(|l| l * 2)),
+			prefix => {
+				let len =
+					len.ok_or_else::<Self::Error, _>(|| ser::Error::custom("map length required"))?;
+				write!(self.output, "{}{}\r\n", prefix as char, len)?;
+
+				Ok(self)
+			}
+		}
 	}
 
 	fn serialize_struct(
@@ -243,7 +325,7 @@ where
 	}
 }
 
-impl<'a, W> ser::SerializeSeq for &'a mut Serializer<W>
+impl<W> ser::SerializeSeq for &mut Serializer<W>
 where
 	W: Write,
 {
@@ -251,9 +333,9 @@ where
 
 	type Error = Error<'static>;
 
-	fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+	fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
 	where
-		T: serde::Serialize,
+		T: ?Sized + serde::Serialize,
 	{
 		value.serialize(&mut **self)
 	}
@@ -263,7 +345,7 @@ where
 	}
 }
 
-impl<'a, W> ser::SerializeTuple for &'a mut Serializer<W>
+impl<W> ser::SerializeTuple for &mut Serializer<W>
 where
 	W: Write,
 {
@@ -271,9 +353,9 @@ where
 
 	type Error = Error<'static>;
 
-	fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+	fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
 	where
-		T: serde::Serialize,
+		T: ?Sized + serde::Serialize,
 	{
 		value.serialize(&mut **self)
 	}
@@ -283,7 +365,7 @@ where
 	}
 }
 
-impl<'a, W> ser::SerializeTupleStruct for &'a mut Serializer<W>
+impl<W> ser::SerializeTupleStruct for &mut Serializer<W>
 where
 	W: Write,
 {
@@ -291,9 +373,9 @@ where
 
 	type Error = Error<'static>;
 
-	fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+	fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
 	where
-		T: serde::Serialize,
+		T: ?Sized + serde::Serialize,
 	{
 		value.serialize(&mut **self)
 	}
@@ -303,7 +385,7 @@ where
 	}
 }
 
-impl<'a, W> ser::SerializeTupleVariant for &'a mut Serializer<W>
+impl<W> ser::SerializeTupleVariant for &mut Serializer<W>
 where
 	W: Write,
 {
@@ -311,9 +393,9 @@ where
 
 	type Error = Error<'static>;
 
-	fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+	fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
 	where
-		T: serde::Serialize,
+		T: ?Sized + serde::Serialize,
 	{
 		value.serialize(&mut **self)
 	}
@@ -323,7 +405,7 @@ where
 	}
 }
 
-impl<'a, W> ser::SerializeMap for &'a mut Serializer<W>
+impl<W> ser::SerializeMap for &mut Serializer<W>
 where
 	W: Write,
 {
@@ -331,16 +413,16 @@ where
 
 	type Error = Error<'static>;
 
-	fn serialize_key<T: ?Sized>(&mut self, key: &T) -> Result<(), Self::Error>
+	fn serialize_key<T>(&mut self, key: &T) -> Result<(), Self::Error>
 	where
-		T: serde::Serialize,
+		T: ?Sized + serde::Serialize,
 	{
 		key.serialize(&mut **self)
 	}
 
-	fn serialize_value<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+	fn serialize_value<T>(&mut self, value: &T) -> Result<(), Self::Error>
 	where
-		T: serde::Serialize,
+		T: ?Sized + serde::Serialize,
 	{
 		value.serialize(&mut **self)
 	}
@@ -350,7 +432,7 @@ where
 	}
 }
 
-impl<'a, W> ser::SerializeStruct for &'a mut Serializer<W>
+impl<W> ser::SerializeStruct for &mut Serializer<W>
 where
 	W: Write,
 {
@@ -358,13 +440,9 @@ where
 
 	type Error = Error<'static>;
 
-	fn serialize_field<T: ?Sized>(
-		&mut self,
-		key: &'static str,
-		value: &T,
-	) -> Result<(), Self::Error>
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
 	where
-		T: serde::Serialize,
+		T: ?Sized + serde::Serialize,
 	{
 		ser::Serialize::serialize(key, &mut **self)?;
 		ser::Serialize::serialize(value, &mut **self)?;
@@ -377,7 +455,7 @@ where
 	}
 }
 
-impl<'a, W> ser::SerializeStructVariant for &'a mut Serializer<W>
+impl<W> ser::SerializeStructVariant for &mut Serializer<W>
 where
 	W: Write,
 {
@@ -385,13 +463,9 @@ where
 
 	type Error = Error<'static>;
 
-	fn serialize_field<T: ?Sized>(
-		&mut self,
-		key: &'static str,
-		value: &T,
-	) -> Result<(), Self::Error>
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
 	where
-		T: serde::Serialize,
+		T: ?Sized + serde::Serialize,
 	{
 		ser::Serialize::serialize(key, &mut **self)?;
 		ser::Serialize::serialize(value, &mut **self)?;
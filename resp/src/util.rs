@@ -13,6 +13,7 @@
 ///     bar: HashMap<String, isize>,
 /// }
 /// ```
+#[cfg(feature = "std")]
 pub mod tuple_map {
 	use std::{collections::HashMap, hash::Hash, marker::PhantomData};
 
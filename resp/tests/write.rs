@@ -0,0 +1,52 @@
+//! The [Write] implementations, which differ with and without the `std` feature. Run this with
+//! `--no-default-features` as well.
+
+use bytes::BytesMut;
+use redust_resp::{array, ser::Write, to_bytes, Data};
+
+const EXPECTED: &[u8] = b"*2\r\n+OK\r\n:1\r\n";
+
+fn data() -> Data<'static> {
+	array!(Data::simple_string("OK"), 1)
+}
+
+#[test]
+fn write_vec() {
+	let mut vec = Vec::new();
+	to_bytes(&data(), &mut vec).unwrap();
+	assert_eq!(vec, EXPECTED);
+
+	to_bytes(&Data::Integer(2), &mut vec).unwrap();
+	assert_eq!(&vec[EXPECTED.len()..], b":2\r\n");
+}
+
+#[test]
+fn write_mut_ref() {
+	fn write(mut output: impl Write, value: i64) {
+		output.write_all(b":").unwrap();
+		write!(output, "{}\r\n", value).unwrap();
+	}
+
+	let mut vec = Vec::new();
+	write(&mut vec, 1);
+	write(&mut &mut vec, 2);
+	assert_eq!(vec, b":1\r\n:2\r\n");
+}
+
+#[cfg(not(feature = "std"))]
+#[test]
+fn write_bytes_mut() {
+	let mut bytes = BytesMut::new();
+	to_bytes(&data(), &mut bytes).unwrap();
+	assert_eq!(bytes, EXPECTED);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn write_bytes_mut() {
+	use bytes::BufMut;
+
+	let mut writer = BytesMut::new().writer();
+	to_bytes(&data(), &mut writer).unwrap();
+	assert_eq!(writer.into_inner(), EXPECTED);
+}
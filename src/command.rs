@@ -2,7 +2,7 @@ use async_trait::async_trait;
 use futures::SinkExt;
 use redust_resp::Data;
 
-use crate::{Connection, Result};
+use crate::{Connection, Result, Transport};
 
 /// [Connection](https://redis.io/commands/?group=connection) commands.
 pub mod connection;
@@ -16,14 +16,19 @@ pub trait Command {
 	type Response;
 
 	/// Run the command using the given connection.
-	async fn run(self, connection: &mut Connection) -> Result<Self::Response>;
+	async fn run<T>(self, connection: &mut Connection<T>) -> Result<Self::Response>
+	where
+		T: Transport;
 }
 
 #[async_trait]
 impl Command for Data<'_> {
 	type Response = Data<'static>;
 
-	async fn run(self, connection: &mut Connection) -> Result<Self::Response> {
+	async fn run<T>(self, connection: &mut Connection<T>) -> Result<Self::Response>
+	where
+		T: Transport,
+	{
 		connection.send(self).await?;
 		connection.read_cmd().await
 	}
@@ -3,7 +3,7 @@ use std::fmt::Debug;
 use async_trait::async_trait;
 use tracing::instrument;
 
-use crate::{Connection, Error, Result};
+use crate::{resp::ErrorKind, Connection, Error, Result, Transport};
 
 use super::Command;
 
@@ -25,7 +25,10 @@ where
 	type Response = ();
 
 	#[instrument(level = "debug")]
-	async fn run(self, connection: &mut Connection) -> Result<Self::Response> {
+	async fn run<T>(self, connection: &mut Connection<T>) -> Result<Self::Response>
+	where
+		T: Transport,
+	{
 		let handshake_res = match self.password {
 			Some(ref password) => {
 				connection
@@ -46,7 +49,9 @@ where
 
 		match handshake_res {
 			Ok(_) => Ok(()),
-			Err(Error::Redis(msg)) if msg == "ERR unknown command 'HELLO'" => {
+			Err(Error::Redis(err))
+				if err.kind() == ErrorKind::Err && err.message().starts_with("unknown command") =>
+			{
 				if let Some(password) = self.password {
 					match self.username {
 						Some(username) => {
@@ -1,9 +1,9 @@
 use async_trait::async_trait;
 use futures::{future::ready, TryStreamExt};
-use redust_resp::{from_data, Data};
+use redust_resp::Data;
 use tracing::instrument;
 
-use crate::{model::pubsub::Response, Connection, Result};
+use crate::{model::pubsub::Response, Connection, Error, Result, Transport};
 
 use super::Command;
 
@@ -16,17 +16,21 @@ impl Command for Unsubscribe {
 	type Response = Vec<Data<'static>>;
 
 	#[instrument(ret, level = "debug")]
-	async fn run(self, connection: &mut Connection) -> Result<Self::Response> {
+	async fn run<T>(self, connection: &mut Connection<T>) -> Result<Self::Response>
+	where
+		T: Transport,
+	{
 		connection
 			.pipeline([["unsubscribe"], ["punsubscribe"]])
 			.await?;
 
 		connection
-			.try_take_while(|data| {
-				ready(from_data::<Response>(data.clone()).map(
+			.try_take_while(|frame| {
+				ready(frame.deserialize::<Response>().map_err(Error::from).map(
 					|response| matches!(response, Response::Unsubscribe(sub) if sub.is_in_pubsub_mode()),
 				))
 			})
+			.and_then(|frame| ready(frame.into_data().map_err(Error::from)))
 			.try_collect()
 			.await
 	}
@@ -1,33 +1,70 @@
+#[cfg(unix)]
+use std::path::Path;
 use std::{
 	convert::identity,
 	fmt::Debug,
+	future::Future,
 	io,
 	pin::Pin,
 	sync::Arc,
-	task::{Context, Poll},
+	task::{ready, Context, Poll},
+	time::Duration,
 };
 
-use futures::{Sink, SinkExt, Stream, TryStreamExt};
+use futures::{future::poll_fn, Sink, SinkExt, Stream, TryStreamExt};
 use pin_project_lite::pin_project;
-use redust_resp::{Codec, Data};
+use redust_resp::{CodecOptions, Data, Frame, StreamCodec, Streamed, ToArgs};
+#[cfg(unix)]
+use tokio::net::UnixStream;
 use tokio::{
+	io::{AsyncRead, AsyncWrite},
 	net::{TcpStream, ToSocketAddrs},
 	sync::Mutex,
 };
-use tokio_util::codec::{Decoder, Framed};
+use tokio_util::codec::Framed;
+#[cfg(feature = "futures-io")]
+use tokio_util::compat::{Compat, FuturesAsyncReadCompatExt};
 use tracing::instrument;
 
 use crate::{Error, Result};
 
+mod builder;
+mod bulk;
+mod info;
+mod init;
+mod socket;
+#[cfg(feature = "tls")]
+mod tls;
+
+pub use builder::*;
+pub use bulk::*;
+pub use info::*;
+pub use init::*;
+pub use socket::*;
+#[cfg(feature = "tls")]
+pub use tls::*;
+
+/// A transport which a [`Connection`] can run over, such as a [`TcpStream`].
+///
+/// Transports from runtimes other than Tokio, which implement the [futures](futures::io) IO traits,
+/// can be used with [`Connection::from_futures_io`].
+pub trait Transport: AsyncRead + AsyncWrite + Unpin + Send + Debug {}
+
+impl<T> Transport for T where T: AsyncRead + AsyncWrite + Unpin + Send + Debug {}
+
 pin_project! {
-	/// A TCP connection to a Redis server.
+	/// A connection to a Redis server, over TCP unless another [`Transport`] is used.
 	///
 	/// To enter PubSub mode, send the appropriate subscription command using [`send_cmd()`](Self::send_cmd()) and
 	/// then consume the stream.
-	pub struct Connection {
+	///
+	/// Use a [`ConnectionBuilder`] to set timeouts and socket options.
+	pub struct Connection<T = TcpStream> {
 		#[pin]
-		framed: Framed<TcpStream, Codec>,
+		framed: Framed<T, StreamCodec>,
 		is_dead: bool,
+		timeout: Option<Duration>,
+		server_info: Option<ServerInfo>,
 	}
 }
 
@@ -35,12 +72,107 @@ impl Connection {
 	/// Connect to the Redis server using the provided `addr`.
 	#[instrument(err)]
 	pub async fn new(addr: impl ToSocketAddrs + Debug) -> Result<Self, std::io::Error> {
+		Self::with_options(addr, CodecOptions::default()).await
+	}
+
+	/// Connect to the Redis server using the provided `addr`, limiting responses with `options`.
+	#[instrument(err)]
+	pub async fn with_options(
+		addr: impl ToSocketAddrs + Debug,
+		options: CodecOptions,
+	) -> Result<Self, std::io::Error> {
 		let stream = TcpStream::connect(addr).await?;
-		let framed = Codec.framed(stream);
-		Ok(Self {
-			framed,
-			is_dead: false,
-		})
+		Ok(Self::from_transport(stream, options))
+	}
+}
+
+impl Connection<Socket> {
+	/// Connect to the Redis server at a URL, such as `redis://localhost:6379/0`. See
+	/// [`ConnectionInfo`] for the URLs which are supported.
+	#[instrument(skip(url), err)]
+	pub async fn from_url(url: &str) -> Result<Self> {
+		ConnectionInfo::parse(url)?.connect().await
+	}
+}
+
+#[cfg(unix)]
+impl Connection<UnixStream> {
+	/// Connect to the Redis server listening on the Unix socket at `path`.
+	#[instrument(err)]
+	pub async fn new_unix(path: impl AsRef<Path> + Debug) -> Result<Self, std::io::Error> {
+		Self::unix_with_options(path, CodecOptions::default()).await
+	}
+
+	/// Connect to the Redis server listening on the Unix socket at `path`, limiting responses
+	/// with `options`.
+	#[instrument(err)]
+	pub async fn unix_with_options(
+		path: impl AsRef<Path> + Debug,
+		options: CodecOptions,
+	) -> Result<Self, std::io::Error> {
+		let stream = UnixStream::connect(path).await?;
+		Ok(Self::from_transport(stream, options))
+	}
+}
+
+#[cfg(feature = "tls")]
+impl Connection<TlsStream<TcpStream>> {
+	/// Connect to the Redis server using the provided `addr` over TLS, verifying the server as
+	/// `host`. Only available when the `tls` feature is enabled.
+	#[instrument(err)]
+	pub async fn new_tls(
+		addr: impl ToSocketAddrs + Debug,
+		host: &str,
+		tls: &TlsConnector,
+	) -> Result<Self, std::io::Error> {
+		Self::tls_with_options(addr, host, tls, CodecOptions::default()).await
+	}
+
+	/// Connect to the Redis server using the provided `addr` over TLS, verifying the server as
+	/// `host` and limiting responses with `options`. Only available when the `tls` feature is
+	/// enabled.
+	#[instrument(err)]
+	pub async fn tls_with_options(
+		addr: impl ToSocketAddrs + Debug,
+		host: &str,
+		tls: &TlsConnector,
+		options: CodecOptions,
+	) -> Result<Self, std::io::Error> {
+		let stream = TcpStream::connect(addr).await?;
+		let stream = tls.connect(host, stream).await?;
+		Ok(Self::from_transport(stream, options))
+	}
+}
+
+#[cfg(feature = "futures-io")]
+impl<T> Connection<Compat<T>>
+where
+	T: futures::AsyncRead + futures::AsyncWrite + Unpin + Send + Debug,
+{
+	/// Use a transport which implements the [futures](futures::io) IO traits, such as a socket
+	/// from smol or async-std. Only available when the `futures-io` feature is enabled.
+	pub fn from_futures_io(io: T, options: CodecOptions) -> Self {
+		Self::from_transport(io.compat(), options)
+	}
+}
+
+impl<T> Connection<T>
+where
+	T: Transport,
+{
+	/// Use a connected `transport`, limiting responses with `options`.
+	pub fn from_transport(transport: T, options: CodecOptions) -> Self {
+		ConnectionBuilder::new().options(options).build(transport)
+	}
+
+	/// Get the transport.
+	pub fn get_ref(&self) -> &T {
+		self.framed.get_ref()
+	}
+
+	/// Get the transport, discarding any buffered data.
+	pub fn into_inner(self) -> T {
+		self.framed.into_inner()
 	}
 
 	/// Run a command. Only available when the `command` feature is enabled.
@@ -59,17 +191,16 @@ impl Connection {
 	/// Pipeline commands to Redis. This avoids extra syscalls when sending and receiving commands
 	/// in bulk.
 	#[instrument(ret, err)]
-	pub async fn pipeline<'a, C, I>(
+	pub async fn pipeline<C>(
 		&mut self,
 		cmds: impl IntoIterator<Item = C> + Debug,
 	) -> Result<Vec<Data<'static>>>
 	where
-		C: IntoIterator<Item = &'a I>,
-		I: 'a + AsRef<[u8]> + ?Sized,
+		C: ToArgs,
 	{
 		let mut len = 0;
 		for cmd in cmds {
-			self.feed(Data::from_bytes_iter(cmd)).await?;
+			self.feed(Data::from_args(&cmd)).await?;
 			len += 1;
 		}
 
@@ -89,32 +220,107 @@ impl Connection {
 	}
 
 	/// Send a command to the server, awaiting a single response.
-	#[instrument(level = "debug", ret, err)]
-	pub async fn cmd<'a, C, I>(&mut self, cmd: C) -> Result<Data<'static>>
+	///
+	/// Commands can be any [ToArgs], such as `["GET", "foo"]` or `("SET", "foo", 1)`.
+	#[instrument(level = "debug", skip(cmd), fields(cmd = %Data::from_args(&cmd)), ret(Display), err)]
+	pub async fn cmd<C>(&mut self, cmd: C) -> Result<Data<'static>>
 	where
-		C: IntoIterator<Item = &'a I> + Debug,
-		I: 'a + AsRef<[u8]> + ?Sized,
+		C: ToArgs,
 	{
 		self.send_cmd(cmd).await?;
 		self.read_cmd().await
 	}
 
 	/// Send a command without waiting for a response.
-	#[instrument(level = "trace", ret, err)]
-	pub async fn send_cmd<'a, C, I>(&mut self, cmd: C) -> Result<()>
+	#[instrument(level = "trace", skip(cmd), fields(cmd = %Data::from_args(&cmd)), err)]
+	pub async fn send_cmd<C>(&mut self, cmd: C) -> Result<()>
 	where
-		C: IntoIterator<Item = &'a I> + Debug,
-		I: 'a + AsRef<[u8]> + ?Sized,
+		C: ToArgs,
 	{
-		self.send(Data::from_bytes_iter(cmd)).await
+		self.send(Data::from_args(&cmd)).await
 	}
 
 	/// Read a single command response.
-	#[instrument(level = "trace", ret, err)]
+	#[instrument(level = "trace", ret(Display), err)]
 	pub async fn read_cmd(&mut self) -> Result<Data<'static>> {
-		self.try_next()
-			.await?
-			.ok_or_else(|| Error::Io(io::Error::new(io::ErrorKind::Other, "stream closed")))
+		Ok(self.read_frame().await?.into_data()?)
+	}
+
+	/// Read a single command response as a [`Frame`], without copying it.
+	#[instrument(level = "trace", ret, err)]
+	pub async fn read_frame(&mut self) -> Result<Frame> {
+		let res = with_timeout(self.timeout, async {
+			self.try_next().await?.ok_or_else(closed)
+		})
+		.await;
+		set_status(&mut self.is_dead)(res)
+	}
+
+	/// Send a command to the server, streaming the response if it's a bulk string longer than
+	/// `threshold` bytes.
+	#[instrument(level = "debug", skip(cmd), fields(cmd = %Data::from_args(&cmd)), err)]
+	pub async fn cmd_streamed<C>(
+		&mut self,
+		cmd: C,
+		threshold: usize,
+	) -> Result<StreamedResponse<'_, T>>
+	where
+		C: ToArgs,
+	{
+		self.send_cmd(cmd).await?;
+		self.read_streamed(threshold).await
+	}
+
+	/// Read a single command response, streaming it if it's a bulk string longer than `threshold`
+	/// bytes. Other responses are read as [`Frame`]s.
+	///
+	/// This allows large values to be piped elsewhere, such as to a file, with bounded memory.
+	#[instrument(level = "trace", err)]
+	pub async fn read_streamed(&mut self, threshold: usize) -> Result<StreamedResponse<'_, T>> {
+		let res = with_timeout(self.timeout, async {
+			poll_fn(|cx| self.poll_streamed(cx, Some(threshold)))
+				.await
+				.ok_or_else(closed)?
+		})
+		.await;
+
+		match set_status(&mut self.is_dead)(res)? {
+			Streamed::Frame(frame) => Ok(StreamedResponse::Frame(frame)),
+			Streamed::Bulk { len } => Ok(StreamedResponse::Bulk(BulkReader::new(self, len))),
+			Streamed::Chunk(_) => unreachable!("chunks should follow a bulk header"),
+		}
+	}
+
+	/// Authenticate, select a database and run the other commands in `options`, as connections
+	/// made by a [`ConnectionBuilder`] do. The reply to `HELLO` is kept as the
+	/// [`server_info`](Self::server_info).
+	pub async fn init(&mut self, options: &InitOptions) -> Result<()> {
+		if let Some(info) = options.run(self).await? {
+			self.server_info = Some(info);
+		}
+
+		Ok(())
+	}
+
+	/// The server's reply to `HELLO`, if it was sent when the connection was
+	/// [initialized](Self::init).
+	pub fn server_info(&self) -> Option<&ServerInfo> {
+		self.server_info.as_ref()
+	}
+
+	/// How long to wait for a response to start to arrive, if there is a timeout.
+	pub fn response_timeout(&self) -> Option<Duration> {
+		self.timeout
+	}
+
+	/// Fail with [`Error::Timeout`] if a response doesn't start to arrive within `timeout`, such as
+	/// when the server hangs. The rest of a response streamed with [`read_streamed`](Self::read_streamed)
+	/// isn't timed.
+	///
+	/// A timeout marks the connection as dead, since a late response would be read as the response
+	/// to the next command. Blocking commands should be sent with no timeout or a longer one.
+	pub fn set_response_timeout(&mut self, timeout: Option<Duration>) {
+		self.timeout = timeout;
 	}
 
 	/// Whether this connection has encountered a non-transient error and should be considered dead.
@@ -123,19 +329,66 @@ impl Connection {
 	}
 }
 
-impl Debug for Connection {
+impl<T> Connection<T>
+where
+	T: AsyncRead + Unpin,
+{
+	/// Poll for the next value, skipping the rest of any string which was streamed but not read to
+	/// the end. Bulk strings longer than `threshold` are streamed.
+	fn poll_streamed(
+		&mut self,
+		cx: &mut Context<'_>,
+		threshold: Option<usize>,
+	) -> Poll<Option<Result<Streamed>>> {
+		loop {
+			let skipping = self.framed.codec().is_streaming();
+			self.framed.codec_mut().threshold = threshold;
+
+			let item = ready!(Pin::new(&mut self.framed).poll_next(cx));
+			match item.map(|res| res.and_then(identity)) {
+				Some(Ok(Streamed::Chunk(_))) if skipping => continue,
+				item => return Poll::Ready(item.map(set_status(&mut self.is_dead))),
+			}
+		}
+	}
+}
+
+impl<T> Debug for Connection<T>
+where
+	T: Debug,
+{
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		let stream = self.framed.get_ref();
 		f.debug_struct("Connection")
-			.field("peer_addr", &stream.peer_addr())
-			.field("local_addr", &stream.local_addr())
+			.field("transport", self.framed.get_ref())
 			.field("is_dead", &self.is_dead)
+			.field("timeout", &self.timeout)
 			.finish_non_exhaustive()
 	}
 }
 
-fn set_status<T>(status: &mut bool) -> impl FnOnce(Result<T>) -> Result<T> + '_ {
+fn closed() -> Error {
+	Error::Io(io::Error::other("stream closed"))
+}
+
+/// Await `fut`, failing with [`Error::Timeout`] if there is a `timeout` and it elapses first.
+async fn with_timeout<T>(
+	timeout: Option<Duration>,
+	fut: impl Future<Output = Result<T>>,
+) -> Result<T> {
+	match timeout {
+		Some(timeout) => tokio::time::timeout(timeout, fut)
+			.await
+			.unwrap_or(Err(Error::Timeout(timeout))),
+		None => fut.await,
+	}
+}
+
+fn set_status<T, E>(status: &mut bool) -> impl FnOnce(Result<T, E>) -> Result<T> + '_
+where
+	E: Into<Error>,
+{
 	|r| {
+		let r = r.map_err(Into::into);
 		if let Err(ref e) = r {
 			*status = !e.is_transient();
 		}
@@ -144,25 +397,33 @@ fn set_status<T>(status: &mut bool) -> impl FnOnce(Result<T>) -> Result<T> + '_
 	}
 }
 
-impl Stream for Connection {
-	type Item = Result<Data<'static>>;
+impl<T> Stream for Connection<T>
+where
+	T: AsyncRead + Unpin,
+{
+	type Item = Result<Frame>;
 
 	fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-		let proj = self.project();
-
-		proj.framed.poll_next(cx).map(|res| {
-			res.map(|item| item.and_then(identity))
-				.map(set_status(proj.is_dead))
+		self.get_mut().poll_streamed(cx, None).map(|item| {
+			item.map(|res| {
+				res.map(|streamed| match streamed {
+					Streamed::Frame(frame) => frame,
+					_ => unreachable!("values are only streamed with a threshold"),
+				})
+			})
 		})
 	}
 }
 
-impl Sink<Data<'_>> for Connection {
+impl<T> Sink<Data<'_>> for Connection<T>
+where
+	T: AsyncWrite,
+{
 	type Error = Error;
 
 	fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
 		let proj = self.project();
-		proj.framed.poll_ready(cx).map(set_status(proj.is_dead))
+		Sink::<Data<'_>>::poll_ready(proj.framed, cx).map(set_status(proj.is_dead))
 	}
 
 	fn start_send(self: Pin<&mut Self>, item: Data<'_>) -> Result<(), Self::Error> {
@@ -173,14 +434,100 @@ impl Sink<Data<'_>> for Connection {
 
 	fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
 		let proj = self.project();
-		proj.framed.poll_flush(cx).map(set_status(proj.is_dead))
+		Sink::<Data<'_>>::poll_flush(proj.framed, cx).map(set_status(proj.is_dead))
 	}
 
 	fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
 		let proj = self.project();
-		proj.framed.poll_close(cx).map(set_status(proj.is_dead))
+		Sink::<Data<'_>>::poll_close(proj.framed, cx).map(set_status(proj.is_dead))
 	}
 }
 
 /// A [`Connection`] that can be shared across threads.
 pub type SharedConnection = Arc<Mutex<Connection>>;
+
+#[cfg(test)]
+mod test {
+	use redust_resp::{CodecOptions, Data};
+	use tokio::io::{duplex, AsyncReadExt, AsyncWriteExt, DuplexStream};
+
+	use super::{Connection, StreamedResponse, Transport};
+
+	async fn ping<T: Transport>(conn: &mut Connection<T>, server: &mut DuplexStream) {
+		server.write_all(b"+PONG\r\n").await.unwrap();
+		let res = conn.cmd(["PING"]).await.unwrap();
+		assert_eq!(res, Data::simple_string("PONG"));
+
+		let mut cmd = [0; 14];
+		server.read_exact(&mut cmd).await.unwrap();
+		assert_eq!(&cmd, b"*1\r\n$4\r\nPING\r\n");
+	}
+
+	#[tokio::test]
+	async fn test_transport() {
+		let (client, mut server) = duplex(64);
+		let mut conn = Connection::from_transport(client, CodecOptions::default());
+		ping(&mut conn, &mut server).await;
+	}
+
+	#[cfg(feature = "futures-io")]
+	#[tokio::test]
+	async fn test_futures_io() {
+		use tokio_util::compat::TokioAsyncReadCompatExt;
+
+		let (client, mut server) = duplex(64);
+		let mut conn = Connection::from_futures_io(client.compat(), CodecOptions::default());
+		ping(&mut conn, &mut server).await;
+	}
+
+	#[tokio::test]
+	async fn test_read_streamed() {
+		let (client, mut server) = duplex(64);
+		let mut conn = Connection::from_transport(client, CodecOptions::default());
+		server
+			.write_all(b"$10\r\n0123456789\r\n$10\r\n0123456789\r\n+OK\r\n:1\r\n")
+			.await
+			.unwrap();
+
+		let StreamedResponse::Bulk(mut reader) = conn.read_streamed(4).await.unwrap() else {
+			panic!("expected a streamed bulk string");
+		};
+		assert_eq!(reader.len(), 10);
+
+		let mut buf = Vec::new();
+		reader.read_to_end(&mut buf).await.unwrap();
+		assert_eq!(buf, b"0123456789");
+
+		// the rest of a string which isn't read to the end is skipped
+		let StreamedResponse::Bulk(mut reader) = conn.read_streamed(4).await.unwrap() else {
+			panic!("expected a streamed bulk string");
+		};
+		reader.read_exact(&mut [0; 2]).await.unwrap();
+
+		assert_eq!(conn.read_cmd().await.unwrap(), Data::simple_string("OK"));
+		assert!(matches!(
+			conn.read_streamed(4).await.unwrap(),
+			StreamedResponse::Frame(frame) if frame == Data::Integer(1)
+		));
+	}
+
+	#[cfg(unix)]
+	#[tokio::test]
+	async fn test_unix() {
+		use tokio::net::UnixListener;
+
+		let path = std::env::temp_dir().join(format!("redust-{}.sock", std::process::id()));
+		let _ = std::fs::remove_file(&path);
+		let listener = UnixListener::bind(&path).unwrap();
+
+		let (conn, server) = tokio::join!(Connection::new_unix(&path), listener.accept());
+		let (mut conn, (mut server, _)) = (conn.unwrap(), server.unwrap());
+		std::fs::remove_file(&path).unwrap();
+
+		server.write_all(b"+PONG\r\n").await.unwrap();
+		assert_eq!(
+			conn.cmd(["PING"]).await.unwrap(),
+			Data::simple_string("PONG")
+		);
+	}
+}
@@ -0,0 +1,307 @@
+#[cfg(unix)]
+use std::path::Path;
+use std::{fmt::Debug, future::Future, io, time::Duration};
+
+use redust_resp::{Codec, CodecOptions, StreamCodec};
+use socket2::{SockRef, TcpKeepalive};
+#[cfg(unix)]
+use tokio::net::UnixStream;
+use tokio::net::{TcpStream, ToSocketAddrs};
+use tokio_util::codec::Framed;
+use tracing::instrument;
+
+use crate::{Error, Result};
+
+use super::{
+	with_timeout, Connection, ConnectionAddr, ConnectionInfo, InitOptions, Socket, Transport,
+};
+#[cfg(feature = "tls")]
+use super::{TlsConnector, TlsOptions};
+
+/// Builds [`Connection`]s with timeouts and socket options.
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use redust::ConnectionBuilder;
+/// # use redust::Error;
+///
+/// # tokio_test::block_on(async {
+/// let mut conn = ConnectionBuilder::new()
+///     .connect_timeout(Duration::from_secs(5))
+///     .response_timeout(Duration::from_secs(1))
+///     .nodelay(true)
+///     .connect("localhost:6379")
+///     .await?;
+/// conn.cmd(["PING"]).await?;
+/// # Ok::<_, Error>(())
+/// # });
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionBuilder {
+	options: CodecOptions,
+	connect_timeout: Option<Duration>,
+	response_timeout: Option<Duration>,
+	nodelay: bool,
+	keepalive: Option<Duration>,
+	read_capacity: Option<usize>,
+	write_capacity: Option<usize>,
+	init: InitOptions,
+	/// The host to verify the server as and the connector to start TLS with.
+	#[cfg(feature = "tls")]
+	tls: Option<(String, TlsConnector)>,
+}
+
+impl ConnectionBuilder {
+	/// Make a builder with the default options and no timeouts.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Limit responses with `options`.
+	pub fn options(mut self, options: CodecOptions) -> Self {
+		self.options = options;
+		self
+	}
+
+	/// Fail with [`Error::Timeout`](crate::Error::Timeout) if connecting, including any TLS
+	/// handshake, takes longer than `timeout`.
+	pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+		self.connect_timeout = Some(timeout);
+		self
+	}
+
+	/// Fail with [`Error::Timeout`](crate::Error::Timeout) if a response doesn't start to arrive
+	/// within `timeout`. See [`Connection::set_response_timeout`].
+	pub fn response_timeout(mut self, timeout: Duration) -> Self {
+		self.response_timeout = Some(timeout);
+		self
+	}
+
+	/// Set `TCP_NODELAY` on TCP sockets, so that commands are sent without delay.
+	pub fn nodelay(mut self, nodelay: bool) -> Self {
+		self.nodelay = nodelay;
+		self
+	}
+
+	/// Enable TCP keepalive, sending probes once the socket has been idle for `time`.
+	pub fn keepalive(mut self, time: Duration) -> Self {
+		self.keepalive = Some(time);
+		self
+	}
+
+	/// The initial capacity of the buffer which responses are read into.
+	pub fn read_buffer_capacity(mut self, capacity: usize) -> Self {
+		self.read_capacity = Some(capacity);
+		self
+	}
+
+	/// The capacity of the buffer which commands are written into. Buffered commands are flushed
+	/// once it fills, even when [feeding](futures::SinkExt::feed) them.
+	pub fn write_buffer_capacity(mut self, capacity: usize) -> Self {
+		self.write_capacity = Some(capacity);
+		self
+	}
+
+	/// Run the commands in `init`, such as authentication, on each new connection.
+	pub fn init(mut self, init: InitOptions) -> Self {
+		self.init = init;
+		self
+	}
+
+	/// Connect over TLS with `tls`, verifying the server as `host`. Only available when the `tls`
+	/// feature is enabled.
+	#[cfg(feature = "tls")]
+	pub fn tls(mut self, host: impl Into<String>, tls: TlsConnector) -> Self {
+		self.tls = Some((host.into(), tls));
+		self
+	}
+
+	/// Connect to the Redis server using the provided `addr`.
+	#[instrument(err)]
+	pub async fn connect(&self, addr: impl ToSocketAddrs + Debug) -> Result<Connection<Socket>> {
+		let socket = self
+			.timed_connect(async {
+				let stream = self.connect_tcp(addr).await?;
+
+				#[cfg(feature = "tls")]
+				if let Some((host, tls)) = &self.tls {
+					return Ok(Socket::from(tls.connect(host, stream).await?));
+				}
+
+				Ok(Socket::Tcp(stream))
+			})
+			.await?;
+
+		self.initialized(socket, &self.init).await
+	}
+
+	/// Connect to the Redis server described by `info`. The [`init`](ConnectionInfo::init)
+	/// commands from `info` are run instead of the builder's.
+	///
+	/// TLS connections use the connector given to [`tls`](Self::tls) if there is one, with the
+	/// host from `info`. In that case, the connector decides which certificates are accepted and
+	/// [`insecure`](ConnectionAddr::Tls::insecure) is ignored. Otherwise, a connector is built for
+	/// each connection.
+	#[instrument(err)]
+	pub async fn connect_info(&self, info: &ConnectionInfo) -> Result<Connection<Socket>> {
+		let socket = match &info.addr {
+			ConnectionAddr::Tcp { host, port } => Socket::Tcp(
+				self.timed_connect(self.connect_tcp((host.as_str(), *port)))
+					.await?,
+			),
+			#[cfg(feature = "tls")]
+			ConnectionAddr::Tls {
+				host,
+				port,
+				insecure,
+			} => {
+				let tls = self.tls_connector(*insecure)?;
+				self.timed_connect(async {
+					let stream = self.connect_tcp((host.as_str(), *port)).await?;
+					Ok(Socket::from(tls.connect(host, stream).await?))
+				})
+				.await?
+			}
+			#[cfg(not(feature = "tls"))]
+			ConnectionAddr::Tls { .. } => {
+				return Err(Error::Message(
+					"TLS connections require the tls feature".into(),
+				))
+			}
+			#[cfg(unix)]
+			ConnectionAddr::Unix(path) => Socket::Unix(self.timed_connect(UnixStream::connect(path)).await?),
+			#[cfg(not(unix))]
+			ConnectionAddr::Unix(_) => {
+				return Err(Error::Message(
+					"Unix sockets are only supported on Unix platforms".into(),
+				))
+			}
+		};
+
+		self.initialized(socket, &info.init).await
+	}
+
+	/// Connect to the Redis server listening on the Unix socket at `path`. Only available on Unix
+	/// platforms.
+	#[cfg(unix)]
+	#[instrument(err)]
+	pub async fn connect_unix(&self, path: impl AsRef<Path> + Debug) -> Result<Connection<Socket>> {
+		let stream = self.timed_connect(UnixStream::connect(path)).await?;
+		self.initialized(Socket::Unix(stream), &self.init).await
+	}
+
+	/// The connector given to [`tls`](Self::tls), or a new one which accepts any certificate if
+	/// `insecure`.
+	#[cfg(feature = "tls")]
+	pub(crate) fn tls_connector(&self, insecure: bool) -> Result<TlsConnector> {
+		match &self.tls {
+			Some((_, tls)) => Ok(tls.clone()),
+			None => TlsOptions {
+				insecure,
+				..Default::default()
+			}
+			.connector()
+			.map_err(|err| Error::Message(err.to_string().into())),
+		}
+	}
+
+	/// Use a connected `transport`. Socket options aren't applied and the init commands aren't
+	/// run, but can be with [`Connection::init`].
+	pub fn build<T>(&self, transport: T) -> Connection<T>
+	where
+		T: Transport,
+	{
+		let codec = StreamCodec::new(Codec::new(self.options), None);
+		let mut framed = match self.read_capacity {
+			Some(capacity) => Framed::with_capacity(transport, codec, capacity),
+			None => Framed::new(transport, codec),
+		};
+
+		if let Some(capacity) = self.write_capacity {
+			framed.write_buffer_mut().reserve(capacity);
+			framed.set_backpressure_boundary(capacity);
+		}
+
+		Connection {
+			framed,
+			is_dead: false,
+			timeout: self.response_timeout,
+			server_info: None,
+		}
+	}
+
+	/// Make a connection over `socket` and run the commands in `init` on it.
+	async fn initialized(&self, socket: Socket, init: &InitOptions) -> Result<Connection<Socket>> {
+		let mut conn = self.build(socket);
+		conn.init(init).await?;
+		Ok(conn)
+	}
+
+	/// Connect a TCP socket to `addr` and set its options.
+	async fn connect_tcp(&self, addr: impl ToSocketAddrs) -> io::Result<TcpStream> {
+		let stream = TcpStream::connect(addr).await?;
+		stream.set_nodelay(self.nodelay)?;
+		if let Some(time) = self.keepalive {
+			SockRef::from(&stream).set_tcp_keepalive(&TcpKeepalive::new().with_time(time))?;
+		}
+
+		Ok(stream)
+	}
+
+	async fn timed_connect<T>(&self, fut: impl Future<Output = io::Result<T>>) -> Result<T> {
+		with_timeout(self.connect_timeout, async { Ok(fut.await?) }).await
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::time::Duration;
+
+	use redust_resp::Data;
+	use tokio::{
+		io::{duplex, AsyncWriteExt},
+		net::TcpListener,
+	};
+
+	use crate::Error;
+
+	use super::ConnectionBuilder;
+
+	#[tokio::test]
+	async fn test_response_timeout() {
+		let (client, mut server) = duplex(64);
+		let mut conn = ConnectionBuilder::new()
+			.response_timeout(Duration::from_millis(10))
+			.build(client);
+
+		let err = conn.cmd(["PING"]).await.unwrap_err();
+		assert!(matches!(err, Error::Timeout(duration) if duration == Duration::from_millis(10)));
+		assert!(conn.is_dead());
+
+		// the late response is read next, which is why the connection is dead
+		server.write_all(b"+PONG\r\n").await.unwrap();
+		conn.set_response_timeout(None);
+		assert_eq!(conn.read_cmd().await.unwrap(), Data::simple_string("PONG"));
+	}
+
+	#[tokio::test]
+	async fn test_connect() {
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let addr = listener.local_addr().unwrap();
+
+		let builder = ConnectionBuilder::new()
+			.connect_timeout(Duration::from_secs(5))
+			.nodelay(true)
+			.keepalive(Duration::from_secs(60))
+			.read_buffer_capacity(16)
+			.write_buffer_capacity(16);
+		let (conn, server) = tokio::join!(builder.connect(addr), listener.accept());
+		let (mut conn, (mut server, _)) = (conn.unwrap(), server.unwrap());
+
+		server.write_all(b"+PONG\r\n").await.unwrap();
+		assert_eq!(
+			conn.cmd(["PING"]).await.unwrap(),
+			Data::simple_string("PONG")
+		);
+	}
+}
@@ -0,0 +1,128 @@
+use std::{
+	convert::identity,
+	io,
+	pin::Pin,
+	task::{ready, Context, Poll},
+};
+
+use bytes::Bytes;
+use futures::Stream;
+use redust_resp::{Frame, Streamed};
+use tokio::{
+	io::{AsyncRead, ReadBuf},
+	net::TcpStream,
+};
+
+use crate::{Error, Result};
+
+use super::{set_status, Connection, Transport};
+
+/// A response read by [`Connection::read_streamed`].
+#[derive(Debug)]
+pub enum StreamedResponse<'c, T = TcpStream> {
+	/// A response which was read in full.
+	Frame(Frame),
+	/// A bulk string which is longer than the threshold, read as it arrives.
+	Bulk(BulkReader<'c, T>),
+}
+
+/// A bulk string which is read from a [`Connection`] as it arrives, instead of being buffered.
+///
+/// The string can be read as a [`Stream`] of chunks or with [`AsyncRead`], such as with
+/// `tokio::io::copy`. If the reader is dropped before the end of the string, the rest of it is
+/// skipped when the connection is next read.
+#[derive(Debug)]
+pub struct BulkReader<'c, T = TcpStream> {
+	conn: &'c mut Connection<T>,
+	len: usize,
+	/// The unread part of the last chunk.
+	chunk: Bytes,
+	done: bool,
+}
+
+impl<'c, T> BulkReader<'c, T>
+where
+	T: Transport,
+{
+	pub(super) fn new(conn: &'c mut Connection<T>, len: usize) -> Self {
+		Self {
+			conn,
+			len,
+			chunk: Bytes::new(),
+			done: false,
+		}
+	}
+
+	/// The total length of the string.
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	/// Whether the string is empty.
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	fn poll_chunk(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<Bytes>>> {
+		if !self.chunk.is_empty() {
+			return Poll::Ready(Some(Ok(std::mem::take(&mut self.chunk))));
+		}
+
+		if self.done {
+			return Poll::Ready(None);
+		}
+
+		let item = ready!(Pin::new(&mut self.conn.framed).poll_next(cx));
+		let res = match item.map(|res| res.and_then(identity)) {
+			Some(Ok(Streamed::Chunk(chunk))) if chunk.is_empty() => {
+				self.done = true;
+				return Poll::Ready(None);
+			}
+			Some(Ok(Streamed::Chunk(chunk))) => Ok(chunk),
+			Some(Ok(_)) => Err(Error::Message("expected a chunk of a bulk string".into())),
+			Some(Err(err)) => Err(err.into()),
+			None => Err(Error::Io(io::Error::new(
+				io::ErrorKind::UnexpectedEof,
+				"stream closed in a bulk string",
+			))),
+		};
+
+		Poll::Ready(Some(set_status(&mut self.conn.is_dead)(res)))
+	}
+}
+
+impl<T> Stream for BulkReader<'_, T>
+where
+	T: Transport,
+{
+	type Item = Result<Bytes>;
+
+	fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		self.get_mut().poll_chunk(cx)
+	}
+}
+
+impl<T> AsyncRead for BulkReader<'_, T>
+where
+	T: Transport,
+{
+	fn poll_read(
+		self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+		buf: &mut ReadBuf<'_>,
+	) -> Poll<io::Result<()>> {
+		let this = self.get_mut();
+		if this.chunk.is_empty() {
+			match ready!(this.poll_chunk(cx)) {
+				Some(Ok(chunk)) => this.chunk = chunk,
+				Some(Err(Error::Io(err))) => return Poll::Ready(Err(err)),
+				Some(Err(err)) => return Poll::Ready(Err(io::Error::other(err))),
+				None => return Poll::Ready(Ok(())),
+			}
+		}
+
+		let len = this.chunk.len().min(buf.remaining());
+		buf.put_slice(&this.chunk.split_to(len));
+		Poll::Ready(Ok(()))
+	}
+}
@@ -0,0 +1,411 @@
+use std::fmt::{self, Debug, Formatter};
+
+use futures::SinkExt;
+use redust_resp::{Data, ErrorKind, ToArgs};
+use tracing::instrument;
+
+use crate::{Error, Result};
+
+use super::{Connection, Transport};
+
+/// A version of RESP to switch to with `HELLO`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+	Resp2,
+	Resp3,
+}
+
+impl Protocol {
+	fn version(self) -> &'static str {
+		match self {
+			Self::Resp2 => "2",
+			Self::Resp3 => "3",
+		}
+	}
+}
+
+/// Commands to run on every new connection, such as each connection made by a
+/// [pool](crate::pool) to replace a dead one.
+///
+/// They run in order: `HELLO` if there's a protocol (otherwise `AUTH` and `CLIENT SETNAME`),
+/// `CLIENT SETINFO`, `SELECT`, then the custom [`commands`](Self::commands).
+///
+/// Servers older than Redis 6 don't have `HELLO`, so if it's an unknown command, `AUTH` and
+/// `CLIENT SETNAME` are sent instead. The connection then stays on RESP2 and there's no
+/// [`ServerInfo`].
+#[derive(Clone, Default, PartialEq)]
+pub struct InitOptions {
+	/// The user to authenticate as, or the default user if there's only a password.
+	pub username: Option<String>,
+	pub password: Option<String>,
+	/// The database to `SELECT`.
+	pub db: u32,
+	/// The protocol to switch to with `HELLO`. If [`None`], the server's default is kept and
+	/// there's no [`ServerInfo`].
+	pub protocol: Option<Protocol>,
+	/// The name to set with `CLIENT SETNAME`.
+	pub client_name: Option<String>,
+	/// Send this library's name and version with `CLIENT SETINFO`. Errors from servers which
+	/// don't support it are ignored.
+	pub lib_info: bool,
+	/// Other commands to run.
+	pub commands: Vec<Data<'static>>,
+}
+
+impl InitOptions {
+	/// Add a command to run.
+	pub fn command<C>(mut self, cmd: C) -> Self
+	where
+		C: ToArgs,
+	{
+		self.commands.push(Data::from_args(&cmd).into_owned());
+		self
+	}
+
+	/// Run these commands on `conn`, returning the reply to `HELLO` if it was sent.
+	#[instrument(level = "debug", skip(conn), err)]
+	pub(crate) async fn run<T>(&self, conn: &mut Connection<T>) -> Result<Option<ServerInfo>>
+	where
+		T: Transport,
+	{
+		let mut server_info = None;
+
+		if let Some(protocol) = self.protocol {
+			let username = self.username.as_deref().unwrap_or("default");
+			let mut cmd = vec!["HELLO", protocol.version()];
+			if let Some(password) = &self.password {
+				cmd.extend(["AUTH", username, password]);
+			}
+			if let Some(name) = &self.client_name {
+				cmd.extend(["SETNAME", name]);
+			}
+
+			match secret_cmd(conn, cmd).await {
+				Ok(reply) => server_info = Some(ServerInfo::from_data(reply)?),
+				Err(Error::Redis(err))
+					if err.kind() == ErrorKind::Err
+						&& err.message().starts_with("unknown command") =>
+				{
+					self.auth(conn).await?
+				}
+				Err(err) => return Err(err),
+			}
+		} else {
+			self.auth(conn).await?;
+		}
+
+		if self.lib_info {
+			for (attr, value) in [
+				("LIB-NAME", env!("CARGO_PKG_NAME")),
+				("LIB-VER", env!("CARGO_PKG_VERSION")),
+			] {
+				match conn.cmd(["CLIENT", "SETINFO", attr, value]).await {
+					Ok(_) | Err(Error::Redis(_)) => {}
+					Err(err) => return Err(err),
+				}
+			}
+		}
+
+		if self.db != 0 {
+			conn.cmd(["SELECT", &self.db.to_string()]).await?;
+		}
+
+		for cmd in &self.commands {
+			conn.send(cmd.clone()).await?;
+			conn.read_cmd().await?;
+		}
+
+		Ok(server_info)
+	}
+
+	/// Authenticate and set the client name without `HELLO`.
+	async fn auth<T>(&self, conn: &mut Connection<T>) -> Result<()>
+	where
+		T: Transport,
+	{
+		if let Some(password) = &self.password {
+			match &self.username {
+				Some(username) => secret_cmd(conn, ["AUTH", username, password]).await?,
+				// servers before Redis 6 only accept a password
+				None => secret_cmd(conn, ["AUTH", password]).await?,
+			};
+		}
+
+		if let Some(name) = &self.client_name {
+			conn.cmd(["CLIENT", "SETNAME", name]).await?;
+		}
+
+		Ok(())
+	}
+}
+
+impl Debug for InitOptions {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.debug_struct("InitOptions")
+			.field("username", &self.username)
+			.field("password", &self.password.as_ref().map(|_| "<redacted>"))
+			.field("db", &self.db)
+			.field("protocol", &self.protocol)
+			.field("client_name", &self.client_name)
+			.field("lib_info", &self.lib_info)
+			.field("commands", &self.commands)
+			.finish()
+	}
+}
+
+/// The server's reply to `HELLO`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ServerInfo {
+	/// The name of the server, such as `redis`.
+	pub server: String,
+	pub version: String,
+	/// The RESP version of the connection.
+	pub protocol: i64,
+	/// The ID of the connection, as used by `CLIENT` commands.
+	pub id: i64,
+	/// `standalone`, `sentinel` or `cluster`.
+	pub mode: String,
+	/// `master` or `replica`.
+	pub role: String,
+	pub modules: Vec<Data<'static>>,
+}
+
+impl ServerInfo {
+	/// Read a `HELLO` reply, which is a map in RESP3 and an array of keys and values in RESP2.
+	fn from_data(data: Data<'static>) -> Result<Self> {
+		let pairs = match data {
+			Data::Map(pairs) => pairs,
+			Data::Array(items) => {
+				let mut items = items.into_iter();
+				let mut pairs = Vec::with_capacity(items.len() / 2);
+				while let (Some(key), Some(value)) = (items.next(), items.next()) {
+					pairs.push((key, value));
+				}
+				pairs
+			}
+			_ => return Err(unexpected()),
+		};
+
+		let mut info = Self::default();
+		for (key, value) in pairs {
+			match &*text(key)? {
+				"server" => info.server = text(value)?,
+				"version" => info.version = text(value)?,
+				"proto" => info.protocol = integer(value)?,
+				"id" => info.id = integer(value)?,
+				"mode" => info.mode = text(value)?,
+				"role" => info.role = text(value)?,
+				"modules" => match value {
+					Data::Array(modules) => info.modules = modules,
+					_ => return Err(unexpected()),
+				},
+				_ => {}
+			}
+		}
+
+		Ok(info)
+	}
+}
+
+/// Send a command containing a password. Unlike [`Connection::cmd`], the command isn't recorded
+/// in a span.
+async fn secret_cmd<T, C>(conn: &mut Connection<T>, cmd: C) -> Result<Data<'static>>
+where
+	T: Transport,
+	C: ToArgs,
+{
+	conn.send(Data::from_args(&cmd)).await?;
+	conn.read_cmd().await
+}
+
+fn text(data: Data<'static>) -> Result<String> {
+	match data {
+		Data::SimpleString(str) => Ok(str.into_owned()),
+		Data::BulkString(bytes) => String::from_utf8(bytes.into_owned()).map_err(|_| unexpected()),
+		_ => Err(unexpected()),
+	}
+}
+
+fn integer(data: Data<'static>) -> Result<i64> {
+	match data {
+		Data::Integer(int) => Ok(int),
+		_ => Err(unexpected()),
+	}
+}
+
+fn unexpected() -> Error {
+	Error::Message("unexpected HELLO reply".into())
+}
+
+#[cfg(test)]
+mod test {
+	use std::{
+		io,
+		sync::{Arc, Mutex},
+	};
+
+	use redust_resp::{CodecOptions, Data};
+	use tokio::{
+		io::{duplex, AsyncReadExt, AsyncWriteExt},
+		net::{TcpListener, TcpStream},
+	};
+	use tracing::Level;
+	use tracing_subscriber::fmt::format::FmtSpan;
+
+	use crate::Connection;
+
+	use super::{InitOptions, Protocol, ServerInfo};
+
+	#[tokio::test]
+	async fn test_init() {
+		let (client, mut server) = duplex(1024);
+		let mut conn = Connection::from_transport(client, CodecOptions::default());
+		server
+			.write_all(
+				b"%7\r\n$6\r\nserver\r\n$5\r\nredis\r\n$7\r\nversion\r\n$5\r\n7.2.4\r\n\
+				$5\r\nproto\r\n:3\r\n$2\r\nid\r\n:42\r\n$4\r\nmode\r\n$10\r\nstandalone\r\n\
+				$4\r\nrole\r\n$6\r\nmaster\r\n$7\r\nmodules\r\n*0\r\n\
+				-ERR unknown subcommand 'SETINFO'\r\n+OK\r\n+OK\r\n+OK\r\n",
+			)
+			.await
+			.unwrap();
+
+		let options = InitOptions {
+			password: Some("pw".to_owned()),
+			db: 3,
+			protocol: Some(Protocol::Resp3),
+			client_name: Some("api".to_owned()),
+			lib_info: true,
+			..Default::default()
+		}
+		.command(["CLIENT", "NO-EVICT", "on"]);
+		conn.init(&options).await.unwrap();
+
+		let info = conn.server_info().unwrap();
+		assert_eq!(info.version, "7.2.4");
+		assert_eq!(info.protocol, 3);
+		assert_eq!(info.id, 42);
+		assert_eq!(info.mode, "standalone");
+		assert_eq!(info.role, "master");
+
+		let expected = format!(
+			"*7\r\n$5\r\nHELLO\r\n$1\r\n3\r\n$4\r\nAUTH\r\n$7\r\ndefault\r\n$2\r\npw\r\n\
+			$7\r\nSETNAME\r\n$3\r\napi\r\n\
+			*4\r\n$6\r\nCLIENT\r\n$7\r\nSETINFO\r\n$8\r\nLIB-NAME\r\n$6\r\nredust\r\n\
+			*4\r\n$6\r\nCLIENT\r\n$7\r\nSETINFO\r\n$7\r\nLIB-VER\r\n${}\r\n{}\r\n\
+			*2\r\n$6\r\nSELECT\r\n$1\r\n3\r\n\
+			*3\r\n$6\r\nCLIENT\r\n$8\r\nNO-EVICT\r\n$2\r\non\r\n",
+			env!("CARGO_PKG_VERSION").len(),
+			env!("CARGO_PKG_VERSION")
+		);
+		let mut sent = vec![0; expected.len()];
+		server.read_exact(&mut sent).await.unwrap();
+		assert_eq!(sent, expected.as_bytes());
+	}
+
+	#[tokio::test]
+	async fn test_init_without_hello() {
+		let (client, mut server) = duplex(1024);
+		let mut conn = Connection::from_transport(client, CodecOptions::default());
+		server
+			.write_all(
+				b"-ERR unknown command `HELLO`, with args beginning with: `3`, `AUTH`, \r\n\
+				+OK\r\n+OK\r\n+OK\r\n",
+			)
+			.await
+			.unwrap();
+
+		let options = InitOptions {
+			password: Some("pw".to_owned()),
+			db: 3,
+			protocol: Some(Protocol::Resp3),
+			client_name: Some("api".to_owned()),
+			..Default::default()
+		};
+		conn.init(&options).await.unwrap();
+		assert!(conn.server_info().is_none());
+
+		let expected =
+			b"*7\r\n$5\r\nHELLO\r\n$1\r\n3\r\n$4\r\nAUTH\r\n$7\r\ndefault\r\n$2\r\npw\r\n\
+			$7\r\nSETNAME\r\n$3\r\napi\r\n\
+			*2\r\n$4\r\nAUTH\r\n$2\r\npw\r\n\
+			*3\r\n$6\r\nCLIENT\r\n$7\r\nSETNAME\r\n$3\r\napi\r\n\
+			*2\r\n$6\r\nSELECT\r\n$1\r\n3\r\n";
+		let mut sent = vec![0; expected.len()];
+		server.read_exact(&mut sent).await.unwrap();
+		assert_eq!(sent, expected);
+	}
+
+	#[derive(Clone, Default)]
+	struct Logs(Arc<Mutex<Vec<u8>>>);
+
+	impl io::Write for Logs {
+		fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+			self.0.lock().unwrap().extend_from_slice(buf);
+			Ok(buf.len())
+		}
+
+		fn flush(&mut self) -> io::Result<()> {
+			Ok(())
+		}
+	}
+
+	#[tokio::test]
+	async fn test_init_hides_password() {
+		let logs = Logs::default();
+		let subscriber = tracing_subscriber::fmt()
+			.with_max_level(Level::TRACE)
+			.with_span_events(FmtSpan::NEW)
+			.with_writer({
+				let logs = logs.clone();
+				move || logs.clone()
+			})
+			.finish();
+		let _guard = tracing::subscriber::set_default(subscriber);
+
+		// a TCP socket, since the Debug output of a duplex stream includes its buffer
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let (client, server) = tokio::join!(
+			TcpStream::connect(listener.local_addr().unwrap()),
+			listener.accept()
+		);
+		let (client, (mut server, _)) = (client.unwrap(), server.unwrap());
+		let mut conn = Connection::from_transport(client, CodecOptions::default());
+		server
+			.write_all(b"-ERR unknown command `HELLO`\r\n+OK\r\n+OK\r\n")
+			.await
+			.unwrap();
+
+		let options = InitOptions {
+			username: Some("user".to_owned()),
+			password: Some("hunter2".to_owned()),
+			protocol: Some(Protocol::Resp3),
+			client_name: Some("api".to_owned()),
+			..Default::default()
+		};
+		conn.init(&options).await.unwrap();
+
+		let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+		assert!(logs.contains("SETNAME"), "{logs}");
+		assert!(!logs.contains("hunter2"), "{logs}");
+	}
+
+	#[test]
+	fn test_server_info() {
+		let reply = Data::Array(vec![
+			Data::bulk_string("server"),
+			Data::bulk_string("redis"),
+			Data::bulk_string("proto"),
+			Data::Integer(2),
+			Data::bulk_string("role"),
+			Data::bulk_string("replica"),
+		]);
+
+		let info = ServerInfo::from_data(reply).unwrap();
+		assert_eq!(info.server, "redis");
+		assert_eq!(info.protocol, 2);
+		assert_eq!(info.role, "replica");
+
+		assert!(ServerInfo::from_data(Data::simple_string("OK")).is_err());
+	}
+}
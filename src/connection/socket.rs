@@ -0,0 +1,107 @@
+use std::{
+	io::{self, IoSlice},
+	pin::Pin,
+	task::{Context, Poll},
+};
+
+#[cfg(unix)]
+use tokio::net::UnixStream;
+use tokio::{
+	io::{AsyncRead, AsyncWrite, ReadBuf},
+	net::TcpStream,
+};
+
+#[cfg(feature = "tls")]
+use super::TlsStream;
+
+/// A socket to a Redis server, so that [`Connection`](super::Connection)s over different kinds of
+/// socket have the same type.
+#[derive(Debug)]
+pub enum Socket {
+	Tcp(TcpStream),
+	/// A TCP socket with TLS. Only available when the `tls` feature is enabled.
+	#[cfg(feature = "tls")]
+	Tls(Box<TlsStream<TcpStream>>),
+	/// A Unix domain socket. Only available on Unix platforms.
+	#[cfg(unix)]
+	Unix(UnixStream),
+}
+
+impl From<TcpStream> for Socket {
+	fn from(stream: TcpStream) -> Self {
+		Self::Tcp(stream)
+	}
+}
+
+#[cfg(feature = "tls")]
+impl From<TlsStream<TcpStream>> for Socket {
+	fn from(stream: TlsStream<TcpStream>) -> Self {
+		Self::Tls(Box::new(stream))
+	}
+}
+
+#[cfg(unix)]
+impl From<UnixStream> for Socket {
+	fn from(stream: UnixStream) -> Self {
+		Self::Unix(stream)
+	}
+}
+
+/// Call `$method` on the stream inside a pinned [Socket].
+macro_rules! delegate {
+	($self:ident.$method:ident($($arg:expr),*)) => {
+		match $self.get_mut() {
+			Socket::Tcp(stream) => Pin::new(stream).$method($($arg),*),
+			#[cfg(feature = "tls")]
+			Socket::Tls(stream) => Pin::new(stream).$method($($arg),*),
+			#[cfg(unix)]
+			Socket::Unix(stream) => Pin::new(stream).$method($($arg),*),
+		}
+	};
+}
+
+impl AsyncRead for Socket {
+	fn poll_read(
+		self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+		buf: &mut ReadBuf<'_>,
+	) -> Poll<io::Result<()>> {
+		delegate!(self.poll_read(cx, buf))
+	}
+}
+
+impl AsyncWrite for Socket {
+	fn poll_write(
+		self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+		buf: &[u8],
+	) -> Poll<io::Result<usize>> {
+		delegate!(self.poll_write(cx, buf))
+	}
+
+	fn poll_write_vectored(
+		self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+		bufs: &[IoSlice<'_>],
+	) -> Poll<io::Result<usize>> {
+		delegate!(self.poll_write_vectored(cx, bufs))
+	}
+
+	fn is_write_vectored(&self) -> bool {
+		match self {
+			Socket::Tcp(stream) => stream.is_write_vectored(),
+			#[cfg(feature = "tls")]
+			Socket::Tls(stream) => stream.is_write_vectored(),
+			#[cfg(unix)]
+			Socket::Unix(stream) => stream.is_write_vectored(),
+		}
+	}
+
+	fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+		delegate!(self.poll_flush(cx))
+	}
+
+	fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+		delegate!(self.poll_shutdown(cx))
+	}
+}
@@ -0,0 +1,285 @@
+use std::{
+	fmt::{self, Debug, Formatter},
+	io,
+	sync::Arc,
+};
+
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_rustls::rustls::{
+	client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+	crypto::{ring, verify_tls12_signature, verify_tls13_signature, CryptoProvider},
+	pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime},
+	ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme,
+};
+
+pub use tokio_rustls::{client::TlsStream, rustls};
+
+/// Options for connecting to Redis over TLS. Only available when the `tls` feature is enabled.
+///
+/// Certificates and keys can be read from PEM files with
+/// [`PemObject`](rustls::pki_types::pem::PemObject).
+#[derive(Debug, Default)]
+pub struct TlsOptions {
+	/// The certificate authorities to trust. If empty, the Mozilla root certificates are trusted.
+	pub root_certs: Vec<CertificateDer<'static>>,
+	/// A certificate chain and its private key to authenticate with, for mutual TLS.
+	pub client_cert: Option<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>)>,
+	/// The name to send with SNI and to verify the server's certificate against, instead of the
+	/// host which is connected to.
+	pub server_name: Option<String>,
+	/// Accept any certificate from the server. This is insecure, so should only be used in
+	/// development.
+	pub insecure: bool,
+}
+
+impl TlsOptions {
+	/// Make a connector with these options.
+	pub fn connector(&self) -> Result<TlsConnector, rustls::Error> {
+		let provider = Arc::new(ring::default_provider());
+		let builder = ClientConfig::builder_with_provider(provider.clone())
+			.with_safe_default_protocol_versions()?;
+
+		let builder = if self.insecure {
+			builder
+				.dangerous()
+				.with_custom_certificate_verifier(Arc::new(NoVerifier(provider)))
+		} else {
+			let mut roots = RootCertStore::empty();
+			if self.root_certs.is_empty() {
+				roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+			} else {
+				for cert in &self.root_certs {
+					roots.add(cert.clone())?;
+				}
+			}
+
+			builder.with_root_certificates(roots)
+		};
+
+		let config = match &self.client_cert {
+			Some((chain, key)) => builder.with_client_auth_cert(chain.clone(), key.clone_key())?,
+			None => builder.with_no_client_auth(),
+		};
+
+		let server_name = self
+			.server_name
+			.as_ref()
+			.map(|name| ServerName::try_from(name.clone()))
+			.transpose()
+			.map_err(|err| rustls::Error::General(err.to_string()))?;
+
+		Ok(TlsConnector {
+			inner: Arc::new(config).into(),
+			server_name,
+		})
+	}
+}
+
+/// Starts TLS on streams to a Redis server, made from [`TlsOptions`]. Cloning the connector is
+/// cheap.
+#[derive(Clone)]
+pub struct TlsConnector {
+	inner: tokio_rustls::TlsConnector,
+	server_name: Option<ServerName<'static>>,
+}
+
+impl TlsConnector {
+	/// Start TLS over `stream`, verifying the server as `host` unless the options override the
+	/// server name.
+	pub async fn connect<T>(&self, host: &str, stream: T) -> io::Result<TlsStream<T>>
+	where
+		T: AsyncRead + AsyncWrite + Unpin,
+	{
+		let server_name = match &self.server_name {
+			Some(name) => name.clone(),
+			None => ServerName::try_from(host.to_owned())
+				.map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?,
+		};
+
+		self.inner.connect(server_name, stream).await
+	}
+}
+
+impl Debug for TlsConnector {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.debug_struct("TlsConnector")
+			.field("server_name", &self.server_name)
+			.finish_non_exhaustive()
+	}
+}
+
+/// Accepts any certificate, but still checks that the server has the certificate's key.
+#[derive(Debug)]
+struct NoVerifier(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for NoVerifier {
+	fn verify_server_cert(
+		&self,
+		_end_entity: &CertificateDer<'_>,
+		_intermediates: &[CertificateDer<'_>],
+		_server_name: &ServerName<'_>,
+		_ocsp_response: &[u8],
+		_now: UnixTime,
+	) -> Result<ServerCertVerified, rustls::Error> {
+		Ok(ServerCertVerified::assertion())
+	}
+
+	fn verify_tls12_signature(
+		&self,
+		message: &[u8],
+		cert: &CertificateDer<'_>,
+		dss: &DigitallySignedStruct,
+	) -> Result<HandshakeSignatureValid, rustls::Error> {
+		verify_tls12_signature(
+			message,
+			cert,
+			dss,
+			&self.0.signature_verification_algorithms,
+		)
+	}
+
+	fn verify_tls13_signature(
+		&self,
+		message: &[u8],
+		cert: &CertificateDer<'_>,
+		dss: &DigitallySignedStruct,
+	) -> Result<HandshakeSignatureValid, rustls::Error> {
+		verify_tls13_signature(
+			message,
+			cert,
+			dss,
+			&self.0.signature_verification_algorithms,
+		)
+	}
+
+	fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+		self.0.signature_verification_algorithms.supported_schemes()
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::{io, sync::Arc};
+
+	use rcgen::CertifiedKey;
+	use redust_resp::{CodecOptions, Data};
+	use tokio::io::{duplex, AsyncWriteExt};
+	use tokio_rustls::{
+		rustls::{
+			crypto::ring,
+			pki_types::{CertificateDer, PrivateKeyDer},
+			server::WebPkiClientVerifier,
+			RootCertStore, ServerConfig,
+		},
+		TlsAcceptor,
+	};
+
+	use crate::Connection;
+
+	use super::TlsOptions;
+
+	type Cert = (CertificateDer<'static>, PrivateKeyDer<'static>);
+
+	fn cert(name: &str) -> Cert {
+		let CertifiedKey { cert, key_pair } =
+			rcgen::generate_simple_self_signed([name.to_owned()]).unwrap();
+		(
+			cert.der().clone(),
+			PrivateKeyDer::Pkcs8(key_pair.serialize_der().into()),
+		)
+	}
+
+	/// Connect to a server with the `server` certificate as `host`, which requires a client
+	/// certificate signed by `client_ca` if there is one, and send a PING.
+	async fn ping(
+		server: &Cert,
+		client_ca: Option<&CertificateDer<'static>>,
+		options: TlsOptions,
+		host: &str,
+	) -> io::Result<()> {
+		let provider = Arc::new(ring::default_provider());
+		let builder = ServerConfig::builder_with_provider(provider.clone())
+			.with_safe_default_protocol_versions()
+			.unwrap();
+		let builder = match client_ca {
+			Some(ca) => {
+				let mut roots = RootCertStore::empty();
+				roots.add(ca.clone()).unwrap();
+				let verifier = WebPkiClientVerifier::builder_with_provider(roots.into(), provider)
+					.build()
+					.unwrap();
+				builder.with_client_cert_verifier(verifier)
+			}
+			None => builder.with_no_client_auth(),
+		};
+		let config = builder
+			.with_single_cert(vec![server.0.clone()], server.1.clone_key())
+			.unwrap();
+
+		let (client, server) = duplex(4096);
+		let connector = options.connector().unwrap();
+		let (server, client) = tokio::join!(
+			TlsAcceptor::from(Arc::new(config)).accept(server),
+			connector.connect(host, client)
+		);
+		let (mut server, client) = (server?, client?);
+
+		let mut conn = Connection::from_transport(client, CodecOptions::default());
+		server.write_all(b"+PONG\r\n").await?;
+		server.flush().await?;
+		let res = conn.cmd(["PING"]).await.map_err(io::Error::other)?;
+		assert_eq!(res, Data::simple_string("PONG"));
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_tls() {
+		let server = cert("localhost");
+		let trusted = || TlsOptions {
+			root_certs: vec![server.0.clone()],
+			..Default::default()
+		};
+
+		ping(&server, None, trusted(), "localhost").await.unwrap();
+		assert!(ping(&server, None, trusted(), "example.com").await.is_err());
+		assert!(ping(&server, None, TlsOptions::default(), "localhost")
+			.await
+			.is_err());
+
+		let options = TlsOptions {
+			server_name: Some("localhost".to_owned()),
+			..trusted()
+		};
+		ping(&server, None, options, "127.0.0.1").await.unwrap();
+
+		let options = TlsOptions {
+			insecure: true,
+			..Default::default()
+		};
+		ping(&server, None, options, "example.com").await.unwrap();
+	}
+
+	#[tokio::test]
+	async fn test_mutual_tls() {
+		let server = cert("localhost");
+		let client = cert("client");
+
+		let options = TlsOptions {
+			root_certs: vec![server.0.clone()],
+			client_cert: Some((vec![client.0.clone()], client.1.clone_key())),
+			..Default::default()
+		};
+		ping(&server, Some(&client.0), options, "localhost")
+			.await
+			.unwrap();
+
+		let options = TlsOptions {
+			root_certs: vec![server.0.clone()],
+			..Default::default()
+		};
+		assert!(ping(&server, Some(&client.0), options, "localhost")
+			.await
+			.is_err());
+	}
+}
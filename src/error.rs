@@ -0,0 +1,53 @@
+use std::{borrow::Cow, io, time::Duration};
+
+use redust_resp::RedisError;
+use thiserror::Error;
+
+use crate::resp;
+
+/// Errors returned from [`Connection`](crate::Connection) and the rest of the client.
+///
+/// Errors from [`resp`] are converted into the matching variant, or [`Error::Resp`] if there is
+/// none.
+#[derive(Debug, Error)]
+pub enum Error {
+	/// A client error, such as an invalid URL or an unexpected reply.
+	#[error("{0}")]
+	Message(Cow<'static, str>),
+	/// An IO error occured on the connection.
+	#[error("io error: {0}")]
+	Io(#[from] io::Error),
+	/// An error was returned by Redis.
+	#[error("Redis error: {0}")]
+	Redis(RedisError<'static>),
+	/// Any other error reading or writing RESP, such as invalid syntax or an exceeded limit.
+	#[error(transparent)]
+	Resp(resp::Error<'static>),
+	/// An operation didn't finish within its timeout.
+	#[error("timed out after {0:?}")]
+	Timeout(Duration),
+}
+
+impl Error {
+	/// Whether this error is transient (i.e. the connection is still valid)
+	pub fn is_transient(&self) -> bool {
+		matches!(self, Self::Redis(_))
+	}
+}
+
+impl From<resp::Error<'_>> for Error {
+	fn from(err: resp::Error<'_>) -> Self {
+		match err.into_owned() {
+			resp::Error::Message(msg) => Self::Message(msg),
+			resp::Error::Io(err) => Self::Io(err),
+			resp::Error::Redis(err) => Self::Redis(err),
+			err => Self::Resp(err),
+		}
+	}
+}
+
+impl From<RedisError<'_>> for Error {
+	fn from(err: RedisError<'_>) -> Self {
+		Self::Redis(err.into_owned())
+	}
+}
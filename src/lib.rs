@@ -21,12 +21,20 @@
 //! [serde] utilities for converting RESP into Rust structures. For reading data from a connection,
 //! use [`resp::from_data`].
 //!
+//! To avoid copying responses, read them as [`resp::Frame`]s with [`Connection::read_frame`] or by
+//! consuming the connection as a stream. Frames can be deserialized into types which borrow from
+//! them. Large bulk strings can be streamed as they arrive with [`Connection::read_streamed`], instead
+//! of being buffered.
+//!
 //! # Additional Features
 //!
 //! - [`command`]: type-safe Redis interactions
+//! - `futures-io`: connections over transports from other runtimes, such as smol or async-std,
+//!   with [`Connection::from_futures_io`]
 //! - [`pool`]: connection pooling with [bb8]
 //! - [`model`]: complex Redis responses, based on [serde]
 //! - [`script`]: Redis scripting utilities
+//! - `tls`: connections over TLS with [rustls], using [`TlsOptions`]
 
 /// [`Command`](crate::command::Command) trait + impelementations.
 ///
@@ -48,6 +56,8 @@
 #[cfg(feature = "command")]
 pub mod command;
 
+mod error;
+
 #[cfg(not(test))]
 mod connection;
 #[cfg(test)]
@@ -102,10 +112,14 @@ pub mod script;
 
 pub use redust_resp as resp;
 
-pub use connection::{Connection, SharedConnection};
-pub use resp::Codec;
+#[cfg(feature = "tls")]
+pub use connection::{rustls, TlsConnector, TlsOptions, TlsStream};
+pub use connection::{
+	BulkReader, Connection, ConnectionAddr, ConnectionBuilder, ConnectionInfo, InitOptions,
+	Protocol, ServerInfo, SharedConnection, Socket, StreamedResponse, Transport,
+};
+pub use error::Error;
+pub use resp::{Codec, CodecOptions, Frame};
 
-/// Static [`resp::Error`] returned from [`Connection`] and [`Codec`].
-pub type Error = resp::Error<'static>;
 /// Result with an error type defaulting to [`Error`].
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -93,10 +93,10 @@ impl<'a, 'de: 'a> de::Deserialize<'de> for Response<'a> {
 					.next_element::<Cow<Bytes>>()?
 					.ok_or_else(self.exp_len(0))?;
 
-				let bytes_str = from_utf8(&*bytes)
-					.map_err(|_| de::Error::invalid_value(Unexpected::Bytes(&*bytes), &self))?;
+				let bytes_str = from_utf8(&bytes)
+					.map_err(|_| de::Error::invalid_value(Unexpected::Bytes(&bytes), &self))?;
 
-				match &*bytes_str {
+				match bytes_str {
 					"subscribe" | "psubscribe" => Ok(Response::Subscribe(Subscription {
 						name: self.next_cow(&mut seq, 1)?,
 						count: seq.next_element()?.ok_or_else(self.exp_len(2))?,
@@ -147,6 +147,6 @@ mod test {
 				name: b"foo"[..].into(),
 			})
 		);
-		assert_eq!(rem, []);
+		assert_eq!(rem, b"");
 	}
 }
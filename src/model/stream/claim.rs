@@ -42,6 +42,6 @@ mod test {
 		entries.0.insert(Id(1234, 5678), entry);
 
 		assert_eq!(res, AutoclaimResponse(Id(0, 0), entries, Vec::new()));
-		assert_eq!(rem, []);
+		assert_eq!(rem, b"");
 	}
 }
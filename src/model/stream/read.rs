@@ -76,6 +76,6 @@ mod test {
 
 		let (resp, rem) = from_bytes::<Option<ReadResponse>>(bytes).expect("read data");
 		assert_eq!(resp, None);
-		assert_eq!(rem, []);
+		assert_eq!(rem, b"");
 	}
 }
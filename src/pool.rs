@@ -1,3 +1,5 @@
+#[cfg(unix)]
+use std::path::PathBuf;
 use std::{
 	fmt::Debug,
 	sync::atomic::{AtomicUsize, Ordering},
@@ -9,25 +11,84 @@ use redust_resp::Data;
 use tokio::net::ToSocketAddrs;
 use tracing::instrument;
 
-use crate::{connection::Connection, Error};
+#[cfg(feature = "tls")]
+use crate::connection::{ConnectionAddr, TlsConnector};
+use crate::{
+	connection::{Connection, ConnectionBuilder, ConnectionInfo, Socket},
+	Error, Result,
+};
 
 pub use deadpool;
 
 /// A deadpool [`Manager`](managed::Manager) for a Redis [`Connection`].
+///
+/// Connections are over a [`Socket`], so that pools of TLS connections have the same type. The
+/// address can also be a [`ConnectionInfo`], made with [`from_url`](Manager::from_url) or
+/// [`from_info`](Manager::from_info).
+///
+/// Each connection is made with a [`ConnectionBuilder`], so is set up with its
+/// [`InitOptions`](crate::InitOptions), including connections which replace dead ones.
 #[derive(Debug)]
 pub struct Manager<A> {
 	addr: A,
+	builder: ConnectionBuilder,
 	ping_number: AtomicUsize,
 }
 
-impl<A> Manager<A> {
+impl<A> Manager<A>
+where
+	A: ToSocketAddrs,
+{
 	/// Make a new manager.
 	pub fn new(addr: A) -> Self {
+		Self::with_builder(addr, ConnectionBuilder::new())
+	}
+
+	/// Make a new manager which makes connections with `builder`.
+	pub fn with_builder(addr: A, builder: ConnectionBuilder) -> Self {
 		Self {
 			addr,
+			builder,
 			ping_number: AtomicUsize::new(0),
 		}
 	}
+
+	/// Make a new manager which connects over TLS, verifying the server as `host`. Only available
+	/// when the `tls` feature is enabled.
+	#[cfg(feature = "tls")]
+	pub fn new_tls(addr: A, host: impl Into<String>, tls: TlsConnector) -> Self {
+		Self::with_builder(addr, ConnectionBuilder::new().tls(host, tls))
+	}
+}
+
+impl Manager<ConnectionInfo> {
+	/// Make a new manager which connects to the server at a URL. See [`ConnectionInfo`] for the
+	/// URLs which are supported.
+	pub fn from_url(url: &str) -> Result<Self> {
+		Self::from_info(ConnectionInfo::parse(url)?, ConnectionBuilder::new())
+	}
+
+	/// Make a new manager which connects to the server described by `info` with `builder`. The
+	/// [`init`](ConnectionInfo::init) commands from `info` are run instead of the builder's.
+	///
+	/// For TLS, the connector is built here and shared by every connection, unless the builder
+	/// already has one. See [`ConnectionBuilder::connect_info`].
+	pub fn from_info(info: ConnectionInfo, builder: ConnectionBuilder) -> Result<Self> {
+		#[cfg(feature = "tls")]
+		let builder = match &info.addr {
+			ConnectionAddr::Tls { host, insecure, .. } => {
+				let tls = builder.tls_connector(*insecure)?;
+				builder.tls(host.clone(), tls)
+			}
+			_ => builder,
+		};
+
+		Ok(Self {
+			addr: info,
+			builder,
+			ping_number: AtomicUsize::new(0),
+		})
+	}
 }
 
 #[async_trait]
@@ -35,29 +96,94 @@ impl<A> managed::Manager for Manager<A>
 where
 	A: ToSocketAddrs + Clone + Send + Sync + Debug,
 {
-	type Type = Connection;
+	type Type = Connection<Socket>;
 	type Error = Error;
 
 	#[instrument(level = "trace")]
 	async fn create(&self) -> Result<Self::Type, Self::Error> {
-		Ok(Connection::new(self.addr.clone()).await?)
+		self.builder.connect(self.addr.clone()).await
 	}
 
 	#[instrument(level = "trace")]
 	async fn recycle(&self, conn: &mut Self::Type) -> RecycleResult<Self::Error> {
-		if conn.is_dead() {
-			return Err(RecycleError::StaticMessage("connection is dead"));
-		}
+		recycle(conn, &self.ping_number).await
+	}
+}
+
+#[async_trait]
+impl managed::Manager for Manager<ConnectionInfo> {
+	type Type = Connection<Socket>;
+	type Error = Error;
+
+	#[instrument(level = "trace")]
+	async fn create(&self) -> Result<Self::Type, Self::Error> {
+		self.builder.connect_info(&self.addr).await
+	}
+
+	#[instrument(level = "trace")]
+	async fn recycle(&self, conn: &mut Self::Type) -> RecycleResult<Self::Error> {
+		recycle(conn, &self.ping_number).await
+	}
+}
+
+/// A deadpool [`Manager`](managed::Manager) for a Redis [`Connection`] over a Unix socket. Only
+/// available on Unix platforms.
+#[cfg(unix)]
+#[derive(Debug)]
+pub struct UnixManager {
+	path: PathBuf,
+	builder: ConnectionBuilder,
+	ping_number: AtomicUsize,
+}
 
-		let ping_number = self.ping_number.fetch_add(1, Ordering::Relaxed).to_string();
-		if conn.cmd(["PING", &ping_number]).await? == Data::bulk_string(ping_number.as_bytes()) {
-			Ok(())
-		} else {
-			Err(RecycleError::StaticMessage("invalid PING response"))
+#[cfg(unix)]
+impl UnixManager {
+	/// Make a new manager which connects to the socket at `path`.
+	pub fn new(path: impl Into<PathBuf>) -> Self {
+		Self::with_builder(path, ConnectionBuilder::new())
+	}
+
+	/// Make a new manager which connects to the socket at `path` with `builder`.
+	pub fn with_builder(path: impl Into<PathBuf>, builder: ConnectionBuilder) -> Self {
+		Self {
+			path: path.into(),
+			builder,
+			ping_number: AtomicUsize::new(0),
 		}
 	}
 }
 
+#[cfg(unix)]
+#[async_trait]
+impl managed::Manager for UnixManager {
+	type Type = Connection<Socket>;
+	type Error = Error;
+
+	#[instrument(level = "trace")]
+	async fn create(&self) -> Result<Self::Type, Self::Error> {
+		self.builder.connect_unix(&self.path).await
+	}
+
+	#[instrument(level = "trace")]
+	async fn recycle(&self, conn: &mut Self::Type) -> RecycleResult<Self::Error> {
+		recycle(conn, &self.ping_number).await
+	}
+}
+
+/// Check that `conn` is alive by sending a PING with the next `ping_number`.
+async fn recycle(conn: &mut Connection<Socket>, ping_number: &AtomicUsize) -> RecycleResult<Error> {
+	if conn.is_dead() {
+		return Err(RecycleError::StaticMessage("connection is dead"));
+	}
+
+	let ping_number = ping_number.fetch_add(1, Ordering::Relaxed).to_string();
+	if conn.cmd(["PING", &ping_number]).await? == Data::bulk_string(ping_number.as_bytes()) {
+		Ok(())
+	} else {
+		Err(RecycleError::StaticMessage("invalid PING response"))
+	}
+}
+
 pub type Pool<A> = managed::Pool<Manager<A>>;
 pub type PoolBuilder<A> = managed::PoolBuilder<Manager<A>>;
 pub type BuildError = managed::BuildError<Error>;
@@ -66,3 +192,56 @@ pub type Object<A> = managed::Object<Manager<A>>;
 pub type Hook<A> = managed::Hook<Manager<A>>;
 pub type HookError = managed::HookError<Error>;
 pub type HookErrorCause = managed::HookErrorCause<Error>;
+
+#[cfg(unix)]
+pub type UnixPool = managed::Pool<UnixManager>;
+#[cfg(unix)]
+pub type UnixPoolBuilder = managed::PoolBuilder<UnixManager>;
+#[cfg(unix)]
+pub type UnixObject = managed::Object<UnixManager>;
+
+#[cfg(all(test, unix))]
+mod test {
+	use redust_resp::Data;
+	use tokio::{
+		io::{AsyncReadExt, AsyncWriteExt},
+		net::UnixListener,
+	};
+
+	use crate::{ConnectionBuilder, InitOptions};
+
+	use super::{UnixManager, UnixPool};
+
+	#[tokio::test]
+	async fn test_unix_manager() {
+		let path = std::env::temp_dir().join(format!("redust-pool-{}.sock", std::process::id()));
+		let _ = std::fs::remove_file(&path);
+		let listener = UnixListener::bind(&path).unwrap();
+
+		let init = InitOptions {
+			db: 1,
+			..Default::default()
+		};
+		let manager = UnixManager::with_builder(&path, ConnectionBuilder::new().init(init));
+		assert!(format!("{:?}", manager).contains(&*path.to_string_lossy()));
+
+		tokio::spawn(async move {
+			let (mut server, _) = listener.accept().await.unwrap();
+			let mut select = [0; 23];
+			server.read_exact(&mut select).await.unwrap();
+			assert_eq!(&select, b"*2\r\n$6\r\nSELECT\r\n$1\r\n1\r\n");
+			server.write_all(b"+OK\r\n").await.unwrap();
+
+			server.read_exact(&mut [0; 14]).await.unwrap();
+			server.write_all(b"+PONG\r\n").await.unwrap();
+		});
+
+		let pool = UnixPool::builder(manager).build().unwrap();
+		let mut conn = pool.get().await.unwrap();
+		assert_eq!(
+			conn.cmd(["PING"]).await.unwrap(),
+			Data::simple_string("PONG")
+		);
+		std::fs::remove_file(&path).unwrap();
+	}
+}
@@ -4,7 +4,9 @@ use bytes::{Bytes, BytesMut};
 use redust_resp::{from_data, Data};
 use tracing::instrument;
 
-use crate::{Connection, Result};
+use tokio::net::TcpStream;
+
+use crate::{Connection, Result, Transport};
 
 /// A Redis script.
 ///
@@ -33,10 +35,10 @@ impl Script {
 	}
 
 	/// Create an [`Invocation`] for this script using the given connection.
-	pub fn exec<'script, 'conn>(
+	pub fn exec<'script, 'conn, T>(
 		&'script self,
-		connection: &'conn mut Connection,
-	) -> Invocation<'script, 'conn, '_> {
+		connection: &'conn mut Connection<T>,
+	) -> Invocation<'script, 'conn, 'script, T> {
 		Invocation {
 			connection,
 			script: self,
@@ -59,7 +61,10 @@ impl Script {
 	/// Load this script into Redis. Once loaded, the SHA1 hash is stored and can be used by future
 	/// invocations to reduce network traffic and improve performance.
 	#[instrument(level = "debug")]
-	pub async fn load(&self, connection: &mut Connection) -> Result<Bytes> {
+	pub async fn load<T>(&self, connection: &mut Connection<T>) -> Result<Bytes>
+	where
+		T: Transport,
+	{
 		let res = connection
 			.cmd([b"script".as_slice(), b"load", &*self.contents])
 			.await?;
@@ -68,13 +73,16 @@ impl Script {
 			.into_iter()
 			.collect();
 
-		self.set_hash(&*hash);
+		self.set_hash(&hash);
 		Ok(hash.freeze())
 	}
 
 	/// Get the SHA1 hash of this script, loading it if necessary.
 	#[instrument(level = "trace")]
-	pub async fn get_hash(&self, connection: &mut Connection) -> Result<Bytes> {
+	pub async fn get_hash<T>(&self, connection: &mut Connection<T>) -> Result<Bytes>
+	where
+		T: Transport,
+	{
 		let hash = self.hash.read().unwrap().clone();
 
 		if hash.is_empty() {
@@ -89,14 +97,17 @@ impl Script {
 ///
 /// Set keys and arguments using [`Invocation::keys`] and [`Invocation::args`].
 #[derive(Debug)]
-pub struct Invocation<'script, 'conn, 'data> {
-	connection: &'conn mut Connection,
+pub struct Invocation<'script, 'conn, 'data, T = TcpStream> {
+	connection: &'conn mut Connection<T>,
 	script: &'script Script,
 	args: Vec<&'data [u8]>,
 	keys: Vec<&'data [u8]>,
 }
 
-impl<'data> Invocation<'_, '_, 'data> {
+impl<'data, T> Invocation<'_, '_, 'data, T>
+where
+	T: Transport,
+{
 	/// Set the arguments to be passed to this script.
 	pub fn args<I, B>(mut self, args: I) -> Self
 	where
@@ -0,0 +1,76 @@
+//! Tests against a Redis server with TLS, such as one started with:
+//!
+//! ```sh
+//! redis-server --port 0 --tls-port 6380 --tls-cert-file redis.crt --tls-key-file redis.key \
+//!     --tls-ca-cert-file ca.crt
+//! ```
+//!
+//! The tests are skipped unless `REDIS_TLS_ADDR` is set. `REDIS_TLS_CA` is the CA certificate
+//! to trust, and `REDIS_TLS_CERT` and `REDIS_TLS_KEY` are the client certificate and key, which
+//! Redis requires by default.
+
+use std::env::var;
+
+use redust::{
+	pool::{Manager, Pool},
+	rustls::pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer},
+	Connection, Result, TlsConnector, TlsOptions,
+};
+use test_log::test;
+
+/// The address of the server and a connector for it, if there is a server to test against.
+fn tls() -> Option<(String, TlsConnector)> {
+	let addr = var("REDIS_TLS_ADDR").ok()?;
+
+	let mut options = TlsOptions::default();
+	if let Ok(path) = var("REDIS_TLS_CA") {
+		options.root_certs = CertificateDer::pem_file_iter(path)
+			.expect("CA file should be readable")
+			.collect::<Result<_, _>>()
+			.expect("CA file should contain certificates");
+	}
+
+	if let (Ok(cert), Ok(key)) = (var("REDIS_TLS_CERT"), var("REDIS_TLS_KEY")) {
+		let chain = CertificateDer::pem_file_iter(cert)
+			.expect("certificate file should be readable")
+			.collect::<Result<_, _>>()
+			.expect("certificate file should contain certificates");
+		let key = PrivateKeyDer::from_pem_file(key).expect("key file should contain a key");
+		options.client_cert = Some((chain, key));
+	}
+
+	Some((addr, options.connector().expect("options should be valid")))
+}
+
+fn host() -> String {
+	var("REDIS_TLS_HOST").unwrap_or_else(|_| "localhost".to_owned())
+}
+
+#[test(tokio::test)]
+async fn ping() -> Result<()> {
+	let Some((addr, tls)) = tls() else {
+		return Ok(());
+	};
+
+	let mut conn = Connection::new_tls(addr, &host(), &tls).await?;
+	let res = conn.cmd(["PING"]).await?;
+	assert_eq!(res, "PONG");
+
+	Ok(())
+}
+
+#[test(tokio::test)]
+async fn pool() -> Result<()> {
+	let Some((addr, tls)) = tls() else {
+		return Ok(());
+	};
+
+	let manager = Manager::new_tls(addr, host(), tls);
+	let pool = Pool::builder(manager).build().unwrap();
+
+	let mut conn = pool.get().await.unwrap();
+	let res = conn.cmd(["PING"]).await?;
+	assert_eq!(res, "PONG");
+
+	Ok(())
+}